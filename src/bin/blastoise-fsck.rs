@@ -0,0 +1,17 @@
+use std::env;
+use std::process::exit;
+extern crate blastoise;
+
+
+fn main() {
+    // the catalog is in the data directory unless the meta directory is given
+    let args : Vec<String> = env::args().collect();
+    if args.len() < 2 || args.len() > 3 {
+        println!("usage: {} <table_file_dir> [table_meta_dir]", args[0]);
+        exit(2);
+    }
+    let table_meta_dir = args.get(2).unwrap_or(&args[1]);
+    if !blastoise::run_fsck(table_meta_dir, &args[1]) {
+        exit(1);
+    }
+}
@@ -0,0 +1,103 @@
+use std::boxed::Box;
+use std::option::Option;
+use ::store::table::TableManagerRef;
+use ::store::tuple::{TupleData, TupleValue};
+use ::parser::AlterStatement;
+use ::parser::alter::AlterAction;
+use ::parser::common::{ValueExpr, ValueType};
+use ::utils::decimal::format_decimal;
+use ::utils::datetime::{format_date, format_time, format_timestamp};
+use super::iter::{ExecIter, ExecIterRef};
+use super::error::ExecError;
+
+
+#[derive(Debug)]
+pub struct AlterTable {
+    stmt : AlterStatement,
+    finished : bool,
+    error : Option<ExecError>,
+    table_manager : TableManagerRef,
+}
+
+impl AlterTable {
+    pub fn new(stmt : AlterStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+        Box::new(AlterTable{
+            finished : false,
+            error : None,
+            stmt : stmt,
+            table_manager : table_manager.clone(),
+        })
+    }
+    fn alter(&self) -> Result<(), String> {
+        let mut manager = self.table_manager.write().unwrap();
+        let table_name = &self.stmt.table;
+        let mut new_table = manager.get_table(table_name).unwrap().read().unwrap().clone();
+        match &self.stmt.action {
+            &AlterAction::AddColumn{ref decl, ref default} => {
+                new_table.attr_list.push(decl.gen_attr());
+                manager.alter_table(table_name, new_table, |values| {
+                    let mut value_list : Vec<ValueExpr> = values.into_iter().map(to_value_expr).collect();
+                    value_list.push(match default {
+                        &Some(ref value) => ValueExpr{ value : value.value.clone(), value_type : value.value_type },
+                        &None => ValueExpr{ value : "null".to_string(), value_type : ValueType::Null },
+                    });
+                    value_list
+                })
+            }
+            &AlterAction::DropColumn{ref attr} => {
+                let i = new_table.attr_list.iter().position(|a| a.name == *attr).unwrap();
+                new_table.attr_list.remove(i);
+                new_table.index_list.retain(|index| index.attr != *attr);
+                manager.alter_table(table_name, new_table, |mut values| {
+                    values.remove(i);
+                    values.into_iter().map(to_value_expr).collect()
+                })
+            }
+            &AlterAction::RenameColumn{ref attr, ref new_name} => {
+                manager.rename_attr(table_name, attr, new_name);
+                Ok(())
+            }
+            &AlterAction::RenameTable{ref new_name} => {
+                manager.rename_table(table_name, new_name);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn to_value_expr(value : TupleValue) -> ValueExpr {
+    match value {
+        TupleValue::Int(n) => ValueExpr{ value : n.to_string(), value_type : ValueType::Integer },
+        TupleValue::Float(n) => ValueExpr{ value : format!("{}", n), value_type : ValueType::Float },
+        TupleValue::BigInt(n) => ValueExpr{ value : n.to_string(), value_type : ValueType::Integer },
+        TupleValue::Double(n) => ValueExpr{ value : format!("{}", n), value_type : ValueType::Float },
+        TupleValue::Decimal(n, scale) => ValueExpr{ value : format_decimal(n, scale), value_type : ValueType::Float },
+        TupleValue::Boolean(b) => ValueExpr{ value : b.to_string(), value_type : ValueType::Boolean },
+        TupleValue::Date(n) => ValueExpr{ value : format_date(n), value_type : ValueType::Date },
+        TupleValue::Time(n) => ValueExpr{ value : format_time(n), value_type : ValueType::Time },
+        TupleValue::Timestamp(n) => ValueExpr{ value : format_timestamp(n), value_type : ValueType::Timestamp },
+        TupleValue::Char(s) => ValueExpr{ value : s, value_type : ValueType::String },
+        TupleValue::Bytes(b) => ValueExpr{ value : String::from_utf8_lossy(&b).into_owned(), value_type : ValueType::String },
+        TupleValue::Null => ValueExpr{ value : "null".to_string(), value_type : ValueType::Null },
+        TupleValue::Overflow{..} => unreachable!(),  // loaded by copy_tuples
+    }
+}
+
+impl ExecIter for AlterTable {
+    fn open(&mut self) {}
+    fn close(&mut self) { self.finished = true; }
+    fn explain(&self) -> String {
+        format!("{}", self.stmt)
+    }
+    fn get_next(&mut self) -> Option<TupleData> {
+        if self.finished {
+            return None;
+        }
+        if let Err(err) = self.alter() {
+            self.error = Some(ExecError::page_corrupted(err));
+        }
+        self.finished = true;
+        None
+    }
+    fn get_error(&self) -> Option<ExecError> { self.error.clone() }
+}
@@ -1,10 +1,35 @@
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub enum ExecErrorType {
-    PrimaryKeyExist,
-}
-
-#[derive(Debug, Clone)]
-pub struct ExecError {
-    pub error_type : ExecErrorType,
-    pub error_msg : String,
-}
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ExecErrorType {
+    PrimaryKeyExist,
+    UniqueKeyExist,
+    PageCorrupted,
+    TypeMismatch,
+    BufferPoolFull,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecError {
+    pub error_type : ExecErrorType,
+    pub error_msg : String,
+}
+
+impl ExecError {
+    pub fn page_corrupted(error_msg : String) -> ExecError {
+        ExecError{
+            error_type : ExecErrorType::PageCorrupted,
+            error_msg : error_msg,
+        }
+    }
+    pub fn type_mismatch(error_msg : String) -> ExecError {
+        ExecError{
+            error_type : ExecErrorType::TypeMismatch,
+            error_msg : error_msg,
+        }
+    }
+    pub fn buffer_pool_full(error_msg : String) -> ExecError {
+        ExecError{
+            error_type : ExecErrorType::BufferPoolFull,
+            error_msg : error_msg,
+        }
+    }
+}
@@ -1,149 +1,392 @@
-use std::ptr::read;
-use std::collections::HashMap;
-use ::parser::condition::{
-    ConditionExpr,
-    ArithExpr,
-    ArithOp,
-    CmpOperantExpr,
-    CmpOp,
-    CondRef,
-    LogicOp,
-};
-use ::parser::common::{ValueExpr, ValueType};
-use ::parser::attribute::AttributeExpr;
-use ::store::buffer::DataPtr;
-use ::store::table::AttrType;
-use ::utils::pointer::read_string;
-
-
-pub type PtrMap = HashMap<(String, String), (DataPtr, AttrType)>;
-
-
-pub fn eval_cond(condition : &ConditionExpr, ptr_map : &PtrMap) -> bool {
-    match condition {
-        &ConditionExpr::NotExpr{ ref operant } => !eval_cond(operant, ptr_map),
-        &ConditionExpr::CmpExpr{ ref lhs, ref rhs, op } =>
-            eval_cmp_operant(lhs, rhs, op, ptr_map),
-        &ConditionExpr::LogicExpr{ ref lhs, ref rhs, op } =>
-            eval_logic_op(lhs, rhs, op, ptr_map),
-    }
-}
-
-pub fn eval_logic_op(lhs : &CondRef, rhs : &CondRef, op : LogicOp, ptr_map : &PtrMap) -> bool {
-    let lresult = eval_cond(&**lhs, ptr_map);
-    let rresult = eval_cond(&**rhs, ptr_map);
-    match op {
-        LogicOp::Or => lresult || rresult,
-        LogicOp::And => lresult && rresult,
-    }
-}
-
-pub fn eval_cmp_operant(
-        lhs : &CmpOperantExpr,
-        rhs : &CmpOperantExpr,
-        op : CmpOp,
-        ptr_map : &PtrMap) -> bool {
-    match (lhs, rhs) {
-        (&CmpOperantExpr::Value(ref l), &CmpOperantExpr::Value(ref r)) => {
-            let lvalue = eval_str(l);
-            let rvalue = eval_str(r);
-            eval_str_cmp(&lvalue, &rvalue, op)
-        }
-        (&CmpOperantExpr::Value(ref l), &CmpOperantExpr::Arith(ref r)) => {
-            let lvalue = eval_str(l);
-            let rvalue = eval_str_attr(r, ptr_map);
-            eval_str_cmp(&lvalue, &rvalue, op)
-        }
-        (&CmpOperantExpr::Arith(ref l), &CmpOperantExpr::Value(ref r)) => {
-            let lvalue = eval_str_attr(l, ptr_map);
-            let rvalue = eval_str(r);
-            eval_str_cmp(&lvalue, &rvalue, op)
-        }
-        (&CmpOperantExpr::Arith(ref l), &CmpOperantExpr::Arith(ref r)) => {
-            let lvalue = eval_arith(l, ptr_map);
-            let rvalue = eval_arith(r, ptr_map);
-            match op {
-                CmpOp::LT => lvalue < rvalue,
-                CmpOp::GT => lvalue > rvalue,
-                CmpOp::LE => lvalue <= rvalue,
-                CmpOp::GE => lvalue >= rvalue,
-                CmpOp::EQ => lvalue == rvalue,
-                CmpOp::NE => lvalue != rvalue,
-                CmpOp::Is => unimplemented!(),
-                CmpOp::IsNot => unimplemented!(),
-            }
-        }
-    }
-}
-
-pub fn eval_str_cmp(lvalue : &String, rvalue : &String, op : CmpOp) -> bool {
-    match op {
-        CmpOp::LT | CmpOp::GT| CmpOp::LE| CmpOp::GE =>
-            panic!("invalid operationo for string"),
-        CmpOp::EQ => lvalue == rvalue,
-        CmpOp::NE => lvalue != rvalue,
-        CmpOp::Is => unimplemented!(),
-        CmpOp::IsNot => unimplemented!(),
-    }
-}
-
-pub fn eval_str_attr(expr : &ArithExpr, ptr_map : &PtrMap) -> String {
-    match expr {
-        &ArithExpr::Attr( ref attr_expr ) => {
-            let (table, attr) = match attr_expr {
-                &AttributeExpr::TableAttr{ref table, ref attr} => (table.clone(), attr.clone()),
-                &AttributeExpr::AggreFuncCall{ref table, ref attr, ..} => (table.clone(), attr.clone()),
-            };
-            assert!(table.is_some());
-            let (p, t) = ptr_map.get(&(table.unwrap(), attr)).unwrap().clone();
-            let len = extract!(t, AttrType::Char{len}, len);
-            unsafe{ read_string(p, len) }
-        }
-        _ => panic!("expected attribute, found {:?}", expr),
-    }
-}
-
-pub fn eval_arith(expr : &ArithExpr, ptr_map : &PtrMap) -> f32 {
-    match expr {
-        &ArithExpr::BinaryExpr{ ref lhs, ref rhs, op } => {
-            let l = eval_arith(lhs, ptr_map);
-            let r = eval_arith(rhs, ptr_map);
-            match op {
-                ArithOp::Add => l + r,
-                ArithOp::Sub => l - r,
-                ArithOp::Mul => l * r,
-                ArithOp::Div => l / r,
-                ArithOp::Mod => l % r,
-            }
-        }
-        &ArithExpr::MinusExpr{ ref operant } => - eval_arith(operant, ptr_map),
-        &ArithExpr::Value(ref v) => eval_num(v),
-        &ArithExpr::Attr( ref attr_expr ) => {
-            let (table, attr) = match attr_expr {
-                &AttributeExpr::TableAttr{ref table, ref attr} => (table.clone(), attr.clone()),
-                &AttributeExpr::AggreFuncCall{ref table, ref attr, ..} => (table.clone(), attr.clone()),
-            };
-            let (p, t) = ptr_map.get(&(table.unwrap(), attr)).unwrap().clone();
-            match t {
-                AttrType::Int => unsafe{ read::<i32>(p as *const i32) as f32 },
-                AttrType::Float => unsafe{ read::<f32>(p as *const f32) },
-                _ => panic!("invalid type {:?}", t),
-            }
-        }
-    }
-}
-
-pub fn eval_num(expr : &ValueExpr) -> f32 {
-    match expr.value_type {
-        ValueType::Integer => expr.value.parse::<i32>().unwrap() as f32,
-        ValueType::Float => expr.value.parse::<f32>().unwrap(),
-        t => panic!("invalid type {:?}", t),
-    }
-}
-
-pub fn eval_str(expr : &ValueExpr) -> String {
-    match expr.value_type {
-        ValueType::String => expr.value.clone(),
-        t => panic!("invalid type {:?}", t),
-    }
-}
+use std::collections::HashMap;
+use std::cmp::{Ordering, min, max};
+use ::parser::condition::{
+    ConditionExpr,
+    ArithExpr,
+    ArithOp,
+    CmpOperantExpr,
+    CmpOp,
+    CondRef,
+    LogicOp,
+};
+use ::parser::common::{ValueExpr, ValueType};
+use ::parser::attribute::AttributeExpr;
+use ::store::buffer::DataPtr;
+use ::store::table::AttrType;
+use ::store::tuple::{TupleValue, gen_tuple_value, parse_tuple_value};
+use ::utils::decimal::{parse_decimal, MAX_DECIMAL_PRECISION};
+use ::utils::pointer::{read_int, read_float, read_bigint, read_double};
+use super::error::ExecError;
+
+
+pub type PtrMap = HashMap<(String, String), (DataPtr, AttrType)>;
+// the operants of different types are rejected by sem_check, but fail the statement instead of
+// the worker if they reach here
+pub type EvalResult<T> = Result<T, ExecError>;
+
+
+// Integers and decimals are computed exactly, and only become floating point numbers
+// when mixed with float or double, or when they overflow.
+// Float stays single precision so that `float_attr = 1.1` still holds.
+// the results keep at most MAX_SCALE fractional digits
+const MAX_SCALE : u32 = MAX_DECIMAL_PRECISION as u32;
+// the quotient of exact numbers has at least QUOTIENT_SCALE fractional digits
+const QUOTIENT_SCALE : u32 = 6;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Num {
+    Int(i64),
+    Decimal(i128, u32),  // the value multiplied by 10^scale, and the scale
+    Float(f32),
+    Double(f64),
+}
+
+impl Num {
+    fn rank(&self) -> usize {
+        match self {
+            &Num::Int(..) => 0,
+            &Num::Decimal(..) => 1,
+            &Num::Float(..) => 2,
+            &Num::Double(..) => 3,
+        }
+    }
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            &Num::Int(n) => n as f64,
+            &Num::Decimal(n, scale) => n as f64 / 10f64.powi(scale as i32),
+            &Num::Float(n) => n as f64,
+            &Num::Double(n) => n,
+        }
+    }
+    fn to_decimal(&self, scale : u32) -> Option<i128> {
+        // return None if the result overflows, a smaller scale is rounded
+        match self {
+            &Num::Int(n) => 10i128.checked_pow(scale).and_then(|p| (n as i128).checked_mul(p)),
+            &Num::Decimal(n, s) if scale >= s => 10i128.checked_pow(scale - s).and_then(|p| n.checked_mul(p)),
+            &Num::Decimal(n, s) => Some(10i128.checked_pow(s - scale).map_or(0, |p| div_round(n, p))),
+            _ => None,
+        }
+    }
+    fn capped_decimal(n : i128, scale : u32) -> Option<Num> {
+        if scale <= MAX_SCALE {
+            return Some(Num::Decimal(n, scale));
+        }
+        Num::Decimal(n, scale).to_decimal(MAX_SCALE).map(|n| Num::Decimal(n, MAX_SCALE))
+    }
+    fn unify(l : Num, r : Num) -> (Num, Num) {
+        // convert both to the wider type, exact numbers too large become double
+        let rank = if l.rank() > r.rank() { l.rank() } else { r.rank() };
+        let scale = |n : &Num| if let &Num::Decimal(_, s) = n { s } else { 0 };
+        let to_rank = |n : Num, rank| match (n, rank) {
+            (Num::Int(..), 0) => Some(n),
+            (Num::Int(..), 1) | (Num::Decimal(..), 1) => {
+                let s = if scale(&l) > scale(&r) { scale(&l) } else { scale(&r) };
+                n.to_decimal(s).map(|d| Num::Decimal(d, s))
+            }
+            (_, 2) => Some(Num::Float(n.to_f64() as f32)),
+            _ => Some(Num::Double(n.to_f64())),
+        };
+        match (to_rank(l, rank), to_rank(r, rank)) {
+            (Some(l), Some(r)) => (l, r),
+            _ => (Num::Double(l.to_f64()), Num::Double(r.to_f64())),
+        }
+    }
+    pub fn compare(l : Num, r : Num) -> Option<Ordering> {
+        match Num::unify(l, r) {
+            (Num::Int(l), Num::Int(r)) => Some(l.cmp(&r)),
+            (Num::Decimal(l, _), Num::Decimal(r, _)) => Some(l.cmp(&r)),
+            (Num::Float(l), Num::Float(r)) => l.partial_cmp(&r),
+            (l, r) => l.to_f64().partial_cmp(&r.to_f64()),
+        }
+    }
+    pub fn apply(op : ArithOp, l : Num, r : Num) -> Option<Num> {
+        // return None when an integer or decimal is divided by zero
+        if op == ArithOp::Div && l.rank() <= 1 && r.rank() <= 1 {
+            if r.to_f64() == 0.0 {
+                return None;
+            }
+            // both have the same scale after unify
+            let (dividend, divisor, scale) = match Num::unify(l, r) {
+                (Num::Int(l), Num::Int(r)) => (l as i128, r as i128, 0),
+                (Num::Decimal(l, s), Num::Decimal(r, _)) => (l, r, s),
+                (l, r) => return Some(Num::Double(l.to_f64() / r.to_f64())),
+            };
+            let scale = min(max(scale, QUOTIENT_SCALE), MAX_SCALE);
+            let quotient = 10i128.checked_pow(scale).and_then(|p| dividend.checked_mul(p))
+                .map(|n| Num::Decimal(div_round(n, divisor), scale));
+            return Some(quotient.unwrap_or_else(|| Num::Double(l.to_f64() / r.to_f64())));
+        }
+        let exact = match Num::unify(l, r) {
+            (Num::Int(l), Num::Int(r)) => match op {
+                ArithOp::Add => l.checked_add(r),
+                ArithOp::Sub => l.checked_sub(r),
+                ArithOp::Mul => l.checked_mul(r),
+                ArithOp::Mod if r == 0 => return None,
+                ArithOp::Mod => l.checked_rem(r),
+                ArithOp::Div => unreachable!(),
+            }.map(Num::Int),
+            (Num::Decimal(l, s), Num::Decimal(r, _)) => match op {
+                ArithOp::Add => l.checked_add(r).map(|n| Num::Decimal(n, s)),
+                ArithOp::Sub => l.checked_sub(r).map(|n| Num::Decimal(n, s)),
+                ArithOp::Mul => l.checked_mul(r).and_then(|n| Num::capped_decimal(n, s * 2)),
+                ArithOp::Mod if r == 0 => return None,
+                ArithOp::Mod => l.checked_rem(r).map(|n| Num::Decimal(n, s)),
+                ArithOp::Div => unreachable!(),
+            },
+            (Num::Float(l), Num::Float(r)) => Some(Num::Float(apply_float(op, l, r))),
+            (l, r) => Some(Num::Double(apply_float(op, l.to_f64(), r.to_f64()))),
+        };
+        // an overflowed integer or decimal is computed again in double
+        Some(exact.unwrap_or_else(|| Num::Double(apply_float(op, l.to_f64(), r.to_f64()))))
+    }
+}
+
+fn div_round(n : i128, d : i128) -> i128 {
+    // rounded half away from zero like parse_decimal
+    let (q, r) = (n / d, n % d);
+    if r.unsigned_abs() >= d.unsigned_abs() - r.unsigned_abs() {
+        if (n < 0) == (d < 0) { q + 1 } else { q - 1 }
+    } else {
+        q
+    }
+}
+
+fn apply_float<T>(op : ArithOp, l : T, r : T) -> T
+        where T : ::std::ops::Add<Output=T> + ::std::ops::Sub<Output=T> + ::std::ops::Mul<Output=T>
+            + ::std::ops::Div<Output=T> + ::std::ops::Rem<Output=T> {
+    match op {
+        ArithOp::Add => l + r,
+        ArithOp::Sub => l - r,
+        ArithOp::Mul => l * r,
+        ArithOp::Div => l / r,
+        ArithOp::Mod => l % r,
+    }
+}
+
+
+// SQL three-valued logic, comparing with null results in Unknown
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Truth {
+    True,
+    False,
+    Unknown,
+}
+
+impl Truth {
+    pub fn from_bool(b : bool) -> Truth {
+        if b { Truth::True } else { Truth::False }
+    }
+    pub fn and(self, other : Truth) -> Truth {
+        match (self, other) {
+            (Truth::False, _) | (_, Truth::False) => Truth::False,
+            (Truth::True, Truth::True) => Truth::True,
+            _ => Truth::Unknown,
+        }
+    }
+    pub fn or(self, other : Truth) -> Truth {
+        match (self, other) {
+            (Truth::True, _) | (_, Truth::True) => Truth::True,
+            (Truth::False, Truth::False) => Truth::False,
+            _ => Truth::Unknown,
+        }
+    }
+    pub fn not(self) -> Truth {
+        match self {
+            Truth::True => Truth::False,
+            Truth::False => Truth::True,
+            Truth::Unknown => Truth::Unknown,
+        }
+    }
+    pub fn is_true(self) -> bool {
+        self == Truth::True
+    }
+}
+
+
+pub fn eval_cond(condition : &ConditionExpr, ptr_map : &PtrMap) -> EvalResult<Truth> {
+    match condition {
+        &ConditionExpr::NotExpr{ ref operant } => eval_cond(operant, ptr_map).map(Truth::not),
+        &ConditionExpr::CmpExpr{ ref lhs, ref rhs, op } =>
+            eval_cmp_operant(lhs, rhs, op, ptr_map),
+        &ConditionExpr::LogicExpr{ ref lhs, ref rhs, op } =>
+            eval_logic_op(lhs, rhs, op, ptr_map),
+    }
+}
+
+pub fn eval_logic_op(lhs : &CondRef, rhs : &CondRef, op : LogicOp, ptr_map : &PtrMap) -> EvalResult<Truth> {
+    let lresult = try!(eval_cond(&**lhs, ptr_map));
+    let rresult = try!(eval_cond(&**rhs, ptr_map));
+    Ok(match op {
+        LogicOp::Or => lresult.or(rresult),
+        LogicOp::And => lresult.and(rresult),
+    })
+}
+
+pub fn eval_cmp_operant(
+        lhs : &CmpOperantExpr,
+        rhs : &CmpOperantExpr,
+        op : CmpOp,
+        ptr_map : &PtrMap) -> EvalResult<Truth> {
+    match op {
+        CmpOp::Is | CmpOp::IsNot => {
+            // sem_check ensures that the right operant is null
+            let is_null = try!(eval_is_null(lhs, ptr_map));
+            return Ok(Truth::from_bool(if op == CmpOp::Is { is_null } else { !is_null }));
+        }
+        _ => (),
+    }
+    let result = if is_num_operant(lhs, ptr_map) && is_num_operant(rhs, ptr_map) {
+        match (try!(eval_arith_operant(lhs, ptr_map)), try!(eval_arith_operant(rhs, ptr_map))) {
+            (Some(lvalue), Some(rvalue)) => Some(match Num::compare(lvalue, rvalue) {
+                Some(ordering) => cmp_by_ordering(ordering, op),
+                None => op == CmpOp::NE,  // NaN
+            }),
+            _ => None,
+        }
+    } else {
+        try!(eval_value_cmp(&try!(eval_value(lhs, ptr_map)), &try!(eval_value(rhs, ptr_map)), op))
+    };
+    Ok(match result {
+        Some(b) => Truth::from_bool(b),
+        None => Truth::Unknown,
+    })
+}
+
+pub fn eval_is_null(operant : &CmpOperantExpr, ptr_map : &PtrMap) -> EvalResult<bool> {
+    match operant {
+        &CmpOperantExpr::Value(ref v) => Ok(v.value_type == ValueType::Null),
+        &CmpOperantExpr::Arith(ArithExpr::Attr(ref attr_expr)) => Ok(get_attr_ptr(attr_expr, ptr_map).0.is_null()),
+        &CmpOperantExpr::Arith(ref arith) => eval_arith(arith, ptr_map).map(|n| n.is_none()),
+    }
+}
+
+fn cmp_by_ordering(ordering : Ordering, op : CmpOp) -> bool {
+    match op {
+        CmpOp::LT => ordering == Ordering::Less,
+        CmpOp::GT => ordering == Ordering::Greater,
+        CmpOp::LE => ordering != Ordering::Greater,
+        CmpOp::GE => ordering != Ordering::Less,
+        CmpOp::EQ => ordering == Ordering::Equal,
+        CmpOp::NE => ordering != Ordering::Equal,
+        CmpOp::Is | CmpOp::IsNot => unreachable!(),
+    }
+}
+
+pub fn eval_value_cmp(lvalue : &Option<TupleValue>, rvalue : &Option<TupleValue>,
+        op : CmpOp) -> EvalResult<Option<bool>> {
+    let ordering = match (lvalue, rvalue) {
+        (&Some(TupleValue::Char(ref l)), &Some(TupleValue::Char(ref r))) => l.cmp(r),
+        (&Some(TupleValue::Boolean(l)), &Some(TupleValue::Boolean(r))) => l.cmp(&r),
+        (&Some(TupleValue::Date(l)), &Some(TupleValue::Date(r))) => l.cmp(&r),
+        (&Some(TupleValue::Time(l)), &Some(TupleValue::Time(r)))
+        | (&Some(TupleValue::Timestamp(l)), &Some(TupleValue::Timestamp(r))) => l.cmp(&r),
+        (&Some(ref l), &Some(ref r)) =>
+            return Err(ExecError::type_mismatch(format!("can't compare {:?} with {:?}", l, r))),
+        _ => return Ok(None),
+    };
+    Ok(Some(cmp_by_ordering(ordering, op)))
+}
+
+fn is_num_operant(operant : &CmpOperantExpr, ptr_map : &PtrMap) -> bool {
+    match operant {
+        &CmpOperantExpr::Value(ref v) => v.value_type == ValueType::Integer || v.value_type == ValueType::Float,
+        &CmpOperantExpr::Arith(ArithExpr::Attr(ref attr_expr)) => {
+            get_attr_ptr(attr_expr, ptr_map).1.is_num()
+        }
+        &CmpOperantExpr::Arith(..) => true,
+    }
+}
+
+fn eval_arith_operant(operant : &CmpOperantExpr, ptr_map : &PtrMap) -> EvalResult<Option<Num>> {
+    match operant {
+        &CmpOperantExpr::Value(ref v) => eval_num(v),
+        &CmpOperantExpr::Arith(ref arith) => eval_arith(arith, ptr_map),
+    }
+}
+
+fn get_attr_ptr(attr_expr : &AttributeExpr, ptr_map : &PtrMap) -> (DataPtr, AttrType) {
+    let (table, attr) = match attr_expr {
+        &AttributeExpr::TableAttr{ref table, ref attr} => (table.clone(), attr.clone()),
+        &AttributeExpr::AggreFuncCall{ref table, ref attr, ..} => (table.clone(), attr.clone()),
+    };
+    assert!(table.is_some());
+    ptr_map.get(&(table.unwrap(), attr)).unwrap().clone()
+}
+
+pub fn eval_value(operant : &CmpOperantExpr, ptr_map : &PtrMap) -> EvalResult<Option<TupleValue>> {
+    // strings, booleans, dates and times, return None if the value is null
+    match operant {
+        &CmpOperantExpr::Value(ref v) => match parse_tuple_value(v, &literal_attr_type(v)) {
+            Some(TupleValue::Null) => Ok(None),
+            Some(value) => Ok(Some(value)),
+            None => Err(ExecError::type_mismatch(format!("invalid literal {}", v))),
+        },
+        &CmpOperantExpr::Arith(ArithExpr::Attr(ref attr_expr)) => {
+            let (p, t) = get_attr_ptr(attr_expr, ptr_map);
+            match gen_tuple_value(&vec![t], vec![p]).pop().unwrap() {
+                TupleValue::Null => Ok(None),
+                value => Ok(Some(value)),
+            }
+        }
+        &CmpOperantExpr::Arith(ref arith) =>
+            Err(ExecError::type_mismatch(format!("expected attribute, found {:?}", arith))),
+    }
+}
+
+fn literal_attr_type(value : &ValueExpr) -> AttrType {
+    match value.value_type {
+        ValueType::Boolean => AttrType::Boolean,
+        ValueType::Date => AttrType::Date,
+        ValueType::Time => AttrType::Time,
+        ValueType::Timestamp => AttrType::Timestamp,
+        _ => AttrType::Varchar{ len : value.value.len() },
+    }
+}
+
+pub fn eval_arith(expr : &ArithExpr, ptr_map : &PtrMap) -> EvalResult<Option<Num>> {
+    // return None if the result is null
+    match expr {
+        &ArithExpr::BinaryExpr{ ref lhs, ref rhs, op } => {
+            match (try!(eval_arith(lhs, ptr_map)), try!(eval_arith(rhs, ptr_map))) {
+                (Some(l), Some(r)) => Ok(Num::apply(op, l, r)),
+                _ => Ok(None),
+            }
+        }
+        &ArithExpr::MinusExpr{ ref operant } => Ok(try!(eval_arith(operant, ptr_map)).map(|n| match n {
+            Num::Int(n) => n.checked_neg().map_or(Num::Double(-(n as f64)), Num::Int),
+            Num::Decimal(n, s) => Num::Decimal(-n, s),
+            Num::Float(n) => Num::Float(-n),
+            Num::Double(n) => Num::Double(-n),
+        })),
+        &ArithExpr::Value(ref v) => eval_num(v),
+        &ArithExpr::Attr( ref attr_expr ) => {
+            let (p, t) = get_attr_ptr(attr_expr, ptr_map);
+            if p.is_null() {
+                return Ok(None);
+            }
+            match t {
+                AttrType::Int => Ok(Some(Num::Int(unsafe{ read_int(p) } as i64))),
+                AttrType::Float => Ok(Some(Num::Float(unsafe{ read_float(p) }))),
+                AttrType::BigInt => Ok(Some(Num::Int(unsafe{ read_bigint(p) }))),
+                AttrType::Double => Ok(Some(Num::Double(unsafe{ read_double(p) }))),
+                AttrType::Decimal{scale, ..} => Ok(Some(Num::Decimal(unsafe{ read_bigint(p) } as i128, scale as u32))),
+                _ => Err(ExecError::type_mismatch(format!("{} of type {:?} is not a number", attr_expr, t))),
+            }
+        }
+    }
+}
+
+pub fn eval_num(expr : &ValueExpr) -> EvalResult<Option<Num>> {
+    // the literals with a point are exact decimals
+    match expr.value_type {
+        ValueType::Integer => Ok(Some(expr.value.parse::<i64>().map(Num::Int)
+            .unwrap_or_else(|_| Num::Double(expr.value.parse::<f64>().unwrap())))),
+        ValueType::Float => {
+            let scale = expr.value.len() - expr.value.find('.').map_or(expr.value.len(), |i| i + 1);
+            Ok(Some(parse_decimal(&expr.value, scale).map(|n| Num::Decimal(n as i128, scale as u32))
+                .unwrap_or_else(|| Num::Double(expr.value.parse::<f64>().unwrap()))))
+        }
+        ValueType::Null => Ok(None),
+        t => Err(ExecError::type_mismatch(format!("{} of type {:?} is not a number", expr, t))),
+    }
+}
@@ -1,224 +1,343 @@
-use std::vec::Vec;
-use std::boxed::Box;
-use std::collections::HashMap;
-use ::parser::common::{Statement, ValueExpr, ValueType};
-use ::parser::select::{Relation, SelectExpr};
-use ::parser::attribute::AttributeExpr;
-use ::parser::condition::gen_check_primary_key_condition_expr;
-use ::parser::{
-    SelectStatement,
-    InsertStatement,
-    UpdateStatement,
-    DeleteStatement,
-    CreateStatement,
-    DropStatement,
-};
-use ::store::table::{TableSet, TableManagerRef, TableRef};
-use ::store::tuple::TupleValue; 
-use super::iter::ExecIterRef;
-use super::create_drop::{CreateTable, DropTable};
-use super::change::{Insert, CheckAndInsert, Update, Delete};
-use super::query::{FileScan, Filter, Projection};
-
-
-pub fn gen_plan(stmt : Statement, table_manager : &TableManagerRef)
-        -> ExecIterRef {
-    match stmt {
-        Statement::Create(create) => gen_create_plan(create, table_manager),
-        Statement::Drop(drop) => gen_drop_plan(drop, table_manager),
-        Statement::Insert(insert) => gen_insert_plan(insert, table_manager),
-        Statement::Update(update) => gen_update_plan(update, table_manager),
-        Statement::Delete(delete) => gen_delete_plan(delete, table_manager),
-        Statement::Select(select) => gen_select_plan(select, table_manager),
-    }
-}
-
-pub fn gen_create_plan(stmt : CreateStatement, table_manager : &TableManagerRef) -> ExecIterRef {
-    CreateTable::new(stmt, table_manager)
-}
-
-pub fn gen_drop_plan(stmt : DropStatement, table_manager : &TableManagerRef) -> ExecIterRef {
-    DropTable::new(stmt, table_manager)
-}
-
-pub fn gen_select_plan(stmt : SelectStatement, table_manager : &TableManagerRef) -> ExecIterRef {
-    // join and sub query not supported now
-    let table_name = extract!(&stmt.relation_list[0], &Relation::TableName(ref name), name.clone());
-    let table = table_manager.borrow().get_table(&table_name).unwrap();
-    let mut query = FileScan::new(&table_name, table_manager);
-    let (attr_index, proj_attr_list) = gen_select_proj_info(&stmt, &table);
-    let need_proj = is_match!(stmt.select_expr, SelectExpr::AttrList(..));
-    if let Some(cond) = stmt.where_condition {
-        query = Filter::new(Box::new(cond),
-            table.borrow().gen_index_map(),
-            table.borrow().gen_tuple_desc(), query);
-    }
-    if need_proj {
-        query = Projection::new(attr_index, proj_attr_list, query);
-    }
-    query
-}
-
-pub fn gen_select_proj_info(
-        stmt : &SelectStatement, table : &TableRef) -> (Vec<usize>, Vec<(String, String)>) {
-    let table = table.borrow();
-    let mut proj_attr_index = Vec::new();
-    let mut proj_attr_list = Vec::new();
-    let mut table_and_attr_list = match stmt.select_expr {
-        SelectExpr::AttrList(ref l) => {
-            let mut table_and_attr_list = Vec::new();
-            for attr in l {
-                let table_and_attr = extract!(attr, &AttributeExpr::TableAttr{ref table, ref attr},
-                    (table.clone().unwrap(), attr.clone()));
-                table_and_attr_list.push(table_and_attr);
-            }
-            table_and_attr_list
-        }
-        SelectExpr::AllAttribute => {
-            table.get_attr_name_list().iter().map(|a| (table.name.clone(), a.clone())).collect()
-        }
-    };
-    let index_map = table.gen_index_map();
-    for table_and_attr in table_and_attr_list.drain(..) {
-        proj_attr_index.push(index_map.get(&table_and_attr).unwrap().clone());
-        proj_attr_list.push(table_and_attr);
-    }
-    (proj_attr_index, proj_attr_list)
-}
-
-pub fn gen_proj_info(
-        stmt : &Statement, table_manager : &TableManagerRef) -> (Vec<usize>, Vec<(String, String)>) {
-    let mut proj_attr_index = Vec::new();
-    let mut proj_attr_list = Vec::new();
-    let table = get_stmt_table(stmt, table_manager);
-    if let &Statement::Select(ref select) = stmt {
-        return gen_select_proj_info(select, &table);
-    } else {
-        let table = table.borrow();
-        let table_name = table.name.clone();
-        for (i, attr) in table.attr_list.iter().enumerate() {
-            proj_attr_list.push((table_name.clone(), attr.name.clone()));
-            proj_attr_index.push(i);
-        }
-    }
-    (proj_attr_index, proj_attr_list)
-}
-
-pub fn get_stmt_table(stmt : &Statement, table_manager : &TableManagerRef) -> TableRef {
-    match stmt {
-        &Statement::Create(..) | &Statement::Drop(..) => panic!("invalid state"),
-        &Statement::Insert(ref insert) => table_manager.borrow().get_table(&insert.table).unwrap(),
-        &Statement::Update(ref update) => table_manager.borrow().get_table(&update.table).unwrap(),
-        &Statement::Delete(ref delete) => table_manager.borrow().get_table(&delete.table).unwrap(),
-        &Statement::Select(ref select) => {
-            let table_name = extract!(
-                select.relation_list[0], Relation::TableName(ref name), name);
-            table_manager.borrow().get_table(&table_name).unwrap()
-        }
-    }
-}
-
-pub fn gen_delete_plan(stmt : DeleteStatement, table_manager : &TableManagerRef) -> ExecIterRef {
-    let table = table_manager.borrow().get_table(&stmt.table).unwrap();
-    let mut data_source = FileScan::new(&stmt.table, table_manager);
-    if let Some(cond) = stmt.where_condition {
-        data_source = Filter::new(Box::new(cond),
-            table.borrow().gen_index_map(),
-            table.borrow().gen_tuple_desc(), data_source);
-    }
-    Delete::new(&stmt.table, data_source, table_manager)
-}
-
-pub fn gen_insert_plan(stmt : InsertStatement, table_manager : &TableManagerRef) -> ExecIterRef {
-    let table = table_manager.borrow().get_table(&stmt.table).unwrap();
-    let pk_index = table.borrow().get_primary_key_index();
-    let pk = stmt.value_list[pk_index].value.parse::<i32>().unwrap();
-    let check = gen_check_primary_key_exist_plan(pk, &stmt.table, table_manager);
-    CheckAndInsert::new(check, Insert::new(stmt, table_manager))
-}
-
-pub fn gen_update_plan(stmt : UpdateStatement, table_manager : &TableManagerRef) -> ExecIterRef {
-    let table = table_manager.borrow().get_table(&stmt.table).unwrap();
-    let mut data_source = FileScan::new(&stmt.table, table_manager);
-    if let Some(cond) = stmt.where_condition {
-        data_source = Filter::new(Box::new(cond),
-            table.borrow().gen_index_map(),
-            table.borrow().gen_tuple_desc(), data_source);
-    }
-    let mut set_values = HashMap::new();
-    let index_map = table.borrow().gen_index_map();
-    for assign in stmt.set_list.iter() {
-        let attr = &assign.attr;
-        let value = &assign.value;
-        let index = index_map.get(&(stmt.table.clone(), attr.clone())).unwrap();
-        let tuple_value = value_expr_to_tuple_value(value);
-        set_values.insert(*index, tuple_value);
-    }
-    let tuple_desc = table.borrow().gen_tuple_desc();
-    Update::new(&stmt.table, tuple_desc, set_values, data_source, table_manager)
-}
-
-pub fn value_expr_to_tuple_value(expr : &ValueExpr) -> TupleValue {
-    match expr.value_type {
-        ValueType::Integer => TupleValue::Int(expr.value.parse::<i32>().unwrap()),
-        ValueType::Float => TupleValue::Float(expr.value.parse::<f32>().unwrap()),
-        ValueType::String => TupleValue::Char(expr.value.clone()),
-        ValueType::Null => unimplemented!(),
-    }
-}
-
-pub fn gen_check_primary_key_exist_plan(
-        pk : i32,
-        table_name : &String,
-        table_manager : &TableManagerRef) -> ExecIterRef {
-    let table = table_manager.borrow().get_table(table_name).unwrap();
-    let pk_attr = table.borrow().get_primary_key_attr();
-    let cond = gen_check_primary_key_condition_expr(table_name, &pk_attr.name, pk);
-    let scan = FileScan::new(table_name, table_manager);
-    let filter = Filter::new(Box::new(cond),
-        table.borrow().gen_index_map(),
-        table.borrow().gen_tuple_desc(), scan);
-    filter
-}
-
-pub fn gen_table_set(stmt : &Statement, table_manager : &TableManagerRef) -> TableSet {
-    let mut table_list = Vec::new();
-    match stmt {
-        &Statement::Select(ref select) => {
-            let mut tables = gen_select_table_set_helper(select);
-            for name in tables.drain(..) {
-                table_list.push(name);
-            }
-        }
-        &Statement::Delete(ref delete) =>
-            { table_list.push(delete.table.clone()); }
-        &Statement::Update(ref update) =>
-            { table_list.push(update.table.clone()); }
-        &Statement::Insert(ref insert) =>
-            { table_list.push(insert.table.clone()); }
-        &Statement::Create(ref create) => {
-            if let Some(..) = table_manager.borrow().get_table(&create.table) {
-                table_list.push(create.table.clone());
-            }
-        }
-        &Statement::Drop(ref drop) => {
-            if let Some(..) = table_manager.borrow().get_table(&drop.table) {
-                table_list.push(drop.table.clone());
-            }
-        }
-
-    }
-    table_manager.borrow().gen_table_set(&table_list)
-}
-
-fn gen_select_table_set_helper(stmt : &SelectStatement) -> Vec<String> {
-    let mut result = Vec::new();
-    for rel in &stmt.relation_list {
-        match rel {
-            &Relation::TableName(ref name) => result.push(name.clone()),
-            &Relation::Select(ref sub_select) =>
-                result.extend_from_slice(&gen_select_table_set_helper(sub_select))
-        }
-    }
-    result
-}
+use std::vec::Vec;
+use std::boxed::Box;
+use std::collections::HashMap;
+use ::parser::common::{Statement, ValueExpr, ValueType};
+use ::parser::select::{Relation, SelectExpr};
+use ::parser::attribute::AttributeExpr;
+use ::parser::condition::{
+    ConditionExpr,
+    CmpOperantExpr,
+    ArithExpr,
+    LogicOp,
+    CmpOp,
+};
+use ::parser::{
+    SelectStatement,
+    InsertStatement,
+    UpdateStatement,
+    DeleteStatement,
+    CreateStatement,
+    DropStatement,
+    CreateIndexStatement,
+    DropIndexStatement,
+    VacuumStatement,
+    AlterStatement,
+};
+use ::parser::alter::AlterAction;
+use ::store::table::{TableSet, TableManagerRef, TableRef, Table, Attr, AttrType, Index};
+use ::store::tuple::{TupleValue, parse_tuple_value};
+use ::store::index::PRIMARY_INDEX;
+use super::iter::ExecIterRef;
+use super::error::{ExecError, ExecErrorType};
+use super::create_drop::{CreateTable, DropTable, CreateIndex, DropIndex};
+use super::change::{Insert, CheckAndInsert, Update, Delete};
+use super::vacuum::Vacuum;
+use super::alter::AlterTable;
+use super::query::{FileScan, IndexScan, Filter, Projection};
+
+
+pub fn gen_plan(stmt : Statement, table_manager : &TableManagerRef)
+        -> ExecIterRef {
+    match stmt {
+        Statement::Create(create) => gen_create_plan(create, table_manager),
+        Statement::Drop(drop) => gen_drop_plan(drop, table_manager),
+        Statement::CreateIndex(create) => gen_create_index_plan(create, table_manager),
+        Statement::DropIndex(drop) => gen_drop_index_plan(drop, table_manager),
+        Statement::Insert(insert) => gen_insert_plan(insert, table_manager),
+        Statement::Update(update) => gen_update_plan(update, table_manager),
+        Statement::Delete(delete) => gen_delete_plan(delete, table_manager),
+        Statement::Select(select) => gen_select_plan(select, table_manager),
+        Statement::Vacuum(vacuum) => gen_vacuum_plan(vacuum, table_manager),
+        Statement::Alter(alter) => gen_alter_plan(alter, table_manager),
+    }
+}
+
+pub fn gen_create_plan(stmt : CreateStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    CreateTable::new(stmt, table_manager)
+}
+
+pub fn gen_drop_plan(stmt : DropStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    DropTable::new(stmt, table_manager)
+}
+
+pub fn gen_create_index_plan(stmt : CreateIndexStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    CreateIndex::new(stmt, table_manager)
+}
+
+pub fn gen_drop_index_plan(stmt : DropIndexStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    DropIndex::new(stmt, table_manager)
+}
+
+pub fn gen_vacuum_plan(stmt : VacuumStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    Vacuum::new(stmt, table_manager)
+}
+
+pub fn gen_alter_plan(stmt : AlterStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    AlterTable::new(stmt, table_manager)
+}
+
+pub fn gen_select_plan(stmt : SelectStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    // join and sub query not supported now
+    let table_name = extract!(&stmt.relation_list[0], &Relation::TableName(ref name), name.clone());
+    let table = table_manager.read().unwrap().get_table(&table_name).unwrap();
+    let mut query = gen_scan_plan(&table, &stmt.where_condition, table_manager);
+    let (attr_index, proj_attr_list) = gen_select_proj_info(&stmt, &table);
+    let need_proj = is_match!(stmt.select_expr, SelectExpr::AttrList(..));
+    if let Some(cond) = stmt.where_condition {
+        query = Filter::new(Box::new(cond),
+            table.read().unwrap().gen_index_map(),
+            table.read().unwrap().gen_tuple_desc(), query);
+    }
+    if need_proj {
+        query = Projection::new(attr_index, proj_attr_list, query);
+    }
+    query
+}
+
+pub fn gen_select_proj_info(
+        stmt : &SelectStatement, table : &TableRef) -> (Vec<usize>, Vec<(String, String)>) {
+    let table = table.read().unwrap();
+    let mut proj_attr_index = Vec::new();
+    let mut proj_attr_list = Vec::new();
+    let mut table_and_attr_list = match stmt.select_expr {
+        SelectExpr::AttrList(ref l) => {
+            let mut table_and_attr_list = Vec::new();
+            for attr in l {
+                let table_and_attr = extract!(attr, &AttributeExpr::TableAttr{ref table, ref attr},
+                    (table.clone().unwrap(), attr.clone()));
+                table_and_attr_list.push(table_and_attr);
+            }
+            table_and_attr_list
+        }
+        SelectExpr::AllAttribute => {
+            table.get_attr_name_list().iter().map(|a| (table.name.clone(), a.clone())).collect()
+        }
+    };
+    let index_map = table.gen_index_map();
+    for table_and_attr in table_and_attr_list.drain(..) {
+        proj_attr_index.push(index_map.get(&table_and_attr).unwrap().clone());
+        proj_attr_list.push(table_and_attr);
+    }
+    (proj_attr_index, proj_attr_list)
+}
+
+pub fn gen_proj_info(
+        stmt : &Statement, table_manager : &TableManagerRef) -> (Vec<usize>, Vec<(String, String)>) {
+    let mut proj_attr_index = Vec::new();
+    let mut proj_attr_list = Vec::new();
+    let table = get_stmt_table(stmt, table_manager);
+    if let &Statement::Select(ref select) = stmt {
+        return gen_select_proj_info(select, &table);
+    } else {
+        let table = table.read().unwrap();
+        let table_name = table.name.clone();
+        for (i, attr) in table.attr_list.iter().enumerate() {
+            proj_attr_list.push((table_name.clone(), attr.name.clone()));
+            proj_attr_index.push(i);
+        }
+    }
+    (proj_attr_index, proj_attr_list)
+}
+
+pub fn get_stmt_table(stmt : &Statement, table_manager : &TableManagerRef) -> TableRef {
+    match stmt {
+        &Statement::Create(..) | &Statement::Drop(..)
+            | &Statement::CreateIndex(..) | &Statement::DropIndex(..)
+            | &Statement::Vacuum(..) | &Statement::Alter(..) => panic!("invalid state"),
+        &Statement::Insert(ref insert) => table_manager.read().unwrap().get_table(&insert.table).unwrap(),
+        &Statement::Update(ref update) => table_manager.read().unwrap().get_table(&update.table).unwrap(),
+        &Statement::Delete(ref delete) => table_manager.read().unwrap().get_table(&delete.table).unwrap(),
+        &Statement::Select(ref select) => {
+            let table_name = extract!(
+                select.relation_list[0], Relation::TableName(ref name), name);
+            table_manager.read().unwrap().get_table(&table_name).unwrap()
+        }
+    }
+}
+
+pub fn gen_delete_plan(stmt : DeleteStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    let table = table_manager.read().unwrap().get_table(&stmt.table).unwrap();
+    let mut data_source = gen_scan_plan(&table, &stmt.where_condition, table_manager);
+    if let Some(cond) = stmt.where_condition {
+        data_source = Filter::new(Box::new(cond),
+            table.read().unwrap().gen_index_map(),
+            table.read().unwrap().gen_tuple_desc(), data_source);
+    }
+    Delete::new(&stmt.table, data_source, table_manager)
+}
+
+pub fn gen_insert_plan(stmt : InsertStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    let table = table_manager.read().unwrap().get_table(&stmt.table).unwrap();
+    let mut check_list = Vec::new();
+    for index in table.read().unwrap().gen_index_list().iter().filter(|i| i.unique) {
+        let attr_list = table.read().unwrap().get_index_attr_list(index);
+        // null never conflicts
+        let key : Option<Vec<TupleValue>> = attr_list.iter().map(|attr| {
+            let attr_type = table.read().unwrap().attr_list[*attr].attr_type;
+            value_expr_to_key_value(&stmt.value_list[*attr], attr_type)
+        }).collect();
+        if let Some(key) = key {
+            check_list.push(gen_check_key_exist_plan(index, key, &stmt.table, table_manager));
+        }
+    }
+    let mut plan = Insert::new(stmt, table_manager);
+    for (check, error) in check_list.into_iter().rev() {
+        plan = CheckAndInsert::new(check, plan, error);
+    }
+    plan
+}
+
+pub fn gen_update_plan(stmt : UpdateStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+    let table = table_manager.read().unwrap().get_table(&stmt.table).unwrap();
+    let mut data_source = gen_scan_plan(&table, &stmt.where_condition, table_manager);
+    if let Some(cond) = stmt.where_condition {
+        data_source = Filter::new(Box::new(cond),
+            table.read().unwrap().gen_index_map(),
+            table.read().unwrap().gen_tuple_desc(), data_source);
+    }
+    let mut set_values = HashMap::new();
+    let index_map = table.read().unwrap().gen_index_map();
+    let tuple_desc = table.read().unwrap().gen_tuple_desc();
+    for assign in stmt.set_list.iter() {
+        let attr = &assign.attr;
+        let value = &assign.value;
+        let index = index_map.get(&(stmt.table.clone(), attr.clone())).unwrap();
+        // checked by sem_check
+        set_values.insert(*index, parse_tuple_value(value, &tuple_desc.attr_desc[*index]).unwrap());
+    }
+    Update::new(&stmt.table, tuple_desc, set_values, data_source, table_manager)
+}
+
+pub fn gen_check_key_exist_plan(
+        index : &Index,
+        key : Vec<TupleValue>,
+        table_name : &String,
+        table_manager : &TableManagerRef) -> (ExecIterRef, ExecError) {
+    let error = if index.name == PRIMARY_INDEX {
+        ExecError{
+            error_type : ExecErrorType::PrimaryKeyExist,
+            error_msg : format!("primary key already exist"),
+        }
+    } else {
+        ExecError{
+            error_type : ExecErrorType::UniqueKeyExist,
+            error_msg : format!("unique key already exist in index {}", index.name),
+        }
+    };
+    (IndexScan::new(table_name, &index.name, key, table_manager), error)
+}
+
+pub fn gen_scan_plan(table : &TableRef, cond : &Option<ConditionExpr>,
+        table_manager : &TableManagerRef) -> ExecIterRef {
+    // use the first index, primary key index first, whose attributes are all compared with values
+    // in the condition, which should still be checked by Filter
+    let table = table.read().unwrap();
+    if let &Some(ref cond) = cond {
+        for index in table.gen_index_list().iter() {
+            let key : Option<Vec<TupleValue>> = table.get_index_attr_list(index).iter()
+                .map(|i| find_attr_value(cond, &table, &table.attr_list[*i])).collect();
+            if let Some(key) = key {
+                return IndexScan::new(&table.name, &index.name, key, table_manager);
+            }
+        }
+    }
+    FileScan::new(&table.name, table_manager)
+}
+
+fn find_attr_value(cond : &ConditionExpr, table : &Table, attr : &Attr) -> Option<TupleValue> {
+    // find `attr = value` in the top level conjunction
+    match cond {
+        &ConditionExpr::LogicExpr{ref lhs, ref rhs, op : LogicOp::And} =>
+            find_attr_value(lhs, table, attr).or_else(|| find_attr_value(rhs, table, attr)),
+        &ConditionExpr::CmpExpr{ref lhs, ref rhs, op : CmpOp::EQ} => {
+            let value = if is_table_attr(lhs, table, &attr.name) {
+                rhs
+            } else if is_table_attr(rhs, table, &attr.name) {
+                lhs
+            } else {
+                return None;
+            };
+            match value {
+                &CmpOperantExpr::Value(ref value) | &CmpOperantExpr::Arith(ArithExpr::Value(ref value)) =>
+                    value_expr_to_key_value(value, attr.attr_type),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_table_attr(operant : &CmpOperantExpr, table : &Table, attr_name : &String) -> bool {
+    match operant {
+        &CmpOperantExpr::Arith(ArithExpr::Attr(AttributeExpr::TableAttr{table : ref t, ref attr})) =>
+            attr == attr_name && t.as_ref().map_or(true, |t| *t == table.name),
+        _ => false,
+    }
+}
+
+fn value_expr_to_key_value(expr : &ValueExpr, attr_type : AttrType) -> Option<TupleValue> {
+    // the rounded decimals only find more tuples, which are checked by Filter later
+    match expr.value_type {
+        ValueType::Null => None,
+        _ => parse_tuple_value(expr, &attr_type),
+    }
+}
+
+pub fn gen_table_set(stmt : &Statement, table_manager : &TableManagerRef) -> TableSet {
+    // tables not existing are not included
+    let table_list = get_table_list(stmt).unwrap_or_else(Vec::new);
+    table_manager.read().unwrap().gen_table_set(&table_list)
+}
+
+pub fn get_table_list(stmt : &Statement) -> Option<Vec<String>> {
+    // tables used by the statement, None means all the tables
+    let mut table_list = Vec::new();
+    match stmt {
+        &Statement::Select(ref select) => {
+            let mut tables = gen_select_table_set_helper(select);
+            for name in tables.drain(..) {
+                table_list.push(name);
+            }
+        }
+        &Statement::Delete(ref delete) =>
+            { table_list.push(delete.table.clone()); }
+        &Statement::Update(ref update) =>
+            { table_list.push(update.table.clone()); }
+        &Statement::Insert(ref insert) =>
+            { table_list.push(insert.table.clone()); }
+        &Statement::Create(ref create) =>
+            { table_list.push(create.table.clone()); }
+        &Statement::Drop(ref drop) =>
+            { table_list.push(drop.table.clone()); }
+        &Statement::CreateIndex(ref create) =>
+            { table_list.push(create.table.clone()); }
+        &Statement::DropIndex(ref drop) =>
+            { table_list.push(drop.table.clone()); }
+        &Statement::Vacuum(ref vacuum) => {
+            match vacuum.table {
+                Some(ref table) => table_list.push(table.clone()),
+                None => return None,
+            }
+        }
+        &Statement::Alter(ref alter) => {
+            table_list.push(alter.table.clone());
+            if let AlterAction::RenameTable{ref new_name} = alter.action {
+                table_list.push(new_name.clone());
+            }
+        }
+
+    }
+    Some(table_list)
+}
+
+fn gen_select_table_set_helper(stmt : &SelectStatement) -> Vec<String> {
+    let mut result = Vec::new();
+    for rel in &stmt.relation_list {
+        match rel {
+            &Relation::TableName(ref name) => result.push(name.clone()),
+            &Relation::Select(ref sub_select) =>
+                result.extend_from_slice(&gen_select_table_set_helper(sub_select))
+        }
+    }
+    result
+}
@@ -1,242 +1,354 @@
-use std::boxed::Box;
-use std::option::Option;
-use std::collections::HashSet;
-use ::store::table::{TableManagerRef, IndexMap};
-use ::store::tuple::{TupleData, TupleDesc};
-use ::store::file::TableFileRef;
-use ::store::buffer::PageKey;
-use ::parser::condition::CondRef;
-use super::iter::{ExecIter, ExecIterRef};
-use super::error::ExecError;
-use super::evaluate::PtrMap;
-use super::evaluate::eval_cond;
-
-
-#[derive(Debug)]
-pub struct FileScan {
-    table : String,
-    table_manager : TableManagerRef,
-    curr_position : usize,
-    pinned_pages : HashSet<PageKey>,
-    file : TableFileRef,
-    finished : bool,
-}
-
-impl FileScan {
-    pub fn new(table : &String, table_manager : &TableManagerRef) -> ExecIterRef {
-        let file = table_manager.borrow_mut().file_manager.get_file(&table);
-        Box::new(FileScan{
-            table : table.clone(),
-            table_manager : table_manager.clone(),
-            curr_position : 0,
-            pinned_pages : HashSet::new(),
-            file : file,
-            finished : false,
-        })
-    }
-    fn find_page_helper(&mut self, page_index : &mut usize,
-            tuple_index : &mut usize) -> Option<usize> {
-        let page_sum = self.file.borrow().page_sum;
-        let slot_sum = self.file.borrow().get_page_slot_sum();
-        while *page_index < page_sum {
-            let next = self.file.borrow().next_tuple_index(*page_index, *tuple_index);
-            match next {
-                Some(i) => return Some(*page_index * slot_sum + i),
-                None => {
-                    let fd = self.file.borrow().get_fd();
-                    self.pinned_pages.remove(&PageKey{ fd : fd, page_index : *page_index as u32 });
-                    self.table_manager.borrow_mut().file_manager.unpin_page(fd, *page_index as u32);
-                    *page_index += 1;
-                    *tuple_index = 0;
-                    if *page_index < page_sum {
-                        self.table_manager.borrow_mut().file_manager.ensure_page_loaded(
-                            &self.file, *page_index);
-                        self.table_manager.borrow_mut().file_manager.pin_page(fd, *page_index as u32);
-                        self.pinned_pages.insert(PageKey{ fd : fd, page_index : *page_index as u32 });
-                    }
-                }
-            }
-        }
-        None
-    }
-}
-
-impl ExecIter for FileScan {
-    fn open(&mut self) {
-        assert!(!self.finished);
-        let page_num = self.file.borrow().page_sum;
-        if page_num == 0 {
-            self.close();
-            return;
-        }
-        let fd = self.file.borrow().get_fd();
-        self.pinned_pages.insert(PageKey{ fd : fd, page_index : 0 });
-        let mut table_manager = self.table_manager.borrow_mut();
-        table_manager.file_manager.ensure_page_loaded(&self.file, 0);
-        table_manager.file_manager.pin_page(fd, 0);
-    }
-    fn close(&mut self) {
-        if self.finished {
-            return;
-        }
-        self.finished = true;
-        let mut table_manager = self.table_manager.borrow_mut();
-        for &PageKey{ fd, page_index } in self.pinned_pages.iter() {
-            table_manager.file_manager.unpin_page(fd, page_index);
-        }
-    }
-    fn explain(&self) -> String {
-        format!("file scan, page sum: {:?}",
-            self.file.borrow().page_sum)
-    }
-    fn get_next(&mut self) -> Option<TupleData> {
-        if self.finished {
-            return None;
-        }
-        let file = self.file.clone();
-        let slot_sum = file.borrow().get_page_slot_sum();
-        let shift_index = if self.curr_position == 0 {
-            self.curr_position
-        } else {
-            self.curr_position - 1
-        };  // to stay in the same page as the last get_next()
-        let mut page_index = shift_index / slot_sum;
-        let mut tuple_index = self.curr_position - slot_sum * page_index;
-        let index = self.find_page_helper(&mut page_index, &mut tuple_index);
-        let result = match index {
-            Some(position) => Some((
-                self.table_manager.borrow_mut().file_manager.get_tuple_data(
-                    &self.table, position).unwrap(),
-                position
-            )),
-            None => None,
-        };
-        match result {
-            Some((tuple_data, new_position)) => {
-                self.curr_position = new_position + 1;
-                Some(tuple_data)
-            }
-            None => {
-                self.finished = true;
-                None
-            }
-        }
-    }
-    fn get_error(&self) -> Option<ExecError> { None }
-}
-
-
-#[derive(Debug)]
-pub struct Filter {
-    data_source : ExecIterRef,
-    condition : CondRef,
-    index_map : IndexMap,
-    tuple_desc : TupleDesc,
-    finished : bool,
-}
-
-impl Filter {
-    pub fn new(
-            condition : CondRef,
-            index_map : IndexMap,
-            tuple_desc : TupleDesc,
-            inner_iter : ExecIterRef) -> ExecIterRef {
-        Box::new(Filter{
-            condition : condition,
-            data_source : inner_iter,
-            index_map : index_map,
-            tuple_desc : tuple_desc,
-            finished : false,
-        })
-    }
-}
-
-impl ExecIter for Filter {
-    fn open(&mut self) {
-        self.data_source.open();
-    }
-    fn close(&mut self) {
-        self.data_source.close();
-        self.finished = true;
-    }
-    fn explain(&self) -> String {
-        format!("filtered by condition: {:?} from source {:?}", self.condition, self.data_source)
-    }
-    fn get_next(&mut self) -> Option<TupleData> {
-        if self.finished {
-            return None;
-        }
-        assert_eq!(self.index_map.len(), self.tuple_desc.attr_desc.len());
-        while let Some(tuple_data) = self.data_source.get_next() {
-            assert_eq!(self.index_map.len(), tuple_data.len());
-            let mut ptr_map = PtrMap::new();
-            for (k, index) in &self.index_map {
-                ptr_map.insert(k.clone(), (
-                    tuple_data[*index],
-                    self.tuple_desc.attr_desc[*index].clone()
-                    ));
-            }
-            if eval_cond(&*self.condition, &ptr_map) {
-                return Some(tuple_data);
-            }
-        }
-        self.close();
-        None
-    }
-    fn get_error(&self) -> Option<ExecError> { None }
-}
-
-
-#[derive(Debug)]
-pub struct Projection {
-    data_source : ExecIterRef,
-    proj_attr_index : Vec<usize>,
-    proj_attr_list : Vec<(String, String)>,
-    finished : bool,
-}
-
-impl Projection {
-    pub fn new(
-            attr_index : Vec<usize>,
-            proj_attr_list : Vec<(String, String)>,
-            inner_iter : ExecIterRef) -> ExecIterRef {
-        Box::new(Projection{
-            data_source : inner_iter,
-            proj_attr_index : attr_index,
-            proj_attr_list : proj_attr_list,
-            finished : false,
-        })
-    }
-}
-
-impl ExecIter for Projection {
-    fn open(&mut self) {
-        self.data_source.open();
-    }
-    fn close(&mut self) {
-        self.data_source.close();
-        self.finished = true;
-    }
-    fn explain(&self) -> String {
-        format!("Projection: {:?} from source {:?}", self.proj_attr_list, self.data_source)
-    }
-    fn get_next(&mut self) -> Option<TupleData> {
-        if self.finished {
-            return None;
-        }
-        match self.data_source.get_next() {
-            Some(tuple_data) => {
-                let mut result = Vec::new();
-                for i in &self.proj_attr_index {
-                    result.push(tuple_data[*i]);
-                }
-                Some(result)
-            }
-            None => {
-                self.close();
-                None
-            }
-        }
-    }
-    fn get_error(&self) -> Option<ExecError> { None }
-}
+use std::boxed::Box;
+use std::option::Option;
+use std::collections::HashSet;
+use ::store::table::{TableManagerRef, IndexMap};
+use ::store::tuple::{TupleData, TupleDesc, TupleValue};
+use ::store::file::TableFileRef;
+use ::store::buffer::PageKey;
+use ::parser::condition::CondRef;
+use super::iter::{ExecIter, ExecIterRef};
+use super::error::ExecError;
+use super::evaluate::PtrMap;
+use super::evaluate::eval_cond;
+
+
+fn load_and_pin(table_manager : &TableManagerRef, file : &TableFileRef, page_index : usize) -> Result<(), ExecError> {
+    // pin before releasing the lock, or other workers may replace the page,
+    // the write lock is only needed when the page should be loaded
+    let loaded = try!(table_manager.read().unwrap().file_manager.pin_loaded_page(file, page_index)
+        .map_err(ExecError::buffer_pool_full));
+    if loaded {
+        return Ok(());
+    }
+    let mut table_manager = table_manager.write().unwrap();
+    try!(table_manager.file_manager.ensure_page_loaded(file, page_index).map_err(ExecError::page_corrupted));
+    let fd = file.read().unwrap().get_fd();
+    table_manager.file_manager.pin_page(fd, page_index as u32).map_err(ExecError::buffer_pool_full)
+}
+
+
+#[derive(Debug)]
+pub struct FileScan {
+    table : String,
+    table_manager : TableManagerRef,
+    curr_position : usize,
+    pinned_pages : HashSet<PageKey>,
+    file : TableFileRef,
+    finished : bool,
+    error : Option<ExecError>,
+}
+
+impl FileScan {
+    pub fn new(table : &String, table_manager : &TableManagerRef) -> ExecIterRef {
+        let file = table_manager.read().unwrap().file_manager.get_file(&table);
+        Box::new(FileScan{
+            table : table.clone(),
+            table_manager : table_manager.clone(),
+            curr_position : 0,
+            pinned_pages : HashSet::new(),
+            file : file,
+            finished : false,
+            error : None,
+        })
+    }
+    fn find_page_helper(&mut self, page_index : &mut usize,
+            tuple_index : &mut usize) -> Option<usize> {
+        let page_sum = self.file.read().unwrap().page_sum;
+        let slot_sum = self.file.read().unwrap().get_page_slot_sum();
+        while *page_index < page_sum {
+            let next = self.file.read().unwrap().next_tuple_index(*page_index, *tuple_index);
+            match next {
+                Some(i) => return Some(*page_index * slot_sum + i),
+                None => {
+                    let fd = self.file.read().unwrap().get_fd();
+                    self.pinned_pages.remove(&PageKey{ fd : fd, page_index : *page_index as u32 });
+                    self.table_manager.read().unwrap().file_manager.unpin_page(fd, *page_index as u32);
+                    *page_index += 1;
+                    *tuple_index = 0;
+                    if *page_index < page_sum {
+                        if let Err(err) = load_and_pin(&self.table_manager, &self.file, *page_index) {
+                            self.error = Some(err);
+                            return None;
+                        }
+                        self.pinned_pages.insert(PageKey{ fd : fd, page_index : *page_index as u32 });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl ExecIter for FileScan {
+    fn open(&mut self) {
+        assert!(!self.finished);
+        let page_num = self.file.read().unwrap().page_sum;
+        if page_num == 0 {
+            self.close();
+            return;
+        }
+        let fd = self.file.read().unwrap().get_fd();
+        if let Err(err) = load_and_pin(&self.table_manager, &self.file, 0) {
+            self.error = Some(err);
+            self.finished = true;  // nothing pinned
+            return;
+        }
+        self.pinned_pages.insert(PageKey{ fd : fd, page_index : 0 });
+    }
+    fn close(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        let table_manager = self.table_manager.read().unwrap();
+        for &PageKey{ fd, page_index } in self.pinned_pages.iter() {
+            table_manager.file_manager.unpin_page(fd, page_index);
+        }
+    }
+    fn explain(&self) -> String {
+        format!("file scan, page sum: {:?}",
+            self.file.read().unwrap().page_sum)
+    }
+    fn get_next(&mut self) -> Option<TupleData> {
+        if self.finished {
+            return None;
+        }
+        let file = self.file.clone();
+        let slot_sum = file.read().unwrap().get_page_slot_sum();
+        let shift_index = if self.curr_position == 0 {
+            self.curr_position
+        } else {
+            self.curr_position - 1
+        };  // to stay in the same page as the last get_next()
+        let mut page_index = shift_index / slot_sum;
+        let mut tuple_index = self.curr_position - slot_sum * page_index;
+        let index = self.find_page_helper(&mut page_index, &mut tuple_index);
+        let result = match index {
+            Some(position) => Some((
+                self.table_manager.read().unwrap().file_manager.get_loaded_tuple_data(
+                    &self.file, position).unwrap(),
+                position
+            )),
+            None => None,
+        };
+        match result {
+            Some((tuple_data, new_position)) => {
+                self.curr_position = new_position + 1;
+                Some(tuple_data)
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+    fn get_error(&self) -> Option<ExecError> { self.error.clone() }
+}
+
+
+#[derive(Debug)]
+pub struct IndexScan {
+    table : String,
+    index : String,
+    key : Vec<TupleValue>,
+    table_manager : TableManagerRef,
+    position_list : Vec<usize>,
+    curr : usize,
+    pinned_page : Option<usize>,
+    file : TableFileRef,
+    finished : bool,
+    error : Option<ExecError>,
+}
+
+impl IndexScan {
+    pub fn new(table : &String, index : &String, key : Vec<TupleValue>,
+            table_manager : &TableManagerRef) -> ExecIterRef {
+        let file = table_manager.read().unwrap().file_manager.get_file(&table);
+        Box::new(IndexScan{
+            table : table.clone(),
+            index : index.clone(),
+            key : key,
+            table_manager : table_manager.clone(),
+            position_list : Vec::new(),
+            curr : 0,
+            pinned_page : None,
+            file : file,
+            finished : false,
+            error : None,
+        })
+    }
+    fn unpin_curr_page(&mut self) {
+        if let Some(page_index) = self.pinned_page.take() {
+            let fd = self.file.read().unwrap().get_fd();
+            self.table_manager.read().unwrap().file_manager.unpin_page(fd, page_index as u32);
+        }
+    }
+}
+
+impl ExecIter for IndexScan {
+    fn open(&mut self) {
+        assert!(!self.finished);
+        // positions are collected first, so the changes made by the caller won't affect the scan
+        self.position_list = self.table_manager.write().unwrap().file_manager.index_lookup(
+            &self.table, &self.index, &self.key);
+    }
+    fn close(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        self.unpin_curr_page();
+    }
+    fn explain(&self) -> String {
+        format!("index scan on {}, key: {:?}", self.index, self.key)
+    }
+    fn get_next(&mut self) -> Option<TupleData> {
+        if self.finished {
+            return None;
+        }
+        if self.curr == self.position_list.len() {
+            self.close();
+            return None;
+        }
+        let position = self.position_list[self.curr];
+        self.curr += 1;
+        let page_index = position / self.file.read().unwrap().get_page_slot_sum();
+        if self.pinned_page != Some(page_index) {
+            self.unpin_curr_page();
+            if let Err(err) = load_and_pin(&self.table_manager, &self.file, page_index) {
+                self.error = Some(err);
+                self.finished = true;  // the last page is unpinned
+                return None;
+            }
+            self.pinned_page = Some(page_index);
+        }
+        self.table_manager.read().unwrap().file_manager.get_loaded_tuple_data(&self.file, position)
+    }
+    fn get_error(&self) -> Option<ExecError> { self.error.clone() }
+}
+
+
+#[derive(Debug)]
+pub struct Filter {
+    data_source : ExecIterRef,
+    condition : CondRef,
+    index_map : IndexMap,
+    tuple_desc : TupleDesc,
+    finished : bool,
+    error : Option<ExecError>,
+}
+
+impl Filter {
+    pub fn new(
+            condition : CondRef,
+            index_map : IndexMap,
+            tuple_desc : TupleDesc,
+            inner_iter : ExecIterRef) -> ExecIterRef {
+        Box::new(Filter{
+            condition : condition,
+            data_source : inner_iter,
+            index_map : index_map,
+            tuple_desc : tuple_desc,
+            finished : false,
+            error : None,
+        })
+    }
+}
+
+impl ExecIter for Filter {
+    fn open(&mut self) {
+        self.data_source.open();
+    }
+    fn close(&mut self) {
+        self.data_source.close();
+        self.finished = true;
+    }
+    fn explain(&self) -> String {
+        format!("filtered by condition: {:?} from source {:?}", self.condition, self.data_source)
+    }
+    fn get_next(&mut self) -> Option<TupleData> {
+        if self.finished {
+            return None;
+        }
+        assert_eq!(self.index_map.len(), self.tuple_desc.attr_desc.len());
+        while let Some(tuple_data) = self.data_source.get_next() {
+            assert_eq!(self.index_map.len(), tuple_data.len());
+            let mut ptr_map = PtrMap::new();
+            for (k, index) in &self.index_map {
+                ptr_map.insert(k.clone(), (
+                    tuple_data[*index],
+                    self.tuple_desc.attr_desc[*index].clone()
+                    ));
+            }
+            match eval_cond(&*self.condition, &ptr_map) {
+                Ok(truth) => if truth.is_true() {
+                    return Some(tuple_data);
+                },
+                Err(err) => {
+                    self.error = Some(err);
+                    break;
+                }
+            }
+        }
+        self.close();
+        None
+    }
+    fn get_error(&self) -> Option<ExecError> {
+        self.error.clone().or_else(|| self.data_source.get_error())
+    }
+}
+
+
+#[derive(Debug)]
+pub struct Projection {
+    data_source : ExecIterRef,
+    proj_attr_index : Vec<usize>,
+    proj_attr_list : Vec<(String, String)>,
+    finished : bool,
+}
+
+impl Projection {
+    pub fn new(
+            attr_index : Vec<usize>,
+            proj_attr_list : Vec<(String, String)>,
+            inner_iter : ExecIterRef) -> ExecIterRef {
+        Box::new(Projection{
+            data_source : inner_iter,
+            proj_attr_index : attr_index,
+            proj_attr_list : proj_attr_list,
+            finished : false,
+        })
+    }
+}
+
+impl ExecIter for Projection {
+    fn open(&mut self) {
+        self.data_source.open();
+    }
+    fn close(&mut self) {
+        self.data_source.close();
+        self.finished = true;
+    }
+    fn explain(&self) -> String {
+        format!("Projection: {:?} from source {:?}", self.proj_attr_list, self.data_source)
+    }
+    fn get_next(&mut self) -> Option<TupleData> {
+        if self.finished {
+            return None;
+        }
+        match self.data_source.get_next() {
+            Some(tuple_data) => {
+                let mut result = Vec::new();
+                for i in &self.proj_attr_index {
+                    result.push(tuple_data[*i]);
+                }
+                Some(result)
+            }
+            None => {
+                self.close();
+                None
+            }
+        }
+    }
+    fn get_error(&self) -> Option<ExecError> { self.data_source.get_error() }
+}
@@ -0,0 +1,46 @@
+use std::boxed::Box;
+use std::option::Option;
+use ::store::table::TableManagerRef;
+use ::store::tuple::TupleData;
+use ::parser::VacuumStatement;
+use super::iter::{ExecIter, ExecIterRef};
+use super::error::ExecError;
+
+
+#[derive(Debug)]
+pub struct Vacuum {
+    stmt : VacuumStatement,
+    finished : bool,
+    error : Option<ExecError>,
+    table_manager : TableManagerRef,
+}
+
+impl Vacuum {
+    pub fn new(stmt : VacuumStatement, table_manager : &TableManagerRef) -> ExecIterRef {
+        Box::new(Vacuum{
+            finished : false,
+            error : None,
+            stmt : stmt,
+            table_manager : table_manager.clone(),
+        })
+    }
+}
+
+impl ExecIter for Vacuum {
+    fn open(&mut self) {}
+    fn close(&mut self) { self.finished = true; }
+    fn explain(&self) -> String {
+        format!("{}", self.stmt)
+    }
+    fn get_next(&mut self) -> Option<TupleData> {
+        if self.finished {
+            return None;
+        }
+        if let Err(err) = self.table_manager.write().unwrap().vacuum(&self.stmt.table) {
+            self.error = Some(ExecError::page_corrupted(err));
+        }
+        self.finished = true;
+        None
+    }
+    fn get_error(&self) -> Option<ExecError> { self.error.clone() }
+}
@@ -1,18 +1,20 @@
-extern crate libc;
-extern crate rustc_serialize;
-extern crate toml;
-extern crate mio;
-extern crate bytes;
-
-#[macro_use]
-mod utils;
-
-mod parser;
-mod store;
-mod exec;
-mod server;
-
-mod test;
-
-pub use server::local_client::LocalClient;
-pub use server::server::run_server;
+extern crate libc;
+extern crate rustc_serialize;
+extern crate toml;
+extern crate mio;
+extern crate slab;
+extern crate bytes;
+
+#[macro_use]
+mod utils;
+
+mod parser;
+mod store;
+mod exec;
+mod server;
+
+mod test;
+
+pub use server::local_client::LocalClient;
+pub use server::server::run_server;
+pub use store::fsck::run_fsck;
@@ -0,0 +1,103 @@
+use std::fmt;
+use std::fmt::{Formatter, Display};
+use std::option::Option::{Some, None};
+use super::lexer::{TokenIter, TokenType};
+use super::compile_error::ErrorList;
+use super::common::{
+    ValueExpr,
+    consume_next_token_with_type,
+    consume_next_token_with_type_list,
+    check_parse_to_end,
+    seq_parse_helper,
+    concat_error_list,
+};
+use super::create_drop::AttributeDeclaration;
+
+
+#[derive(Debug)]
+pub enum AlterAction {
+    AddColumn{ decl : AttributeDeclaration, default : Option<ValueExpr> },
+    DropColumn{ attr : String },
+    RenameColumn{ attr : String, new_name : String },
+    RenameTable{ new_name : String },
+}
+
+impl Display for AlterAction {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        match self {
+            &AlterAction::AddColumn{ref decl, ref default} => match default {
+                &Some(ref value) => write!(f, "add column {} default {}", decl, value),
+                &None => write!(f, "add column {}", decl),
+            },
+            &AlterAction::DropColumn{ref attr} => write!(f, "drop column {}", attr),
+            &AlterAction::RenameColumn{ref attr, ref new_name} =>
+                write!(f, "rename column {} to {}", attr, new_name),
+            &AlterAction::RenameTable{ref new_name} => write!(f, "rename to {}", new_name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AlterStatement {
+    pub table : String,
+    pub action : AlterAction,
+}
+
+impl Display for AlterStatement {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        write!(f, "alter table {} {}", self.table, self.action)
+    }
+}
+
+impl AlterStatement {
+    pub fn parse(it : &mut TokenIter) -> Result<AlterStatement, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Alter));
+        try!(consume_next_token_with_type(it, TokenType::Table));
+        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        let action_tokens = vec![TokenType::AddKeyword, TokenType::Drop, TokenType::Rename];
+        let action = match try!(consume_next_token_with_type_list(it, &action_tokens)).token_type {
+            TokenType::AddKeyword => try!(AlterStatement::parse_add_column(it)),
+            TokenType::Drop => {
+                try!(consume_next_token_with_type(it, TokenType::Column));
+                let attr_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+                AlterAction::DropColumn{ attr : attr_token.value.clone() }
+            }
+            _ => try!(AlterStatement::parse_rename(it)),
+        };
+        match check_parse_to_end(it) {
+            Some(err) => Err(vec![err]),
+            None => Ok(AlterStatement{
+                table : table_token.value.clone(),
+                action : action,
+            }),
+        }
+    }
+    fn parse_add_column(it : &mut TokenIter) -> Result<AlterAction, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Column));
+        let decl = try!(AttributeDeclaration::parse_decl(it));
+        let (default, errs) = seq_parse_helper(AlterStatement::parse_default, it);
+        if let Some(err) = check_parse_to_end(it) {
+            return Err(concat_error_list(vec![vec![err], errs]));
+        }
+        Ok(AlterAction::AddColumn{ decl : decl, default : default })
+    }
+    fn parse_default(it : &mut TokenIter) -> Result<ValueExpr, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Default));
+        ValueExpr::parse(it)
+    }
+    fn parse_rename(it : &mut TokenIter) -> Result<AlterAction, ErrorList> {
+        let rename_tokens = vec![TokenType::Column, TokenType::To];
+        let token = try!(consume_next_token_with_type_list(it, &rename_tokens));
+        if token.token_type == TokenType::To {
+            let name_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+            return Ok(AlterAction::RenameTable{ new_name : name_token.value.clone() });
+        }
+        let attr_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        try!(consume_next_token_with_type(it, TokenType::To));
+        let name_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        Ok(AlterAction::RenameColumn{
+            attr : attr_token.value.clone(),
+            new_name : name_token.value.clone(),
+        })
+    }
+}
@@ -1,161 +1,349 @@
-use std::fmt;
-use std::fmt::{Formatter, Display};
-use std::option::Option::{Some, None};
-use super::lexer::{TokenIter, TokenType};
-use super::compile_error::ErrorList;
-use super::common::{
-    consume_next_token_with_type,
-    consume_next_token_with_type_list,
-    check_parse_to_end,
-    exp_list_to_string,
-    parse_list_helper,
-    seq_parse_helper,
-};
-
-
-#[derive(Debug)]
-pub struct CreateStatement {
-    pub table : String,
-    pub decl_list : AttrDeclList,
-}
-
-impl Display for CreateStatement {
-    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
-        write!(f, "create table {} ({})", self.table, exp_list_to_string(&self.decl_list))
-    }
-}
-
-impl CreateStatement {
-    pub fn parse(it : &mut TokenIter) -> Result<CreateStatement, ErrorList> {
-        try!(consume_next_token_with_type(it, TokenType::Create));
-        try!(consume_next_token_with_type(it, TokenType::Table));
-        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
-        try!(consume_next_token_with_type(it, TokenType::OpenBracket));
-        let decl_list = try!(AttributeDeclaration::parse_list(it));
-        try!(consume_next_token_with_type(it, TokenType::CloseBracket));
-        match check_parse_to_end(it) {
-            Some(err) => Err(vec![err]),
-            None => Ok(CreateStatement {
-                table : table_token.value.clone(),
-                decl_list : decl_list,
-            }),
-        }
-    }
-}
-
-#[derive(Debug)]
-pub enum AttrType {
-    Int,
-    Float,
-    Char{ len : String },
-}
-
-impl Display for AttrType {
-    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
-        match self {
-            &AttrType::Int => write!(f, "Int"),
-            &AttrType::Float => write!(f, "Float"),
-            &AttrType::Char{ ref len } => write!(f, "Char({})", len),
-        }
-    }
-}
-
-impl AttrType {
-    pub fn parse(it : &mut TokenIter) -> Result<AttrType, ErrorList> {
-        let data_type_tokens = vec![TokenType::Int, TokenType::Float, TokenType::Char];
-        let token = try!(consume_next_token_with_type_list(it, &data_type_tokens));
-        match token.token_type {
-            TokenType::Int => Ok(AttrType::Int),
-            TokenType::Float => Ok(AttrType::Float),
-            TokenType::Char => {
-                try!(consume_next_token_with_type(it, TokenType::OpenBracket));
-                let len_token = try!(consume_next_token_with_type(it, TokenType::IntegerLiteral));
-                try!(consume_next_token_with_type(it, TokenType::CloseBracket));
-                Ok(AttrType::Char{ len : len_token.value.clone() })
-            }
-            other => panic!("unexpected token: {:?}", other),
-        }
-    }
-}
-
-pub type AttrDeclList = Vec<AttributeDeclaration>;
-
-#[derive(Debug)]
-pub struct AttributeDeclaration {
-    pub name : String,
-    pub attr_type : AttrType,
-    pub nullable : bool,
-    pub primary : bool,
-}
-
-impl Display for AttributeDeclaration {
-    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
-        let null = if self.nullable {" null"} else {" not null"};
-        let primary = if self.primary {" primary"} else {""};
-        write!(f, "({} {}{}{})", self.name, self.attr_type, null, primary)
-    }
-}
-
-impl AttributeDeclaration {
-    pub fn parse_list(it : &mut TokenIter) -> Result<AttrDeclList, ErrorList> {
-        parse_list_helper(AttributeDeclaration::parse_decl, it)
-    }
-    pub fn parse_decl(it : &mut TokenIter) -> Result<AttributeDeclaration, ErrorList> {
-        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
-        let attr_type = try!(AttrType::parse(it));
-        let nullable = !is_match!(seq_parse_helper(
-            AttributeDeclaration::parse_null_specifier, it), (Some(false), _));
-        let primary = is_match!(seq_parse_helper(
-            AttributeDeclaration::parse_primary, it), (Some(true), _));
-        Ok(AttributeDeclaration{
-            name : table_token.value.clone(),
-            attr_type : attr_type,
-            nullable : nullable,
-            primary : primary,
-        })
-    }
-    fn parse_primary(it : &mut TokenIter) -> Result<bool, ErrorList> {
-        try!(consume_next_token_with_type(it, TokenType::Primary));
-        Ok(true)
-    }
-    fn parse_null_specifier(it : &mut TokenIter) -> Result<bool, ErrorList> {
-        or_parse_combine!(it,
-            AttributeDeclaration::parse_null,
-            AttributeDeclaration::parse_not_null
-        )
-    }
-    fn parse_null(it : &mut TokenIter) -> Result<bool, ErrorList> {
-        try!(consume_next_token_with_type(it, TokenType::Null));
-        Ok(true)
-    }
-    fn parse_not_null(it : &mut TokenIter) -> Result<bool, ErrorList> {
-        try!(consume_next_token_with_type(it, TokenType::Not));
-        try!(consume_next_token_with_type(it, TokenType::Null));
-        Ok(false)
-    }
-}
-
-#[derive(Debug)]
-pub struct DropStatement {
-    pub table : String,
-}
-
-impl Display for DropStatement {
-    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
-        write!(f, "drop table {}", self.table)
-    }
-}
-
-impl DropStatement {
-    pub fn parse(it : &mut TokenIter) -> Result<DropStatement, ErrorList> {
-        try!(consume_next_token_with_type(it, TokenType::Drop));
-        try!(consume_next_token_with_type(it, TokenType::Table));
-        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
-        match check_parse_to_end(it) {
-            Some(err) => Err(vec![err]),
-            None => Ok(DropStatement{
-                table : table_token.value.clone(),
-            })
-        }
-    }
-}
+use std::fmt;
+use std::fmt::{Formatter, Display};
+use std::option::Option::{Some, None};
+use ::store::table::Attr;
+use ::store::table;
+use super::lexer::{TokenIter, TokenType};
+use super::compile_error::ErrorList;
+use super::common::{
+    consume_next_token_with_type,
+    consume_next_token_with_type_list,
+    get_next_token,
+    check_parse_to_end,
+    exp_list_to_string,
+    parse_list_helper,
+    seq_parse_helper,
+};
+
+
+#[derive(Debug)]
+pub struct CreateStatement {
+    pub table : String,
+    pub decl_list : AttrDeclList,
+    pub primary_key : Option<Vec<String>>,  // primary key (a, b)
+}
+
+impl Display for CreateStatement {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        match self.primary_key {
+            Some(ref key) => write!(f, "create table {} ({}, primary key ({}))",
+                self.table, exp_list_to_string(&self.decl_list), key.join(", ")),
+            None => write!(f, "create table {} ({})", self.table, exp_list_to_string(&self.decl_list)),
+        }
+    }
+}
+
+impl CreateStatement {
+    pub fn parse(it : &mut TokenIter) -> Result<CreateStatement, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Create));
+        try!(consume_next_token_with_type(it, TokenType::Table));
+        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        try!(consume_next_token_with_type(it, TokenType::OpenBracket));
+        let mut decl_list = Vec::new();
+        let mut primary_key = None;
+        loop {
+            // at most one primary key constraint among the attribute declarations
+            let token = try!(get_next_token(it));
+            if token.token_type == TokenType::Primary && primary_key.is_none() {
+                primary_key = Some(try!(CreateStatement::parse_primary_key(it)));
+            } else {
+                decl_list.push(try!(AttributeDeclaration::parse_decl(it)));
+            }
+            if consume_next_token_with_type(&mut it.clone(), TokenType::Comma).is_err() {
+                break;
+            }
+            it.next();
+        }
+        try!(consume_next_token_with_type(it, TokenType::CloseBracket));
+        match check_parse_to_end(it) {
+            Some(err) => Err(vec![err]),
+            None => Ok(CreateStatement {
+                table : table_token.value.clone(),
+                decl_list : decl_list,
+                primary_key : primary_key,
+            }),
+        }
+    }
+    fn parse_primary_key(it : &mut TokenIter) -> Result<Vec<String>, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Primary));
+        try!(consume_next_token_with_type(it, TokenType::Key));
+        try!(consume_next_token_with_type(it, TokenType::OpenBracket));
+        let mut key = vec![try!(consume_next_token_with_type(it, TokenType::Identifier)).value.clone()];
+        while try!(consume_next_token_with_type_list(it, &vec![TokenType::Comma, TokenType::CloseBracket]))
+                .token_type == TokenType::Comma {
+            key.push(try!(consume_next_token_with_type(it, TokenType::Identifier)).value.clone());
+        }
+        Ok(key)
+    }
+    pub fn gen_attr_list(&self) -> Vec<Attr> {
+        // the attributes in the primary key constraint are primary
+        let mut attr_list : Vec<Attr> = self.decl_list.iter().map(|d| d.gen_attr()).collect();
+        if let Some(ref key) = self.primary_key {
+            for attr in attr_list.iter_mut().filter(|a| key.contains(&a.name)) {
+                attr.primary = true;
+            }
+        }
+        attr_list
+    }
+}
+
+#[derive(Debug)]
+pub enum AttrType {
+    Int,
+    Float,
+    BigInt,
+    Double,
+    Decimal{ precision : String, scale : String },
+    Boolean,
+    Date,
+    Time,
+    Timestamp,
+    Char{ len : String },
+    Varchar{ len : String },
+    Text,
+    Blob,
+}
+
+impl Display for AttrType {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        match self {
+            &AttrType::Int => write!(f, "Int"),
+            &AttrType::Float => write!(f, "Float"),
+            &AttrType::BigInt => write!(f, "BigInt"),
+            &AttrType::Double => write!(f, "Double"),
+            &AttrType::Decimal{ ref precision, ref scale } => write!(f, "Decimal({},{})", precision, scale),
+            &AttrType::Boolean => write!(f, "Boolean"),
+            &AttrType::Date => write!(f, "Date"),
+            &AttrType::Time => write!(f, "Time"),
+            &AttrType::Timestamp => write!(f, "Timestamp"),
+            &AttrType::Char{ ref len } => write!(f, "Char({})", len),
+            &AttrType::Varchar{ ref len } => write!(f, "Varchar({})", len),
+            &AttrType::Text => write!(f, "Text"),
+            &AttrType::Blob => write!(f, "Blob"),
+        }
+    }
+}
+
+impl AttrType {
+    pub fn parse(it : &mut TokenIter) -> Result<AttrType, ErrorList> {
+        let data_type_tokens = vec![TokenType::Int, TokenType::Float, TokenType::BigInt, TokenType::Double,
+            TokenType::Decimal, TokenType::Boolean, TokenType::Date, TokenType::Time, TokenType::Timestamp,
+            TokenType::Char, TokenType::Varchar, TokenType::Text, TokenType::Blob];
+        let token = try!(consume_next_token_with_type_list(it, &data_type_tokens));
+        match token.token_type {
+            TokenType::Int => Ok(AttrType::Int),
+            TokenType::Float => Ok(AttrType::Float),
+            TokenType::BigInt => Ok(AttrType::BigInt),
+            TokenType::Double => Ok(AttrType::Double),
+            TokenType::Decimal => AttrType::parse_decimal(it),
+            TokenType::Boolean => Ok(AttrType::Boolean),
+            TokenType::Date => Ok(AttrType::Date),
+            TokenType::Time => Ok(AttrType::Time),
+            TokenType::Timestamp => Ok(AttrType::Timestamp),
+            TokenType::Char => Ok(AttrType::Char{ len : try!(AttrType::parse_len(it)) }),
+            TokenType::Varchar => Ok(AttrType::Varchar{ len : try!(AttrType::parse_len(it)) }),
+            TokenType::Text => Ok(AttrType::Text),
+            TokenType::Blob => Ok(AttrType::Blob),
+            other => panic!("unexpected token: {:?}", other),
+        }
+    }
+    fn parse_decimal(it : &mut TokenIter) -> Result<AttrType, ErrorList> {
+        // decimal(precision) or decimal(precision, scale), the scale is 0 by default
+        try!(consume_next_token_with_type(it, TokenType::OpenBracket));
+        let precision = try!(consume_next_token_with_type(it, TokenType::IntegerLiteral)).value.clone();
+        let token = try!(consume_next_token_with_type_list(it, &vec![TokenType::Comma, TokenType::CloseBracket]));
+        let scale = if token.token_type == TokenType::Comma {
+            let scale = try!(consume_next_token_with_type(it, TokenType::IntegerLiteral)).value.clone();
+            try!(consume_next_token_with_type(it, TokenType::CloseBracket));
+            scale
+        } else {
+            "0".to_string()
+        };
+        Ok(AttrType::Decimal{ precision : precision, scale : scale })
+    }
+    fn parse_len(it : &mut TokenIter) -> Result<String, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::OpenBracket));
+        let len_token = try!(consume_next_token_with_type(it, TokenType::IntegerLiteral));
+        try!(consume_next_token_with_type(it, TokenType::CloseBracket));
+        Ok(len_token.value.clone())
+    }
+}
+
+pub type AttrDeclList = Vec<AttributeDeclaration>;
+
+#[derive(Debug)]
+pub struct AttributeDeclaration {
+    pub name : String,
+    pub attr_type : AttrType,
+    pub nullable : bool,
+    pub primary : bool,
+}
+
+impl Display for AttributeDeclaration {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        let null = if self.nullable {" null"} else {" not null"};
+        let primary = if self.primary {" primary"} else {""};
+        write!(f, "({} {}{}{})", self.name, self.attr_type, null, primary)
+    }
+}
+
+impl AttributeDeclaration {
+    pub fn parse_list(it : &mut TokenIter) -> Result<AttrDeclList, ErrorList> {
+        parse_list_helper(AttributeDeclaration::parse_decl, it)
+    }
+    pub fn parse_decl(it : &mut TokenIter) -> Result<AttributeDeclaration, ErrorList> {
+        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        let attr_type = try!(AttrType::parse(it));
+        let nullable = !is_match!(seq_parse_helper(
+            AttributeDeclaration::parse_null_specifier, it), (Some(false), _));
+        let primary = is_match!(seq_parse_helper(
+            AttributeDeclaration::parse_primary, it), (Some(true), _));
+        Ok(AttributeDeclaration{
+            name : table_token.value.clone(),
+            attr_type : attr_type,
+            nullable : nullable,
+            primary : primary,
+        })
+    }
+    pub fn gen_attr(&self) -> Attr {
+        Attr{
+            name : self.name.clone(),
+            attr_type : match self.attr_type {
+                AttrType::Int => table::AttrType::Int,
+                AttrType::Float => table::AttrType::Float,
+                AttrType::BigInt => table::AttrType::BigInt,
+                AttrType::Double => table::AttrType::Double,
+                AttrType::Decimal{ref precision, ref scale} => table::AttrType::Decimal{
+                    precision : precision.parse::<usize>().unwrap(),
+                    scale : scale.parse::<usize>().unwrap(),
+                },
+                AttrType::Boolean => table::AttrType::Boolean,
+                AttrType::Date => table::AttrType::Date,
+                AttrType::Time => table::AttrType::Time,
+                AttrType::Timestamp => table::AttrType::Timestamp,
+                AttrType::Char{ref len} => table::AttrType::Char{len : len.parse::<usize>().unwrap()},
+                AttrType::Varchar{ref len} => table::AttrType::Varchar{len : len.parse::<usize>().unwrap()},
+                AttrType::Text => table::AttrType::Text,
+                AttrType::Blob => table::AttrType::Blob,
+            },
+            primary : self.primary,
+            nullable : self.nullable,
+        }
+    }
+    fn parse_primary(it : &mut TokenIter) -> Result<bool, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Primary));
+        Ok(true)
+    }
+    fn parse_null_specifier(it : &mut TokenIter) -> Result<bool, ErrorList> {
+        or_parse_combine!(it,
+            AttributeDeclaration::parse_null,
+            AttributeDeclaration::parse_not_null
+        )
+    }
+    fn parse_null(it : &mut TokenIter) -> Result<bool, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Null));
+        Ok(true)
+    }
+    fn parse_not_null(it : &mut TokenIter) -> Result<bool, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Not));
+        try!(consume_next_token_with_type(it, TokenType::Null));
+        Ok(false)
+    }
+}
+
+#[derive(Debug)]
+pub struct DropStatement {
+    pub table : String,
+}
+
+impl Display for DropStatement {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        write!(f, "drop table {}", self.table)
+    }
+}
+
+impl DropStatement {
+    pub fn parse(it : &mut TokenIter) -> Result<DropStatement, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Drop));
+        try!(consume_next_token_with_type(it, TokenType::Table));
+        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        match check_parse_to_end(it) {
+            Some(err) => Err(vec![err]),
+            None => Ok(DropStatement{
+                table : table_token.value.clone(),
+            })
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateIndexStatement {
+    pub index : String,
+    pub table : String,
+    pub attr : String,
+    pub unique : bool,
+}
+
+impl Display for CreateIndexStatement {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        let unique = if self.unique {"unique "} else {""};
+        write!(f, "create {}index {} on {}({})", unique, self.index, self.table, self.attr)
+    }
+}
+
+impl CreateIndexStatement {
+    pub fn parse(it : &mut TokenIter) -> Result<CreateIndexStatement, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Create));
+        let unique = is_match!(seq_parse_helper(
+            CreateIndexStatement::parse_unique, it), (Some(true), _));
+        try!(consume_next_token_with_type(it, TokenType::Index));
+        let index_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        try!(consume_next_token_with_type(it, TokenType::On));
+        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        try!(consume_next_token_with_type(it, TokenType::OpenBracket));
+        let attr_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        try!(consume_next_token_with_type(it, TokenType::CloseBracket));
+        match check_parse_to_end(it) {
+            Some(err) => Err(vec![err]),
+            None => Ok(CreateIndexStatement{
+                index : index_token.value.clone(),
+                table : table_token.value.clone(),
+                attr : attr_token.value.clone(),
+                unique : unique,
+            }),
+        }
+    }
+    fn parse_unique(it : &mut TokenIter) -> Result<bool, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Unique));
+        Ok(true)
+    }
+}
+
+#[derive(Debug)]
+pub struct DropIndexStatement {
+    pub index : String,
+    pub table : String,
+}
+
+impl Display for DropIndexStatement {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        write!(f, "drop index {} on {}", self.index, self.table)
+    }
+}
+
+impl DropIndexStatement {
+    pub fn parse(it : &mut TokenIter) -> Result<DropIndexStatement, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Drop));
+        try!(consume_next_token_with_type(it, TokenType::Index));
+        let index_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        try!(consume_next_token_with_type(it, TokenType::On));
+        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        match check_parse_to_end(it) {
+            Some(err) => Err(vec![err]),
+            None => Ok(DropIndexStatement{
+                index : index_token.value.clone(),
+                table : table_token.value.clone(),
+            })
+        }
+    }
+}
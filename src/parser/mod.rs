@@ -1,31 +1,37 @@
-pub mod lexer;
-pub mod compile_error;
-
-#[macro_use]
-#[allow(dead_code)]
-pub mod common;
-#[allow(dead_code)]
-pub mod attribute;
-#[allow(dead_code)]
-pub mod condition;
-
-#[allow(dead_code)]
-pub mod select;
-#[allow(dead_code)]
-pub mod update;
-#[allow(dead_code)]
-pub mod insert;
-#[allow(dead_code)]
-pub mod delete;
-#[allow(dead_code)]
-pub mod create_drop;
-#[allow(dead_code)]
-pub mod sem_check;
-#[allow(dead_code)]
-pub mod unimpl;
-
-pub use self::select::SelectStatement;
-pub use self::update::UpdateStatement;
-pub use self::insert::InsertStatement;
-pub use self::delete::DeleteStatement;
-pub use self::create_drop::{CreateStatement, DropStatement};
+pub mod lexer;
+pub mod compile_error;
+
+#[macro_use]
+#[allow(dead_code)]
+pub mod common;
+#[allow(dead_code)]
+pub mod attribute;
+#[allow(dead_code)]
+pub mod condition;
+
+#[allow(dead_code)]
+pub mod select;
+#[allow(dead_code)]
+pub mod update;
+#[allow(dead_code)]
+pub mod insert;
+#[allow(dead_code)]
+pub mod delete;
+#[allow(dead_code)]
+pub mod create_drop;
+#[allow(dead_code)]
+pub mod vacuum;
+#[allow(dead_code)]
+pub mod alter;
+#[allow(dead_code)]
+pub mod sem_check;
+#[allow(dead_code)]
+pub mod unimpl;
+
+pub use self::select::SelectStatement;
+pub use self::update::UpdateStatement;
+pub use self::insert::InsertStatement;
+pub use self::delete::DeleteStatement;
+pub use self::create_drop::{CreateStatement, DropStatement, CreateIndexStatement, DropIndexStatement};
+pub use self::vacuum::VacuumStatement;
+pub use self::alter::AlterStatement;
@@ -1,404 +1,666 @@
-use std::vec::Vec;
-use std::collections::HashSet;
-use super::attribute::AttributeExpr;
-use super::lexer::{Token, TokenRef, TokenType};
-use super::compile_error::{CompileError, CompileErrorType, ErrorList, ErrorRef};
-use super::common::{Statement, ValueExpr, ValueType};
-use super::select::{SelectStatement, GroupbyHaving, SelectExpr, Relation};
-use super::update::UpdateStatement;
-use super::insert::InsertStatement;
-use super::delete::DeleteStatement;
-use super::create_drop::{CreateStatement, DropStatement};
-use super::condition::{ConditionExpr, ArithExpr, CmpOperantExpr, CmpOp};
-use ::store::table::{TableSet, AttrType, Attr};
-
-
-pub type SemResult = Result<(), ErrorList>;
-
-
-pub fn check_sem(statement : &mut Statement, table_set : &TableSet) -> SemResult {
-    match statement {
-        &mut Statement::Select(ref mut stmt) => check_select(stmt, table_set),
-        &mut Statement::Update(ref mut stmt) => check_update(stmt, table_set),
-        &mut Statement::Insert(ref mut stmt) => check_insert(stmt, table_set),
-        &mut Statement::Delete(ref mut stmt) => check_delete(stmt, table_set),
-        &mut Statement::Create(ref stmt) => check_create(stmt, table_set),
-        &mut Statement::Drop(ref stmt) => check_drop(stmt, table_set),
-    }
-}
-
-pub fn check_select(stmt : &mut SelectStatement, table_set : &TableSet) -> SemResult {
-    // join not supported now
-    assert_eq!(stmt.relation_list.len(), 1);
-    let table_name = extract!(&stmt.relation_list[0], &Relation::TableName(ref name), name.clone());
-    try!(check_table_exist(&table_name, table_set));
-
-    if let Some(ref mut cond) = stmt.where_condition {
-        try!(check_condition(cond, table_set, &None));
-    }
-    if let Some(GroupbyHaving{ref mut attr, ref mut having_condition}) = stmt.groupby_having {
-        let (table, attr) = attr.get_attr();
-        try!(check_attr_exist(table, attr, table_set));
-        let group_by_attr = Some((table.clone(), attr.clone()));
-        if let &mut Some(ref mut cond) = having_condition {
-            try!(check_condition(cond, table_set, &group_by_attr));
-        }
-        match stmt.select_expr {
-            SelectExpr::AllAttribute =>
-                return Err(create_error(CompileErrorType::SemSelectAllWithGroupBy,
-                    "can't select all when using group by".to_string())),
-            SelectExpr::AttrList(ref mut attr_list) => {
-                for attr_expr in attr_list {
-                    try!(check_attr(attr_expr, table_set, &group_by_attr));
-                }
-            }
-        }
-        if let Some(ref mut attr) = stmt.order_by_attr {
-            try!(check_attr(attr, table_set, &group_by_attr));
-        }
-    } else {
-        if let SelectExpr::AttrList(ref mut attr_list) = stmt.select_expr {
-            for attr_expr in attr_list {
-                try!(check_attr(attr_expr, table_set, &None));
-            }
-        }
-        if let Some(ref mut attr) = stmt.order_by_attr {
-            try!(check_attr(attr, table_set, &None));
-        }
-    }
-    Ok(())
-}
-
-pub fn check_update(stmt : &mut UpdateStatement, table_set : &TableSet) -> SemResult {
-    try!(check_table_exist(&stmt.table, table_set));
-    if let Some(ref mut cond) = stmt.where_condition {
-        try!(check_condition(cond, table_set, &None));
-    }
-    for assign in &mut stmt.set_list {
-        try!(check_attr_exist(&mut Some(stmt.table.clone()), &mut assign.attr, table_set));
-        let attr = table_set.get_attr(&Some(stmt.table.clone()), &assign.attr).unwrap();
-        if attr.primary {
-            return Err(create_error(CompileErrorType::SemChangePrimaryAttr,
-                format!("can't change primary attribute: {}", attr.name)));
-        }
-        try!(check_assign(&assign.value, &attr));
-    }
-    Ok(())
-}
-
-pub fn check_insert(stmt : &mut InsertStatement, table_set : &TableSet) -> SemResult {
-    try!(check_table_exist(&stmt.table, table_set));
-    let value_list  = &stmt.value_list;
-    let attr_list = table_set.gen_attr_list(&stmt.table);  // table should exist
-    if attr_list.len() != value_list.len() {
-        return Err(create_error(CompileErrorType::SemInvalidInsertValuesNum,
-            format!("invalid insert values number, expected {}, found {}",
-                attr_list.len(), value_list.len())));
-    }
-    for (value, attr) in value_list.iter().zip(attr_list.iter()) {
-        try!(check_assign(value, attr));
-    }
-    Ok(())
-}
-
-pub fn check_assign(value : &ValueExpr, attr : &Attr) -> SemResult {
-    match (value.value_type, attr.attr_type) {
-            (ValueType::Integer, AttrType::Int)
-        | (ValueType::Integer, AttrType::Float)
-        | (ValueType::Float, AttrType::Float) => (),
-        (ValueType::String, AttrType::Char{len}) => {
-            if value.value.len() > len {
-                return Err(create_error(CompileErrorType::SemInvalidInsertCharLen,
-                    format!("invalid char len, expected {}, found {}", len, value.value.len())));
-            }
-        }
-        (ValueType::Null, _) => {
-            if !attr.nullable {
-                return Err(create_error(CompileErrorType::SemAttributeNotNullable,
-                    format!("attribute {} is not nullable", attr.name)));
-            }
-        }
-        (value_type, attr_type) =>
-            return Err(create_error(CompileErrorType::SemInvalidInsertValueType,
-                format!("invalid insert value type, attribute type is {:?}, found {:?}",
-                    attr_type, value_type))),
-    }
-    Ok(())
-}
-
-pub fn check_delete(stmt : &mut DeleteStatement, table_set : &TableSet) -> SemResult {
-    try!(check_table_exist(&stmt.table, table_set));
-    match &mut stmt.where_condition {
-        &mut Some(ref mut cond) => check_condition(cond, table_set, &None),
-        &mut None => Ok(()),
-    }
-}
-
-pub fn check_condition(
-        condition : &mut ConditionExpr,
-        table_set : &TableSet,
-        group_by_attr : &Option<(Option<String>, String)>) -> SemResult {
-    match condition {
-        &mut ConditionExpr::NotExpr{ref mut operant} => check_condition(operant, table_set, &group_by_attr),
-        &mut ConditionExpr::LogicExpr{ref mut lhs, ref mut rhs, .. } => {
-            try!(check_condition(lhs, table_set, &group_by_attr));
-            check_condition(rhs, table_set, &group_by_attr)
-        }
-        &mut ConditionExpr::CmpExpr{ref mut lhs, ref mut rhs, op } => {
-            let must_be_num_type = match op {
-                CmpOp::LT | CmpOp::GT | CmpOp::LE | CmpOp::GE => {
-                    match (lhs.get_type(), rhs.get_type()) {
-                        (ValueType::String, _) | (ValueType::Null, _)
-                        | (_, ValueType::String) | (_, ValueType::Null) => {
-                                return Err(create_error(CompileErrorType::SemInvalidValueType,
-                                    format!("invalid operant type: {} {} {}", lhs, rhs, op)))
-                            }
-                        _ => ()
-                    }
-                    true
-                }
-                CmpOp::EQ | CmpOp::NE => {
-                    match (lhs.get_type(), rhs.get_type()) {
-                        (ValueType::Null, _) | (_, ValueType::Null)  => {
-                            return Err(create_error(CompileErrorType::SemInvalidValueType,
-                                format!("invalid operant type: {} {} {}", lhs, rhs, op)))
-                        }
-                        _ => ()
-                    }
-                    false
-                }
-                CmpOp::Is | CmpOp::IsNot => {
-                    match lhs {
-                        &mut CmpOperantExpr::Arith(ArithExpr::Attr(ref mut attr)) => {
-                            try!(check_is_nullable(attr, table_set));
-                        }
-                        _ => return Err(create_error(CompileErrorType::SemInvalidValueType,
-                            format!("expected attribute or aggregate function\
-                                in the left of `is` and `is not`, found {}", lhs)))
-                    }
-                    match rhs {
-                        &mut CmpOperantExpr::Value(ValueExpr{value_type : ValueType::Null, ..}) => (),
-                        _ => return Err(create_error(CompileErrorType::SemInvalidValueType,
-                            format!("only null is allowd after `is` or `is not`, found {}", rhs)))
-                    }
-                    false
-                }
-            };
-            if let &mut CmpOperantExpr::Arith(ref mut lhs_arith) = lhs {
-                try!(check_arith_expr(lhs_arith, table_set, must_be_num_type, &group_by_attr));
-            }
-            if let &mut CmpOperantExpr::Arith(ref mut rhs_arith) = rhs {
-                try!(check_arith_expr(rhs_arith, table_set, must_be_num_type, &group_by_attr));
-            }
-            Ok(())
-        }
-    }
-}
-
-pub fn check_is_nullable(attr_expr : &mut AttributeExpr, table_set : &TableSet) -> SemResult {
-    let (table, attr) = attr_expr.get_attr();
-    try!(check_attr_exist(table, attr, table_set));
-    if !table_set.get_attr(table, attr).unwrap().nullable {
-        return Err(create_error(CompileErrorType::SemAttributeNotNullable,
-            format!("attribute `{}` is not nullale", attr)));
-    }
-    Ok(())
-}
-
-pub fn check_arith_expr(
-        arith : &mut ArithExpr,
-        table_set : &TableSet,
-        must_be_num_type : bool,
-        group_by_attr : &Option<(Option<String>, String)>) -> SemResult {
-    match arith {
-        &mut ArithExpr::Value(ValueExpr{value_type, ..}) => {
-            // already guranteed by grammar
-            assert!(value_type == ValueType::Integer || value_type == ValueType::Float);
-            Ok(())
-        }
-        &mut ArithExpr::MinusExpr{ref mut operant} => {
-            check_arith_expr(operant, table_set, must_be_num_type, &group_by_attr)
-        }
-        &mut ArithExpr::BinaryExpr{ref mut lhs, ref mut rhs, ..} => {
-            try!(check_arith_expr(lhs, table_set, must_be_num_type, &group_by_attr));
-            check_arith_expr(rhs, table_set, must_be_num_type, &group_by_attr)
-        }
-        &mut ArithExpr::Attr(ref mut attr) => {
-            try!(check_attr(attr, table_set, &group_by_attr));
-            if must_be_num_type {
-                check_attr_num_type(attr, table_set)
-            } else {
-                Ok(())
-            }
-        }
-    }
-}
-
-pub fn check_attr_num_type(attr_expr : &mut AttributeExpr, table_set : &TableSet) -> SemResult {
-    let err_msg = format!("invalid attribute type: {}", attr_expr);
-    let (table, attr) = attr_expr.get_attr();
-    let attr = table_set.get_attr(table, attr).unwrap();
-    if let AttrType::Char{..} = attr.attr_type {
-        return Err(create_error(CompileErrorType::SemInvalidValueType, err_msg));
-    }
-    Ok(())
-}
-
-pub fn check_attr(
-        attr_expr : &mut AttributeExpr,
-        table_set : &TableSet,
-        group_by_attr : &Option<(Option<String>, String)>) -> SemResult {
-    let invalid_aggre_func_use_err_msg = format!("can't use {} in `where`", attr_expr);
-    let should_use_group_by_attr_err_msg =
-        format!("expected group by attribute {:?}, got {}", group_by_attr, attr_expr);
-    let (table, attr) = match attr_expr {
-        &mut AttributeExpr::TableAttr{ref mut table, ref mut attr} => {
-            try!(check_attr_exist(table, attr, table_set));
-            (table, attr)
-        }
-        &mut AttributeExpr::AggreFuncCall{ref func, ref mut table, ref mut attr} => {
-            try!(check_aggre_func_name(func));
-            try!(check_attr_exist(table, attr, table_set));
-            if let &None = group_by_attr {
-                return Err(create_error(CompileErrorType::SemInvalidAggregateFunctionUse,
-                    invalid_aggre_func_use_err_msg));
-            }
-            (table, attr)
-        }
-    };
-    let group_by_attr = match group_by_attr {
-        &Some(ref expr) => expr,
-        &None => return Ok(()),
-    };
-    // unique already guranteed
-    if (!is_match!(group_by_attr.0, None) && !is_match!(table, &mut None) && group_by_attr.0 != *table)
-            || group_by_attr.1 != *attr {
-        return Err(create_error(CompileErrorType::SemShouldUseGroupByAttribute,
-            should_use_group_by_attr_err_msg))
-    }
-    Ok(())
-}
-
-pub fn check_attr_exist(table : &mut Option<String>, attr : &mut String,
-        table_set : &TableSet) -> SemResult {
-    if table_set.get_attr(table, attr).is_some() {
-        table_set.complete_table_name(table, attr);
-        Ok(())
-    } else {
-        Err(create_error(CompileErrorType::SemInvalidAttribute,
-            format!("{} not exist or multiple found", attr)))
-    }
-}
-
-pub fn check_aggre_func_name(name : &String) -> SemResult {
-    let aggre_func_list = ["max", "min", "count", "sum"];
-    if aggre_func_list.into_iter().filter(|s| *name == s.to_string()).next().is_some() {
-        Ok(())
-    } else {
-        Err(create_error(CompileErrorType::SemInvalidAggreFuncName,
-            format!("invalid aggregate function name: {}, expected {:?}", name, aggre_func_list)))
-    }
-}
-
-pub fn check_create(stmt : &CreateStatement, table_set : &TableSet) -> SemResult {
-    try!(check_create_table_exit(stmt, table_set));
-    try!(check_unique_primary(stmt));
-    try!(check_primary_not_null(stmt));
-    try!(check_attr_unique(stmt));
-    Ok(())
-}
-
-pub fn check_create_table_exit(stmt : &CreateStatement, table_set : &TableSet) -> SemResult {
-    if table_set.exist(&stmt.table) {
-        Err(vec![ErrorRef::new(CompileError{
-            error_type : CompileErrorType::SemTableExist,
-            token : dummy_token(),
-            error_msg : format!("table {} already exist", &stmt.table),
-        })])
-    } else {
-        Ok(())
-    }
-}
-
-pub fn check_unique_primary(stmt : &CreateStatement) -> SemResult {
-    let primary_attr_list : Vec<String> =
-        stmt.decl_list.iter().filter(|d| d.primary).map(|d| d.name.clone()).collect();
-    if primary_attr_list.len() == 1 {
-        Ok(())
-    } else if primary_attr_list.is_empty() {
-        Err(vec![ErrorRef::new(CompileError{
-            error_type : CompileErrorType::SemNoPrimary,
-            token : dummy_token(),
-            error_msg : "no primary attribute found".to_string(),
-        })])
-    } else {
-        Err(vec![ErrorRef::new(CompileError{
-            error_type : CompileErrorType::SemMultiplePrimary,
-            token : dummy_token(),
-            error_msg : format!("multiple primary not support: {:?}", primary_attr_list),
-        })])
-    }
-}
-
-pub fn check_primary_not_null(stmt : &CreateStatement) -> SemResult {
-    for decl in stmt.decl_list.iter().filter(|d| d.primary && d.nullable) {
-        return Err(vec![ErrorRef::new(CompileError{
-            error_type : CompileErrorType::SemNullablePrimary,
-            token : dummy_token(),
-            error_msg : format!("primary attribute can't be null: {}", decl.name),
-        })]);
-    }
-    Ok(())
-}
-
-pub fn check_attr_unique(stmt : &CreateStatement) -> SemResult {
-    let mut table_set = HashSet::new();
-    for name in stmt.decl_list.iter().map(|d| &d.name) {
-        if table_set.contains(name) {
-            return Err(vec![ErrorRef::new(CompileError{
-                error_type : CompileErrorType::SemDuplicateAttr,
-                token : dummy_token(),
-                error_msg : format!("duplicate attribute name :{}", name),
-            })])
-        } else {
-            table_set.insert(name);
-        }
-    }
-    Ok(())
-}
-
-pub fn check_drop(stmt : &DropStatement, table_set : &TableSet) -> SemResult {
-    check_table_exist(&stmt.table, table_set)
-}
-
-pub fn check_table_exist(table : &str, table_set : &TableSet) -> SemResult {
-    if table_set.exist(table) {
-        Ok(())
-    } else {
-        Err(vec![ErrorRef::new(CompileError{
-            error_type : CompileErrorType::SemTableNotExist,
-            token : dummy_token(),
-            error_msg : table_not_exist(table),
-        })])
-    }
-}
-
-pub fn table_not_exist(table : &str) -> String {
-    format!("table `{}` not exist", table)
-}
-
-pub fn dummy_token() -> TokenRef {
-    TokenRef::new(Token{
-        column : 0,
-        value : "".to_string(),
-        token_type : TokenType::UnKnown
-    })
-}
-
-pub fn create_error(error_type : CompileErrorType, error_msg : String) -> ErrorList {
-    vec![ErrorRef::new(CompileError{
-            error_type : error_type,
-            token : dummy_token(),
-            error_msg : error_msg,
-        })]
-}
+use std::vec::Vec;
+use std::collections::HashSet;
+use super::attribute::AttributeExpr;
+use super::lexer::{Token, TokenRef, TokenType};
+use super::compile_error::{CompileError, CompileErrorType, ErrorList, ErrorRef};
+use super::common::{Statement, ValueExpr, ValueType};
+use super::select::{SelectStatement, GroupbyHaving, SelectExpr, Relation};
+use super::update::UpdateStatement;
+use super::insert::InsertStatement;
+use super::delete::DeleteStatement;
+use super::create_drop::{CreateStatement, DropStatement, CreateIndexStatement, DropIndexStatement};
+use super::vacuum::VacuumStatement;
+use super::alter::{AlterStatement, AlterAction};
+use super::condition::{ConditionExpr, ArithExpr, CmpOperantExpr, CmpOp};
+use ::store::table::{TableSet, AttrType, Attr};
+use ::store::tuple::{TupleDesc, parse_tuple_value};
+use ::store::file::tuple_fits_in_page;
+use ::utils::decimal::MAX_DECIMAL_PRECISION;
+use ::utils::datetime::{parse_date, parse_time, parse_timestamp};
+
+
+pub type SemResult = Result<(), ErrorList>;
+
+
+pub fn check_sem(statement : &mut Statement, table_set : &TableSet) -> SemResult {
+    match statement {
+        &mut Statement::Select(ref mut stmt) => check_select(stmt, table_set),
+        &mut Statement::Update(ref mut stmt) => check_update(stmt, table_set),
+        &mut Statement::Insert(ref mut stmt) => check_insert(stmt, table_set),
+        &mut Statement::Delete(ref mut stmt) => check_delete(stmt, table_set),
+        &mut Statement::Create(ref stmt) => check_create(stmt, table_set),
+        &mut Statement::Drop(ref stmt) => check_drop(stmt, table_set),
+        &mut Statement::CreateIndex(ref stmt) => check_create_index(stmt, table_set),
+        &mut Statement::DropIndex(ref stmt) => check_drop_index(stmt, table_set),
+        &mut Statement::Vacuum(ref stmt) => check_vacuum(stmt, table_set),
+        &mut Statement::Alter(ref stmt) => check_alter(stmt, table_set),
+    }
+}
+
+pub fn check_select(stmt : &mut SelectStatement, table_set : &TableSet) -> SemResult {
+    // join not supported now
+    assert_eq!(stmt.relation_list.len(), 1);
+    let table_name = extract!(&stmt.relation_list[0], &Relation::TableName(ref name), name.clone());
+    try!(check_table_exist(&table_name, table_set));
+
+    if let Some(ref mut cond) = stmt.where_condition {
+        try!(check_condition(cond, table_set, &None));
+    }
+    if let Some(GroupbyHaving{ref mut attr, ref mut having_condition}) = stmt.groupby_having {
+        let (table, attr) = attr.get_attr();
+        try!(check_attr_exist(table, attr, table_set));
+        try!(check_attr_not_large(table, attr, table_set));
+        let group_by_attr = Some((table.clone(), attr.clone()));
+        if let &mut Some(ref mut cond) = having_condition {
+            try!(check_condition(cond, table_set, &group_by_attr));
+        }
+        match stmt.select_expr {
+            SelectExpr::AllAttribute =>
+                return Err(create_error(CompileErrorType::SemSelectAllWithGroupBy,
+                    "can't select all when using group by".to_string())),
+            SelectExpr::AttrList(ref mut attr_list) => {
+                for attr_expr in attr_list {
+                    try!(check_attr(attr_expr, table_set, &group_by_attr));
+                }
+            }
+        }
+        if let Some(ref mut attr) = stmt.order_by_attr {
+            try!(check_attr(attr, table_set, &group_by_attr));
+            let (table, attr) = attr.get_attr();
+            try!(check_attr_not_large(table, attr, table_set));
+        }
+    } else {
+        if let SelectExpr::AttrList(ref mut attr_list) = stmt.select_expr {
+            for attr_expr in attr_list {
+                try!(check_attr(attr_expr, table_set, &None));
+            }
+        }
+        if let Some(ref mut attr) = stmt.order_by_attr {
+            try!(check_attr(attr, table_set, &None));
+            let (table, attr) = attr.get_attr();
+            try!(check_attr_not_large(table, attr, table_set));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_update(stmt : &mut UpdateStatement, table_set : &TableSet) -> SemResult {
+    try!(check_table_exist(&stmt.table, table_set));
+    if let Some(ref mut cond) = stmt.where_condition {
+        try!(check_condition(cond, table_set, &None));
+    }
+    for assign in &mut stmt.set_list {
+        try!(check_attr_exist(&mut Some(stmt.table.clone()), &mut assign.attr, table_set));
+        let attr = table_set.get_attr(&Some(stmt.table.clone()), &assign.attr).unwrap();
+        try!(check_assign(&assign.value, &attr));
+    }
+    Ok(())
+}
+
+pub fn check_insert(stmt : &mut InsertStatement, table_set : &TableSet) -> SemResult {
+    try!(check_table_exist(&stmt.table, table_set));
+    let value_list  = &stmt.value_list;
+    let attr_list = table_set.gen_attr_list(&stmt.table);  // table should exist
+    if attr_list.len() != value_list.len() {
+        return Err(create_error(CompileErrorType::SemInvalidInsertValuesNum,
+            format!("invalid insert values number, expected {}, found {}",
+                attr_list.len(), value_list.len())));
+    }
+    for (value, attr) in value_list.iter().zip(attr_list.iter()) {
+        try!(check_assign(value, attr));
+    }
+    Ok(())
+}
+
+pub fn check_assign(value : &ValueExpr, attr : &Attr) -> SemResult {
+    match (value.value_type, attr.attr_type) {
+            (ValueType::Integer, AttrType::Int)
+        | (ValueType::Integer, AttrType::BigInt)
+        | (ValueType::Integer, AttrType::Float)
+        | (ValueType::Float, AttrType::Float)
+        | (ValueType::Integer, AttrType::Double)
+        | (ValueType::Float, AttrType::Double)
+        | (ValueType::Integer, AttrType::Decimal{..})
+        | (ValueType::Float, AttrType::Decimal{..}) => {
+            if parse_tuple_value(value, &attr.attr_type).is_none() {
+                return Err(create_error(CompileErrorType::SemNumberOutOfRange,
+                    format!("{} is out of the range of {:?}", value.value, attr.attr_type)));
+            }
+        }
+        (ValueType::String, AttrType::Char{len}) | (ValueType::String, AttrType::Varchar{len}) => {
+            if value.value.len() > len {
+                return Err(create_error(CompileErrorType::SemInvalidInsertCharLen,
+                    format!("invalid char len, expected {}, found {}", len, value.value.len())));
+            }
+        }
+        (ValueType::String, AttrType::Text) | (ValueType::String, AttrType::Blob) => (),
+        (ValueType::Boolean, AttrType::Boolean) => (),
+        (ValueType::Date, AttrType::Date)
+        | (ValueType::Time, AttrType::Time)
+        | (ValueType::Timestamp, AttrType::Timestamp) => try!(check_datetime_literal(value)),
+        (ValueType::Null, _) => {
+            if !attr.nullable {
+                return Err(create_error(CompileErrorType::SemAttributeNotNullable,
+                    format!("attribute {} is not nullable", attr.name)));
+            }
+        }
+        (value_type, attr_type) =>
+            return Err(create_error(CompileErrorType::SemInvalidInsertValueType,
+                format!("invalid insert value type, attribute type is {:?}, found {:?}",
+                    attr_type, value_type))),
+    }
+    Ok(())
+}
+
+pub fn check_delete(stmt : &mut DeleteStatement, table_set : &TableSet) -> SemResult {
+    try!(check_table_exist(&stmt.table, table_set));
+    match &mut stmt.where_condition {
+        &mut Some(ref mut cond) => check_condition(cond, table_set, &None),
+        &mut None => Ok(()),
+    }
+}
+
+pub fn check_condition(
+        condition : &mut ConditionExpr,
+        table_set : &TableSet,
+        group_by_attr : &Option<(Option<String>, String)>) -> SemResult {
+    match condition {
+        &mut ConditionExpr::NotExpr{ref mut operant} => check_condition(operant, table_set, &group_by_attr),
+        &mut ConditionExpr::LogicExpr{ref mut lhs, ref mut rhs, .. } => {
+            try!(check_condition(lhs, table_set, &group_by_attr));
+            check_condition(rhs, table_set, &group_by_attr)
+        }
+        &mut ConditionExpr::CmpExpr{ref mut lhs, ref mut rhs, op } => {
+            if !is_match!(op, CmpOp::Is) && !is_match!(op, CmpOp::IsNot)
+                    && try!(check_typed_cmp(lhs, rhs, table_set, &group_by_attr)) {
+                return Ok(());
+            }
+            let must_be_num_type = match op {
+                CmpOp::LT | CmpOp::GT | CmpOp::LE | CmpOp::GE => {
+                    match (lhs.get_type(), rhs.get_type()) {
+                        (ValueType::String, _) | (ValueType::Null, _)
+                        | (_, ValueType::String) | (_, ValueType::Null) => {
+                                return Err(create_error(CompileErrorType::SemInvalidValueType,
+                                    format!("invalid operant type: {} {} {}", lhs, rhs, op)))
+                            }
+                        _ => ()
+                    }
+                    true
+                }
+                CmpOp::EQ | CmpOp::NE => {
+                    match (lhs.get_type(), rhs.get_type()) {
+                        (ValueType::Null, _) | (_, ValueType::Null)  => {
+                            return Err(create_error(CompileErrorType::SemInvalidValueType,
+                                format!("invalid operant type: {} {} {}", lhs, rhs, op)))
+                        }
+                        _ => ()
+                    }
+                    false
+                }
+                CmpOp::Is | CmpOp::IsNot => {
+                    match lhs {
+                        &mut CmpOperantExpr::Arith(ArithExpr::Attr(ref mut attr)) => {
+                            try!(check_is_nullable(attr, table_set));
+                        }
+                        _ => return Err(create_error(CompileErrorType::SemInvalidValueType,
+                            format!("expected attribute or aggregate function\
+                                in the left of `is` and `is not`, found {}", lhs)))
+                    }
+                    match rhs {
+                        &mut CmpOperantExpr::Value(ValueExpr{value_type : ValueType::Null, ..}) => (),
+                        _ => return Err(create_error(CompileErrorType::SemInvalidValueType,
+                            format!("only null is allowd after `is` or `is not`, found {}", rhs)))
+                    }
+                    false
+                }
+            };
+            if let &mut CmpOperantExpr::Arith(ref mut lhs_arith) = lhs {
+                try!(check_arith_expr(lhs_arith, table_set, must_be_num_type, &group_by_attr));
+            }
+            if let &mut CmpOperantExpr::Arith(ref mut rhs_arith) = rhs {
+                try!(check_arith_expr(rhs_arith, table_set, must_be_num_type, &group_by_attr));
+            }
+            // texts and blobs can only be checked for null
+            if !is_match!(op, CmpOp::Is) && !is_match!(op, CmpOp::IsNot) {
+                for operant in vec![lhs, rhs] {
+                    if let &mut CmpOperantExpr::Arith(ArithExpr::Attr(ref mut attr_expr)) = operant {
+                        let (table, attr) = attr_expr.get_attr();
+                        try!(check_attr_not_large(table, attr, table_set));
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn check_typed_cmp(
+        lhs : &mut CmpOperantExpr,
+        rhs : &mut CmpOperantExpr,
+        table_set : &TableSet,
+        group_by_attr : &Option<(Option<String>, String)>) -> Result<bool, ErrorList> {
+    // numbers can only be compared with numbers, strings with strings,
+    // and booleans, dates and times with the same type,
+    // return true if both sides are booleans, dates or times which need no more check
+    let lhs_type = try!(get_operant_type(lhs, table_set, group_by_attr));
+    let rhs_type = try!(get_operant_type(rhs, table_set, group_by_attr));
+    match (lhs_type, rhs_type) {
+        (Some(l), Some(r)) if l != r => Err(create_error(CompileErrorType::SemInvalidValueType,
+            format!("can't compare {} with {}", lhs, rhs))),
+        (Some(ValueType::Boolean), Some(..)) | (Some(ValueType::Date), Some(..))
+        | (Some(ValueType::Time), Some(..)) | (Some(ValueType::Timestamp), Some(..)) => Ok(true),
+        _ => Ok(false),
+    }
+}
+
+fn get_operant_type(
+        operant : &mut CmpOperantExpr,
+        table_set : &TableSet,
+        group_by_attr : &Option<(Option<String>, String)>) -> Result<Option<ValueType>, ErrorList> {
+    // all the numbers are Integer, return None for null, texts and blobs which are checked later
+    let value_type = match operant {
+        &mut CmpOperantExpr::Value(ref value) => {
+            try!(check_datetime_literal(value));
+            value.value_type
+        }
+        &mut CmpOperantExpr::Arith(ArithExpr::Attr(ref mut attr_expr)) => {
+            try!(check_attr(attr_expr, table_set, group_by_attr));
+            if let &mut AttributeExpr::AggreFuncCall{ref func, ..} = attr_expr {
+                if func == "count" {
+                    return Ok(Some(ValueType::Integer));
+                }
+            }
+            let (table, attr) = attr_expr.get_attr();
+            match table_set.get_attr(table, attr).unwrap().attr_type {
+                AttrType::Boolean => ValueType::Boolean,
+                AttrType::Date => ValueType::Date,
+                AttrType::Time => ValueType::Time,
+                AttrType::Timestamp => ValueType::Timestamp,
+                AttrType::Char{..} | AttrType::Varchar{..} => ValueType::String,
+                AttrType::Text | AttrType::Blob => return Ok(None),
+                _ => ValueType::Integer,
+            }
+        }
+        &mut CmpOperantExpr::Arith(..) => ValueType::Integer,
+    };
+    Ok(match value_type {
+        ValueType::Float => Some(ValueType::Integer),
+        ValueType::Null => None,
+        _ => Some(value_type),
+    })
+}
+
+pub fn check_datetime_literal(value : &ValueExpr) -> SemResult {
+    let valid = match value.value_type {
+        ValueType::Date => parse_date(&value.value).is_some(),
+        ValueType::Time => parse_time(&value.value).is_some(),
+        ValueType::Timestamp => parse_timestamp(&value.value).is_some(),
+        _ => true,
+    };
+    if !valid {
+        return Err(create_error(CompileErrorType::SemInvalidDateTime,
+            format!("invalid {:?}: '{}'", value.value_type, value.value)));
+    }
+    Ok(())
+}
+
+pub fn check_is_nullable(attr_expr : &mut AttributeExpr, table_set : &TableSet) -> SemResult {
+    let (table, attr) = attr_expr.get_attr();
+    try!(check_attr_exist(table, attr, table_set));
+    if !table_set.get_attr(table, attr).unwrap().nullable {
+        return Err(create_error(CompileErrorType::SemAttributeNotNullable,
+            format!("attribute `{}` is not nullale", attr)));
+    }
+    Ok(())
+}
+
+pub fn check_arith_expr(
+        arith : &mut ArithExpr,
+        table_set : &TableSet,
+        must_be_num_type : bool,
+        group_by_attr : &Option<(Option<String>, String)>) -> SemResult {
+    match arith {
+        &mut ArithExpr::Value(ValueExpr{value_type, ..}) => {
+            // already guranteed by grammar
+            assert!(value_type == ValueType::Integer || value_type == ValueType::Float);
+            Ok(())
+        }
+        // only numbers can be computed
+        &mut ArithExpr::MinusExpr{ref mut operant} => {
+            check_arith_expr(operant, table_set, true, &group_by_attr)
+        }
+        &mut ArithExpr::BinaryExpr{ref mut lhs, ref mut rhs, ..} => {
+            try!(check_arith_expr(lhs, table_set, true, &group_by_attr));
+            check_arith_expr(rhs, table_set, true, &group_by_attr)
+        }
+        &mut ArithExpr::Attr(ref mut attr) => {
+            try!(check_attr(attr, table_set, &group_by_attr));
+            if must_be_num_type {
+                check_attr_num_type(attr, table_set)
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn check_attr_num_type(attr_expr : &mut AttributeExpr, table_set : &TableSet) -> SemResult {
+    let err_msg = format!("invalid attribute type: {}", attr_expr);
+    let (table, attr) = attr_expr.get_attr();
+    let attr = table_set.get_attr(table, attr).unwrap();
+    if !attr.attr_type.is_num() {
+        return Err(create_error(CompileErrorType::SemInvalidValueType, err_msg));
+    }
+    Ok(())
+}
+
+pub fn check_attr_not_large(table : &Option<String>, attr : &String, table_set : &TableSet) -> SemResult {
+    // texts and blobs can't be compared, sorted or indexed
+    let attr = table_set.get_attr(table, attr).unwrap();
+    if attr.attr_type.is_large() {
+        return Err(create_error(CompileErrorType::SemInvalidValueType,
+            format!("can't compare attribute `{}` of type {:?}", attr.name, attr.attr_type)));
+    }
+    Ok(())
+}
+
+pub fn check_attr(
+        attr_expr : &mut AttributeExpr,
+        table_set : &TableSet,
+        group_by_attr : &Option<(Option<String>, String)>) -> SemResult {
+    let invalid_aggre_func_use_err_msg = format!("can't use {} in `where`", attr_expr);
+    let should_use_group_by_attr_err_msg =
+        format!("expected group by attribute {:?}, got {}", group_by_attr, attr_expr);
+    let (table, attr) = match attr_expr {
+        &mut AttributeExpr::TableAttr{ref mut table, ref mut attr} => {
+            try!(check_attr_exist(table, attr, table_set));
+            (table, attr)
+        }
+        &mut AttributeExpr::AggreFuncCall{ref func, ref mut table, ref mut attr} => {
+            try!(check_aggre_func_name(func));
+            try!(check_attr_exist(table, attr, table_set));
+            if func != "count" {
+                try!(check_attr_not_large(table, attr, table_set));
+            }
+            if let &None = group_by_attr {
+                return Err(create_error(CompileErrorType::SemInvalidAggregateFunctionUse,
+                    invalid_aggre_func_use_err_msg));
+            }
+            (table, attr)
+        }
+    };
+    let group_by_attr = match group_by_attr {
+        &Some(ref expr) => expr,
+        &None => return Ok(()),
+    };
+    // unique already guranteed
+    if (!is_match!(group_by_attr.0, None) && !is_match!(table, &mut None) && group_by_attr.0 != *table)
+            || group_by_attr.1 != *attr {
+        return Err(create_error(CompileErrorType::SemShouldUseGroupByAttribute,
+            should_use_group_by_attr_err_msg))
+    }
+    Ok(())
+}
+
+pub fn check_attr_exist(table : &mut Option<String>, attr : &mut String,
+        table_set : &TableSet) -> SemResult {
+    if table_set.get_attr(table, attr).is_some() {
+        table_set.complete_table_name(table, attr);
+        Ok(())
+    } else {
+        Err(create_error(CompileErrorType::SemInvalidAttribute,
+            format!("{} not exist or multiple found", attr)))
+    }
+}
+
+pub fn check_aggre_func_name(name : &String) -> SemResult {
+    let aggre_func_list = ["max", "min", "count", "sum"];
+    if aggre_func_list.into_iter().filter(|s| *name == s.to_string()).next().is_some() {
+        Ok(())
+    } else {
+        Err(create_error(CompileErrorType::SemInvalidAggreFuncName,
+            format!("invalid aggregate function name: {}, expected {:?}", name, aggre_func_list)))
+    }
+}
+
+pub fn check_create(stmt : &CreateStatement, table_set : &TableSet) -> SemResult {
+    try!(check_create_table_exit(stmt, table_set));
+    try!(check_unique_primary(stmt));
+    try!(check_primary_not_null(stmt));
+    try!(check_attr_unique(stmt));
+    let attr_list = stmt.gen_attr_list();
+    for attr in attr_list.iter() {
+        try!(check_attr_type(attr));
+    }
+    if let Some(attr) = attr_list.iter().find(|a| a.primary && a.attr_type.is_large()) {
+        return Err(create_error(CompileErrorType::SemInvalidValueType,
+            format!("primary attribute can't be {:?}: {}", attr.attr_type, attr.name)));
+    }
+    check_tuple_len(&attr_list, table_set)
+}
+
+pub fn check_attr_type(attr : &Attr) -> SemResult {
+    if let AttrType::Decimal{precision, scale} = attr.attr_type {
+        if precision == 0 || precision > MAX_DECIMAL_PRECISION || scale > precision {
+            return Err(create_error(CompileErrorType::SemInvalidDecimalType,
+                format!("invalid decimal({}, {}) of {}, the precision should be 1 to {} and not less than the scale",
+                    precision, scale, attr.name, MAX_DECIMAL_PRECISION)));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_tuple_len(attr_list : &Vec<Attr>, table_set : &TableSet) -> SemResult {
+    // the longest tuple should fit in a page, texts and blobs longer than their inline space are moved out
+    if !tuple_fits_in_page(&TupleDesc::new(attr_list), table_set.page_size) {
+        return Err(create_error(CompileErrorType::SemTupleTooLong,
+            format!("the tuple can't fit in a page of {} bytes", table_set.page_size)));
+    }
+    Ok(())
+}
+
+pub fn check_create_table_exit(stmt : &CreateStatement, table_set : &TableSet) -> SemResult {
+    if table_set.exist(&stmt.table) {
+        Err(vec![ErrorRef::new(CompileError{
+            error_type : CompileErrorType::SemTableExist,
+            token : dummy_token(),
+            error_msg : format!("table {} already exist", &stmt.table),
+        })])
+    } else {
+        Ok(())
+    }
+}
+
+pub fn check_unique_primary(stmt : &CreateStatement) -> SemResult {
+    if let Some(ref key) = stmt.primary_key {
+        return check_primary_key_constraint(stmt, key);
+    }
+    let primary_attr_list : Vec<String> =
+        stmt.decl_list.iter().filter(|d| d.primary).map(|d| d.name.clone()).collect();
+    if primary_attr_list.len() == 1 {
+        Ok(())
+    } else if primary_attr_list.is_empty() {
+        Err(vec![ErrorRef::new(CompileError{
+            error_type : CompileErrorType::SemNoPrimary,
+            token : dummy_token(),
+            error_msg : "no primary attribute found".to_string(),
+        })])
+    } else {
+        Err(vec![ErrorRef::new(CompileError{
+            error_type : CompileErrorType::SemMultiplePrimary,
+            token : dummy_token(),
+            error_msg : format!("multiple primary not support: {:?}", primary_attr_list),
+        })])
+    }
+}
+
+pub fn check_primary_key_constraint(stmt : &CreateStatement, key : &Vec<String>) -> SemResult {
+    // primary key (a, b) replaces the primary of the attribute declarations
+    if let Some(decl) = stmt.decl_list.iter().find(|d| d.primary) {
+        return Err(create_error(CompileErrorType::SemMultiplePrimary,
+            format!("primary attribute {} can't be declared with the primary key constraint", decl.name)));
+    }
+    let mut name_set = HashSet::new();
+    for name in key.iter() {
+        if stmt.decl_list.iter().all(|d| d.name != *name) {
+            return Err(create_error(CompileErrorType::SemInvalidAttribute,
+                format!("primary key attribute `{}` not exist in table `{}`", name, stmt.table)));
+        }
+        if !name_set.insert(name) {
+            return Err(create_error(CompileErrorType::SemDuplicateAttr,
+                format!("duplicate primary key attribute: {}", name)));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_primary_not_null(stmt : &CreateStatement) -> SemResult {
+    for attr in stmt.gen_attr_list().iter().filter(|a| a.primary && a.nullable) {
+        return Err(vec![ErrorRef::new(CompileError{
+            error_type : CompileErrorType::SemNullablePrimary,
+            token : dummy_token(),
+            error_msg : format!("primary attribute can't be null: {}", attr.name),
+        })]);
+    }
+    Ok(())
+}
+
+pub fn check_attr_unique(stmt : &CreateStatement) -> SemResult {
+    let mut table_set = HashSet::new();
+    for name in stmt.decl_list.iter().map(|d| &d.name) {
+        if table_set.contains(name) {
+            return Err(vec![ErrorRef::new(CompileError{
+                error_type : CompileErrorType::SemDuplicateAttr,
+                token : dummy_token(),
+                error_msg : format!("duplicate attribute name :{}", name),
+            })])
+        } else {
+            table_set.insert(name);
+        }
+    }
+    Ok(())
+}
+
+pub fn check_drop(stmt : &DropStatement, table_set : &TableSet) -> SemResult {
+    check_table_exist(&stmt.table, table_set)
+}
+
+pub fn check_create_index(stmt : &CreateIndexStatement, table_set : &TableSet) -> SemResult {
+    try!(check_table_exist(&stmt.table, table_set));
+    let table = table_set.tables.get(&stmt.table).unwrap();
+    if table.attr_list.iter().all(|a| a.name != stmt.attr) {
+        return Err(create_error(CompileErrorType::SemInvalidAttribute,
+            format!("attribute `{}` not exist in table `{}`", stmt.attr, stmt.table)));
+    }
+    try!(check_attr_not_large(&Some(stmt.table.clone()), &stmt.attr, table_set));
+    if table.get_index(&stmt.index).is_some() {
+        return Err(create_error(CompileErrorType::SemIndexExist,
+            format!("index {} already exist on table {}", stmt.index, stmt.table)));
+    }
+    Ok(())
+}
+
+pub fn check_drop_index(stmt : &DropIndexStatement, table_set : &TableSet) -> SemResult {
+    try!(check_table_exist(&stmt.table, table_set));
+    let table = table_set.tables.get(&stmt.table).unwrap();
+    if table.get_index(&stmt.index).is_none() {
+        return Err(create_error(CompileErrorType::SemIndexNotExist,
+            format!("index `{}` not exist on table `{}`", stmt.index, stmt.table)));
+    }
+    Ok(())
+}
+
+pub fn check_vacuum(stmt : &VacuumStatement, table_set : &TableSet) -> SemResult {
+    match stmt.table {
+        Some(ref table) => check_table_exist(table, table_set),
+        None => Ok(()),
+    }
+}
+
+pub fn check_alter(stmt : &AlterStatement, table_set : &TableSet) -> SemResult {
+    try!(check_table_exist(&stmt.table, table_set));
+    let table = table_set.tables.get(&stmt.table).unwrap();
+    let find_attr = |name : &String| table.attr_list.iter().find(|a| a.name == *name);
+    match &stmt.action {
+        &AlterAction::AddColumn{ref decl, ref default} => {
+            if find_attr(&decl.name).is_some() {
+                return Err(create_error(CompileErrorType::SemDuplicateAttr,
+                    format!("attribute `{}` already exist in table `{}`", decl.name, stmt.table)));
+            }
+            if decl.primary {
+                return Err(create_error(CompileErrorType::SemChangePrimaryAttr,
+                    format!("can't add primary attribute: {}", decl.name)));
+            }
+            try!(check_attr_type(&decl.gen_attr()));
+            let mut attr_list = table.attr_list.clone();
+            attr_list.push(decl.gen_attr());
+            try!(check_tuple_len(&attr_list, table_set));
+            match default {
+                &Some(ref value) => try!(check_assign(value, &decl.gen_attr())),
+                &None => if !decl.nullable {
+                    // the existing tuples need a value
+                    return Err(create_error(CompileErrorType::SemAttributeNotNullable,
+                        format!("attribute {} is not nullable and has no default value", decl.name)));
+                },
+            }
+        }
+        &AlterAction::DropColumn{ref attr} => {
+            match find_attr(attr) {
+                None => return Err(create_error(CompileErrorType::SemInvalidAttribute,
+                    format!("attribute `{}` not exist in table `{}`", attr, stmt.table))),
+                Some(a) if a.primary => return Err(create_error(CompileErrorType::SemChangePrimaryAttr,
+                    format!("can't drop primary attribute: {}", attr))),
+                Some(..) => (),
+            }
+        }
+        &AlterAction::RenameColumn{ref attr, ref new_name} => {
+            if find_attr(attr).is_none() {
+                return Err(create_error(CompileErrorType::SemInvalidAttribute,
+                    format!("attribute `{}` not exist in table `{}`", attr, stmt.table)));
+            }
+            if find_attr(new_name).is_some() {
+                return Err(create_error(CompileErrorType::SemDuplicateAttr,
+                    format!("attribute `{}` already exist in table `{}`", new_name, stmt.table)));
+            }
+        }
+        &AlterAction::RenameTable{ref new_name} => {
+            if table_set.exist(new_name) {
+                return Err(create_error(CompileErrorType::SemTableExist,
+                    format!("table {} already exist", new_name)));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn check_table_exist(table : &str, table_set : &TableSet) -> SemResult {
+    if table_set.exist(table) {
+        Ok(())
+    } else {
+        Err(vec![ErrorRef::new(CompileError{
+            error_type : CompileErrorType::SemTableNotExist,
+            token : dummy_token(),
+            error_msg : table_not_exist(table),
+        })])
+    }
+}
+
+pub fn table_not_exist(table : &str) -> String {
+    format!("table `{}` not exist", table)
+}
+
+pub fn dummy_token() -> TokenRef {
+    TokenRef::new(Token{
+        column : 0,
+        value : "".to_string(),
+        token_type : TokenType::UnKnown
+    })
+}
+
+pub fn create_error(error_type : CompileErrorType, error_msg : String) -> ErrorList {
+    vec![ErrorRef::new(CompileError{
+            error_type : error_type,
+            token : dummy_token(),
+            error_msg : error_msg,
+        })]
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+use std::fmt::{Formatter, Display};
+use std::option::Option::{Some, None};
+use super::lexer::{TokenIter, TokenType};
+use super::compile_error::ErrorList;
+use super::common::{
+    consume_next_token_with_type,
+    check_parse_to_end,
+    seq_parse_helper,
+    concat_error_list,
+};
+
+
+#[derive(Debug)]
+pub struct VacuumStatement {
+    pub table : Option<String>,  // all the tables when not specified
+}
+
+impl Display for VacuumStatement {
+    fn fmt(&self, f : &mut Formatter) -> fmt::Result {
+        match self.table {
+            Some(ref table) => write!(f, "vacuum {}", table),
+            None => write!(f, "vacuum"),
+        }
+    }
+}
+
+impl VacuumStatement {
+    pub fn parse(it : &mut TokenIter) -> Result<VacuumStatement, ErrorList> {
+        try!(consume_next_token_with_type(it, TokenType::Vacuum));
+        let (table, errs) = seq_parse_helper(VacuumStatement::parse_table, it);
+        match check_parse_to_end(it) {
+            Some(err) => Err(concat_error_list(vec![vec![err], errs])),
+            None => Ok(VacuumStatement{
+                table : table,
+            })
+        }
+    }
+    pub fn parse_table(it : &mut TokenIter) -> Result<String, ErrorList> {
+        let table_token = try!(consume_next_token_with_type(it, TokenType::Identifier));
+        Ok(table_token.value.clone())
+    }
+}
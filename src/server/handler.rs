@@ -1,132 +1,189 @@
-use std::result::Result;
-use ::parser::common::Statement;
-use ::parser::compile_error::ErrorList;
-use ::parser::lexer::{TokenLine, TokenType};
-use ::parser::sem_check::check_sem;
-use ::parser::unimpl::check_stmt_unimpl;
-use ::store::tuple::TupleData;
-use ::store::table::{TableManagerRef, Table, TableSet, AttrType};
-use ::exec::gen_plan::{gen_table_set, gen_plan};
-use ::exec::gen_plan::gen_proj_info;
-use ::exec::error::ExecError;
-use ::utils::array::projection;
-
-
-pub type ResultHandlerRef = Box<ResultHandler>;
-
-pub trait ResultHandler {
-    fn handle_error(&mut self, err_msg : String);
-    fn handle_tuple_data(&mut self, tuple_data : Option<TupleData>);
-    fn handle_non_query_finished(&mut self);
-    fn set_tuple_info(&mut self, attr_desc : Vec<AttrType>, attr_index : Vec<usize>);
-}
-
-
-fn gen_parse_result(input : &String) -> Result<Statement, ErrorList> {
-    let line = TokenLine::parse(input);
-    if line.errors.len() > 0 {
-        return Err(line.errors);
-    }
-    Statement::parse(&mut line.tokens.iter())
-}
-
-
-pub fn process_table_command(input : &String, manager : &TableManagerRef) -> Result<String, ()> {
-    match input.as_ref() {
-        "show tables" => Ok(show_tables(manager)),
-        _ => Err(()),
-    }
-}
-
-fn show_tables(manager : &TableManagerRef) -> String {
-    manager.borrow().show_tables()
-}
-
-
-pub fn sql_handler(input : &String, result_handler : &mut ResultHandler, manager : &TableManagerRef) {
-    let parse_result = gen_parse_result(input);
-    let mut stmt = match parse_result {
-        Ok(stmt) => stmt,
-        Err(ref err_list) => return result_handler.handle_error(handle_sql_err(err_list)),
-    };
-    if let Err(ref err_list) = check_stmt_unimpl(&stmt) {
-        return result_handler.handle_error(handle_sql_err(err_list));
-    }
-    let table_set = gen_table_set(&stmt, manager);
-    if let Err(ref err_list) = check_sem(&mut stmt, &table_set) {
-        return result_handler.handle_error(handle_sql_err(err_list));
-    }
-
-    match &stmt {
-        &Statement::Select(..) => {
-            let table = get_table(&table_set);
-            let mut attr_desc = table.gen_tuple_desc().attr_desc;
-            let (attr_index, _) = gen_proj_info(&stmt, &manager);
-            attr_desc = projection(&attr_index, attr_desc);
-            result_handler.set_tuple_info(attr_desc, attr_index);
-
-            let mut plan = gen_plan(stmt, manager);
-            plan.open();
-            loop {
-                match plan.get_next() {
-                    Some(tuple_data) => {
-                        result_handler.handle_tuple_data(Some(tuple_data));
-                    }
-                    None => {
-                        if let Some(ref err) = plan.get_error() {
-                            result_handler.handle_error(handle_exec_err(err));
-                        } else {
-                            result_handler.handle_tuple_data(None);
-                        }
-                        break;
-                    }
-                }
-            }
-        }
-        _ => {
-            let mut plan = gen_plan(stmt, manager);
-            plan.open();
-            loop {
-                match plan.get_next() {
-                    Some(..) => continue,
-                    None => break,
-                }
-            }
-            if let Some(ref err) = plan.get_error() {
-                result_handler.handle_error(handle_exec_err(err));
-            } else {
-                result_handler.handle_non_query_finished();
-                manager.borrow_mut().save_to_file();
-            }
-        }
-    }
-}
-
-fn get_table(table_set : &TableSet) -> Table {
-    // only suport one table now
-    assert_eq!(table_set.tables.len(), 1);
-    for (_, t) in table_set.tables.iter() {
-        return t.clone();
-    }
-    panic!("empty table set");
-}
-
-fn handle_sql_err(err_list : &ErrorList) -> String {
-    let mut err_msg = String::new();
-    for err in err_list.iter() {
-        let msg = match err.token.token_type {
-            TokenType::UnKnown =>
-                format!("{:?}: {}", err.error_type, err.error_msg),
-            _ => format!("{:?} column {} `{}`: {}",
-                err.error_type, err.token.column, err.token.value, err.error_msg),
-        };
-        err_msg.push_str(&msg);
-        err_msg.push('\n');
-    }
-    err_msg.pop();  // pop the last \n
-    err_msg
-}
-
-fn handle_exec_err(err : &ExecError) -> String {
-    format!("{:?}: {}", err.error_type, err.error_msg)
-}
+use std::result::Result;
+use ::parser::common::Statement;
+use ::parser::compile_error::ErrorList;
+use ::parser::lexer::{TokenLine, TokenType};
+use ::parser::sem_check::check_sem;
+use ::parser::unimpl::check_stmt_unimpl;
+use ::store::tuple::{TupleData, TupleValue};
+use ::store::overflow::read_overflow_chain;
+use ::store::table::{TableManagerRef, Table, TableSet, AttrType};
+use ::store::lock::LockTarget;
+use ::exec::gen_plan::{gen_table_set, gen_plan, get_table_list};
+use ::exec::gen_plan::gen_proj_info;
+use ::exec::error::ExecError;
+use ::utils::array::projection;
+
+
+pub type ResultHandlerRef = Box<ResultHandler>;
+
+pub trait ResultHandler {
+    fn handle_error(&mut self, err_msg : String);
+    fn handle_tuple_data(&mut self, tuple_data : Option<TupleData>);
+    fn handle_non_query_finished(&mut self);
+    fn set_tuple_info(&mut self, attr_desc : Vec<AttrType>, attr_index : Vec<usize>, reader : LargeValueReader);
+}
+
+
+#[derive(Debug, Clone)]
+pub struct LargeValueReader {
+    // read the text and blob values saved in the overflow pages of the queried table
+    manager : TableManagerRef,
+    table : String,
+}
+
+impl LargeValueReader {
+    pub fn new(manager : &TableManagerRef, table : &String) -> LargeValueReader {
+        LargeValueReader{
+            manager : manager.clone(),
+            table : table.clone(),
+        }
+    }
+    pub fn read_chunks<F>(&self, len : usize, first_page : usize, handle_chunk : F) -> Result<(), String>
+            where F : FnMut(&[u8]) {
+        // the manager is only locked while reading a page, so a long value can be streamed
+        read_overflow_chain(len, first_page, |page_index| {
+            self.manager.write().unwrap().file_manager.read_overflow_page(&self.table, page_index)
+        }, handle_chunk)
+    }
+    pub fn read_value(&self, value : TupleValue) -> Result<TupleValue, String> {
+        match value {
+            TupleValue::Overflow{len, first_page} => {
+                let mut bytes = Vec::with_capacity(len);
+                try!(self.read_chunks(len, first_page, |data| bytes.extend_from_slice(data)));
+                Ok(TupleValue::Bytes(bytes))
+            }
+            value => Ok(value),
+        }
+    }
+}
+
+
+fn gen_parse_result(input : &String) -> Result<Statement, ErrorList> {
+    let line = TokenLine::parse(input);
+    if line.errors.len() > 0 {
+        return Err(line.errors);
+    }
+    Statement::parse(&mut line.tokens.iter())
+}
+
+
+pub fn process_table_command(input : &String, manager : &TableManagerRef) -> Result<String, ()> {
+    match input.as_ref() {
+        "show tables" => Ok(show_tables(manager)),
+        "show buffer status" => Ok(show_buffer_status(manager)),
+        _ => Err(()),
+    }
+}
+
+fn show_tables(manager : &TableManagerRef) -> String {
+    manager.read().unwrap().show_tables()
+}
+
+fn show_buffer_status(manager : &TableManagerRef) -> String {
+    manager.read().unwrap().show_buffer_status()
+}
+
+
+pub fn sql_handler(input : &String, result_handler : &mut ResultHandler, manager : &TableManagerRef) {
+    let parse_result = gen_parse_result(input);
+    let mut stmt = match parse_result {
+        Ok(stmt) => stmt,
+        Err(ref err_list) => return result_handler.handle_error(handle_sql_err(err_list)),
+    };
+    if let Err(ref err_list) = check_stmt_unimpl(&stmt) {
+        return result_handler.handle_error(handle_sql_err(err_list));
+    }
+    // locked before the semantic check, so the tables won't change until the statement finishes
+    let lock_manager = manager.read().unwrap().lock_manager.clone();
+    let _lock = match (&stmt, get_table_list(&stmt)) {
+        (&Statement::Select(..), Some(tables)) => lock_manager.lock_read(tables),
+        (_, Some(tables)) => lock_manager.lock_write(LockTarget::Tables(tables)),
+        (_, None) => lock_manager.lock_write(LockTarget::All),
+    };
+    let table_set = gen_table_set(&stmt, manager);
+    if let Err(ref err_list) = check_sem(&mut stmt, &table_set) {
+        return result_handler.handle_error(handle_sql_err(err_list));
+    }
+
+    match &stmt {
+        &Statement::Select(..) => {
+            let table = get_table(&table_set);
+            let mut attr_desc = table.gen_tuple_desc().attr_desc;
+            let (attr_index, _) = gen_proj_info(&stmt, &manager);
+            attr_desc = projection(&attr_index, attr_desc);
+            let reader = LargeValueReader::new(manager, &table.name);
+            result_handler.set_tuple_info(attr_desc, attr_index, reader);
+
+            let mut plan = gen_plan(stmt, manager);
+            plan.open();
+            loop {
+                match plan.get_next() {
+                    Some(tuple_data) => {
+                        result_handler.handle_tuple_data(Some(tuple_data));
+                    }
+                    None => {
+                        if let Some(ref err) = plan.get_error() {
+                            result_handler.handle_error(handle_exec_err(err));
+                        } else {
+                            result_handler.handle_tuple_data(None);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        _ => {
+            if is_match!(stmt, Statement::Alter(..)) {
+                // the log must not keep the changes of the old files of the table
+                manager.write().unwrap().flush();
+            }
+            manager.write().unwrap().begin_statement();
+            let mut plan = gen_plan(stmt, manager);
+            plan.open();
+            loop {
+                match plan.get_next() {
+                    Some(..) => continue,
+                    None => break,
+                }
+            }
+            if let Some(ref err) = plan.get_error() {
+                manager.write().unwrap().abort_statement();
+                result_handler.handle_error(handle_exec_err(err));
+            } else {
+                manager.write().unwrap().commit_statement();
+                // saved before the client sees the success, so the change of the schema is durable
+                manager.write().unwrap().save_to_file();
+                result_handler.handle_non_query_finished();
+            }
+        }
+    }
+}
+
+fn get_table(table_set : &TableSet) -> Table {
+    // only suport one table now
+    assert_eq!(table_set.tables.len(), 1);
+    for (_, t) in table_set.tables.iter() {
+        return t.clone();
+    }
+    panic!("empty table set");
+}
+
+fn handle_sql_err(err_list : &ErrorList) -> String {
+    let mut err_msg = String::new();
+    for err in err_list.iter() {
+        let msg = match err.token.token_type {
+            TokenType::UnKnown =>
+                format!("{:?}: {}", err.error_type, err.error_msg),
+            _ => format!("{:?} column {} `{}`: {}",
+                err.error_type, err.token.column, err.token.value, err.error_msg),
+        };
+        err_msg.push_str(&msg);
+        err_msg.push('\n');
+    }
+    err_msg.pop();  // pop the last \n
+    err_msg
+}
+
+fn handle_exec_err(err : &ExecError) -> String {
+    format!("{:?}: {}", err.error_type, err.error_msg)
+}
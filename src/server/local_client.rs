@@ -1,86 +1,109 @@
-use std::io::{stdin, stdout};
-use std::io::Write;
-use std::rc::Rc;
-use std::cell::RefCell;
-use ::store::table::TableManager;
-use ::store::tuple::TupleData;
-use ::store::table::AttrType;
-use ::store::tuple::gen_tuple_value;
-use ::utils::config::Config;
-use super::handler::{sql_handler, ResultHandler, process_table_command};
-
-
-#[derive(Debug)]
-pub struct LocalClient;
-
-impl LocalClient {
-    pub fn shell_loop(&mut self) {
-        let config = Config::from_cwd_config();
-        let mut manager = Rc::new(RefCell::new(TableManager::from_json_file(&config)));
-        let mut sql = String::new();
-        let mut line = String::new();
-        let mut process = Process::new();
-        loop {
-            print!("Blastoise> ");
-            stdout().flush().ok();
-            match stdin().read_line(&mut line) {
-                Ok(n) => {
-                    line.pop();  // remove '\n'
-                    if n == 0 { continue }
-                    if line == "q" { break; }
-                    sql.push_str(&line);
-                    if let Some(';') = line.chars().rev().take(1).next() {
-                        sql.pop();  // remove ';'
-                        if let Ok(out) = process_table_command(&sql, &manager) {
-                            println!("{}", out);
-                        } else {
-                            println!("processing {:?}", sql);
-                            sql_handler(&sql, &mut process, &mut manager);
-                            process = Process::new();
-                        }
-                        sql.clear();
-                    }
-                    line.clear();
-                }
-                Err(error) => println!("error: {}", error),
-            }
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Process {
-    attr_desc : Vec<AttrType>,
-    attr_index : Vec<usize>,
-}
-
-impl Process {
-    pub fn new() -> Process {
-        Process{
-            attr_desc : Vec::new(),
-            attr_index : Vec::new(),
-        }
-    }
-}
-
-impl ResultHandler for Process {
-    fn handle_error(&mut self, err_msg : String) {
-        println!("{}", err_msg);
-    }
-    fn handle_tuple_data(&mut self, tuple_data : Option<TupleData>) {
-        match tuple_data {
-            Some(data) => {
-                let value = gen_tuple_value(&self.attr_desc, data);
-                println!("{:?}", value);
-            }
-            None => println!("end"),
-        }
-    }
-    fn set_tuple_info(&mut self, attr_desc : Vec<AttrType>, attr_index : Vec<usize>) {
-        self.attr_desc = attr_desc;
-        self.attr_index = attr_index;
-    }
-    fn handle_non_query_finished(&mut self) {
-        print!("end");
-    }
-}
+use std::io::{stdin, stdout};
+use std::io::Write;
+use std::sync::{Arc, RwLock};
+use ::store::table::TableManager;
+use ::store::tuple::TupleData;
+use ::store::table::AttrType;
+use ::store::tuple::{gen_tuple_value, TupleValue};
+use ::store::policy::{check_policy, DEFAULT_POLICY};
+use ::utils::config::Config;
+use super::handler::{sql_handler, ResultHandler, LargeValueReader, process_table_command};
+
+
+#[derive(Debug)]
+pub struct LocalClient;
+
+impl LocalClient {
+    pub fn shell_loop(&mut self) {
+        let config = Config::from_cwd_config();
+        if let Err(err) = check_policy(&config.get_str_or("buffer_policy", DEFAULT_POLICY)) {
+            println!("{}", err);
+            return;
+        }
+        let mut manager = match TableManager::from_json_file(&config) {
+            Ok(manager) => {
+                if let Some(ref warning) = manager.catalog_warning {
+                    println!("{}", warning);
+                }
+                Arc::new(RwLock::new(manager))
+            }
+            Err(err) => {
+                println!("can't open the database: {}", err);
+                return;
+            }
+        };
+        let mut sql = String::new();
+        let mut line = String::new();
+        let mut process = Process::new();
+        loop {
+            print!("Blastoise> ");
+            stdout().flush().ok();
+            match stdin().read_line(&mut line) {
+                Ok(n) => {
+                    line.pop();  // remove '\n'
+                    if n == 0 { continue }
+                    if line == "q" {
+                        manager.write().unwrap().flush();
+                        break;
+                    }
+                    sql.push_str(&line);
+                    if let Some(';') = line.chars().rev().take(1).next() {
+                        sql.pop();  // remove ';'
+                        if let Ok(out) = process_table_command(&sql, &manager) {
+                            println!("{}", out);
+                        } else {
+                            println!("processing {:?}", sql);
+                            sql_handler(&sql, &mut process, &mut manager);
+                            process = Process::new();
+                        }
+                        sql.clear();
+                    }
+                    line.clear();
+                }
+                Err(error) => println!("error: {}", error),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Process {
+    attr_desc : Vec<AttrType>,
+    attr_index : Vec<usize>,
+    reader : Option<LargeValueReader>,
+}
+
+impl Process {
+    pub fn new() -> Process {
+        Process{
+            attr_desc : Vec::new(),
+            attr_index : Vec::new(),
+            reader : None,
+        }
+    }
+}
+
+impl ResultHandler for Process {
+    fn handle_error(&mut self, err_msg : String) {
+        println!("{}", err_msg);
+    }
+    fn handle_tuple_data(&mut self, tuple_data : Option<TupleData>) {
+        match tuple_data {
+            Some(data) => {
+                let reader = self.reader.as_ref().unwrap();
+                let value : Vec<TupleValue> = gen_tuple_value(&self.attr_desc, data).into_iter()
+                    .map(|v| reader.read_value(v).unwrap_or_else(|err| TupleValue::Char(err))).collect();
+                println!("{:?}", value);
+            }
+            None => println!("end"),
+        }
+    }
+    fn set_tuple_info(&mut self, attr_desc : Vec<AttrType>, attr_index : Vec<usize>, reader : LargeValueReader) {
+        self.attr_desc = attr_desc;
+        self.attr_index = attr_index;
+        self.reader = Some(reader);
+    }
+    fn handle_non_query_finished(&mut self) {
+        print!("end");
+    }
+}
@@ -1,10 +1,10 @@
-#[allow(dead_code)]
-pub mod handler;
-#[allow(dead_code)]
-pub mod local_client;
-#[allow(dead_code)]
-pub mod server;
-#[allow(dead_code)]
-pub mod queue;
-#[allow(dead_code)]
-pub mod buf;
+#[allow(dead_code)]
+pub mod handler;
+#[allow(dead_code)]
+pub mod local_client;
+#[allow(dead_code)]
+pub mod server;
+#[allow(dead_code)]
+pub mod queue;
+#[allow(dead_code)]
+pub mod buf;
@@ -1,127 +1,234 @@
-use std::ptr::null_mut;
-use std::hash::{Hash, Hasher};
-use std::option::Option;
-use std::rc::Rc;
-use std::cell::RefCell;
-use libc::{c_void, free};
-use super::lru::{CacheValue, LruCache};
-use ::utils::libwrapper::alloc_page;
-
-
-pub type DataPtr = *mut c_void;
-
-
-#[derive(Debug, Eq, PartialEq)]
-pub struct PageKey {
-    pub fd : i32,
-    pub page_index : u32,
-}
-
-impl Hash for PageKey {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        let mut n = self.fd as u64;
-        n = n.rotate_left(32);
-        n = n + (self.page_index as u64);
-        n.hash(state);
-    }
-}
-
-
-pub type PageRef = Rc<RefCell<Page>>;
-
-#[derive(Debug)]
-pub struct Page {
-    pub fd : i32,
-    pub page_index : u32,
-    pub data : DataPtr,
-    pub dirty : bool,
-    pub pinned : bool,
-}
-
-impl CacheValue for PageRef {
-    type KeyType = PageKey;
-    fn is_pinned(&self) -> bool {
-        self.borrow().pinned
-    }
-}
-
-impl Page {
-    pub fn new(fd : i32, page_index : u32) -> Page {
-        Page{
-            fd : fd,
-            page_index : page_index,
-            data : null_mut(),
-            dirty : false,
-            pinned : false,
-        }
-    }
-    pub fn alloc(&mut self) {
-        assert!(self.data.is_null());
-        self.data = alloc_page();
-    }
-    pub fn mark_dirty(&mut self) {
-        self.dirty = true;
-    }
-}
-
-impl Drop for Page {
-    fn drop(&mut self) {
-        if self.data.is_null() {
-            return;
-        }
-        unsafe{ free(self.data) };
-    }
-}
-
-
-#[derive(Debug)]
-pub struct PagePool {
-    // should be protected by mutex
-    cache: LruCache<PageRef>,
-    unpinned : usize,
-}
-
-impl PagePool {
-    pub fn new(capacity : usize) -> PagePool {
-        PagePool{
-            cache : LruCache::new(capacity),
-            unpinned : capacity,
-        }
-    }
-    pub fn get_capacity(&self) -> usize {
-        self.cache.capacity
-    }
-    pub fn get_page(&mut self, fd : i32, page_index : u32) -> Option<PageRef> {
-        let key = PageKey{ fd : fd, page_index : page_index };
-        self.cache.get(&key)
-    }
-    pub fn prepare_page(&mut self) -> Option<PageRef> {
-        self.cache.prepare_page()
-    }
-    pub fn remove_tail(&mut self) {
-        self.cache.remove_tail();
-    }
-    pub fn put_page(&mut self, fd : i32, page_index : u32, ptr : DataPtr) {
-        let key = PageKey{ fd : fd, page_index : page_index };
-        let mut new_page = Page::new(fd, page_index);
-        new_page.data = ptr;
-        if ptr.is_null() {
-            new_page.alloc();
-        }
-        self.cache.put(&key, Rc::new(RefCell::new(new_page)));
-    }
-    pub fn pin_page(&mut self, fd : i32, page_index : u32) {
-        assert!(self.unpinned > 0);
-        self.unpinned -= 1;
-        let page = self.get_page(fd, page_index).unwrap();
-        page.borrow_mut().pinned = true;
-    }
-    pub fn unpin_page(&mut self, fd : i32, page_index : u32) {
-        assert!(self.unpinned < self.cache.capacity);
-        self.unpinned += 1;
-        let page = self.get_page(fd, page_index).unwrap();
-        assert!(page.borrow().pinned);
-        page.borrow_mut().pinned = false;
-    }
-    pub fn get_unpinned_num(&self) -> usize { self.unpinned }
-}
+use std::ptr::null_mut;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::option::Option;
+use std::sync::{Arc, RwLock, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use libc::{c_void, free};
+use super::lru::CacheValue;
+use super::policy::{CachePolicyRef, gen_cache_policy, DEFAULT_POLICY, ALL_PINNED};
+use super::format::DEFAULT_PAGE_SIZE;
+use ::utils::libwrapper::alloc_page;
+
+
+pub type DataPtr = *mut c_void;
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PageKey {
+    pub fd : i32,
+    pub page_index : u32,
+}
+
+impl Hash for PageKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut n = self.fd as u64;
+        n = n.rotate_left(32);
+        n = n + (self.page_index as u64);
+        n.hash(state);
+    }
+}
+
+
+// the lock is the latch of the page
+pub type PageRef = Arc<RwLock<Page>>;
+
+#[derive(Debug)]
+pub struct Page {
+    pub fd : i32,
+    pub page_index : u32,
+    pub data : DataPtr,
+    pub dirty : bool,
+    pin_count : AtomicUsize,  // pinned by several scans, maybe in different workers
+}
+
+// the data is owned by the page and only reached through the latch
+unsafe impl Send for Page {}
+unsafe impl Sync for Page {}
+
+impl CacheValue for PageRef {
+    type KeyType = PageKey;
+    fn is_pinned(&self) -> bool {
+        self.read().unwrap().is_pinned()
+    }
+}
+
+impl Page {
+    pub fn new(fd : i32, page_index : u32) -> Page {
+        Page{
+            fd : fd,
+            page_index : page_index,
+            data : null_mut(),
+            dirty : false,
+            pin_count : AtomicUsize::new(0),
+        }
+    }
+    pub fn alloc(&mut self, page_size : usize) {
+        assert!(self.data.is_null());
+        self.data = alloc_page(page_size);
+    }
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+    pub fn is_pinned(&self) -> bool {
+        self.get_pin_count() > 0
+    }
+    pub fn get_pin_count(&self) -> usize {
+        self.pin_count.load(Ordering::SeqCst)
+    }
+    pub fn pin(&self) -> bool {
+        // return true if the page was not pinned before
+        self.pin_count.fetch_add(1, Ordering::SeqCst) == 0
+    }
+    pub fn pin_if_pinned(&self) -> bool {
+        // pin the page only if it's already pinned, which doesn't take another page of the pool
+        let mut count = self.pin_count.load(Ordering::SeqCst);
+        while count > 0 {
+            match self.pin_count.compare_exchange(count, count + 1, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(..) => return true,
+                Err(c) => count = c,
+            }
+        }
+        false
+    }
+    pub fn unpin(&self) -> bool {
+        // return true if the page is not pinned any more
+        let count = self.pin_count.fetch_sub(1, Ordering::SeqCst);
+        assert!(count > 0);
+        count == 1
+    }
+}
+
+impl Drop for Page {
+    fn drop(&mut self) {
+        if self.data.is_null() {
+            return;
+        }
+        unsafe{ free(self.data) };
+    }
+}
+
+
+#[derive(Debug, Default, Clone)]
+pub struct BufferStats {
+    pub hits : usize,
+    pub misses : usize,
+    pub evictions : usize,
+    pub write_backs : usize,  // dirty pages written back by eviction or saving
+}
+
+impl BufferStats {
+    pub fn desc(&self) -> String {
+        format!("hits {}, misses {}, evictions {}, write_backs {}",
+            self.hits, self.misses, self.evictions, self.write_backs)
+    }
+}
+
+
+// shared by all the workers, pages are only replaced by the holder of the
+// table manager's write lock, while pinning only needs the read lock
+#[derive(Debug)]
+pub struct PagePool {
+    cache: Mutex<CachePolicyRef<PageRef>>,
+    capacity : usize,
+    page_size : usize,
+    unpinned : AtomicUsize,
+    stats : Mutex<HashMap<i32, BufferStats>>,  // key is fd, updated by the readers of the table manager too
+}
+
+impl PagePool {
+    pub fn new(capacity : usize) -> PagePool {
+        Self::with_policy(DEFAULT_POLICY, capacity, DEFAULT_PAGE_SIZE)
+    }
+    pub fn with_policy(policy : &str, capacity : usize, page_size : usize) -> PagePool {
+        PagePool{
+            cache : Mutex::new(gen_cache_policy(policy, capacity)),
+            capacity : capacity,
+            page_size : page_size,
+            unpinned : AtomicUsize::new(capacity),
+            stats : Mutex::new(HashMap::new()),
+        }
+    }
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+    pub fn get_page_size(&self) -> usize {
+        self.page_size
+    }
+    pub fn get_load(&self) -> usize {
+        self.cache.lock().unwrap().get_load()
+    }
+    pub fn get_page(&self, fd : i32, page_index : u32) -> Option<PageRef> {
+        let key = PageKey{ fd : fd, page_index : page_index };
+        self.cache.lock().unwrap().get(&key)
+    }
+    pub fn prepare_page(&self) -> Result<Option<PageRef>, String> {
+        self.cache.lock().unwrap().prepare_page()
+    }
+    pub fn remove_victim(&self) {
+        self.cache.lock().unwrap().remove_victim();
+    }
+    pub fn put_page(&self, fd : i32, page_index : u32, ptr : DataPtr) {
+        let key = PageKey{ fd : fd, page_index : page_index };
+        let mut new_page = Page::new(fd, page_index);
+        new_page.data = ptr;
+        if ptr.is_null() {
+            new_page.alloc(self.page_size);
+        }
+        self.cache.lock().unwrap().put(&key, Arc::new(RwLock::new(new_page)));
+    }
+    pub fn remove_page(&self, fd : i32, page_index : u32) -> Option<PageRef> {
+        // drop the page without writing back, the page should not be pinned
+        let key = PageKey{ fd : fd, page_index : page_index };
+        let page = self.cache.lock().unwrap().remove(&key);
+        if let Some(ref page) = page {
+            assert!(!page.read().unwrap().is_pinned());
+        }
+        page
+    }
+    pub fn pin_page(&self, fd : i32, page_index : u32) -> Result<(), String> {
+        // one page is always left unpinned, so that the holder of the write lock
+        // can still load the index pages
+        let page = self.get_page(fd, page_index).unwrap();
+        let page = page.read().unwrap();
+        if page.pin_if_pinned() {
+            return Ok(());
+        }
+        let mut unpinned = self.unpinned.load(Ordering::SeqCst);
+        loop {
+            if unpinned <= 1 {
+                return Err(ALL_PINNED.to_string());
+            }
+            match self.unpinned.compare_exchange(unpinned, unpinned - 1, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(..) => break,
+                Err(n) => unpinned = n,
+            }
+        }
+        if !page.pin() {
+            // pinned by another scan in the meantime
+            self.unpinned.fetch_add(1, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+    pub fn unpin_page(&self, fd : i32, page_index : u32) {
+        let page = self.get_page(fd, page_index).unwrap();
+        if page.read().unwrap().unpin() {
+            let unpinned = self.unpinned.fetch_add(1, Ordering::SeqCst);
+            assert!(unpinned < self.capacity);
+        }
+    }
+    pub fn get_unpinned_num(&self) -> usize { self.unpinned.load(Ordering::SeqCst) }
+    pub fn get_stats(&self, fd : i32) -> BufferStats {
+        self.stats.lock().unwrap().get(&fd).cloned().unwrap_or_default()
+    }
+    pub fn update_stats<F : FnOnce(&mut BufferStats)>(&self, fd : i32, f : F) {
+        f(self.stats.lock().unwrap().entry(fd).or_insert_with(BufferStats::default));
+    }
+    pub fn remove_stats(&self, fd : i32) {
+        // the fd may be reused by another file
+        self.stats.lock().unwrap().remove(&fd);
+    }
+}
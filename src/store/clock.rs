@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::vec::Vec;
+use std::option::Option::{Some, None};
+use super::lru::CacheValue;
+use super::policy::{CachePolicy, ALL_PINNED};
+
+
+#[derive(Debug)]
+struct Slot<ValueType : CacheValue> {
+    key : ValueType::KeyType,
+    value : ValueType,
+    referenced : bool,
+}
+
+// second chance: the hand skips and clears the referenced slots
+#[derive(Debug)]
+pub struct ClockCache<ValueType : CacheValue> {
+    capacity : usize,
+    hash_map : HashMap<ValueType::KeyType, usize>,
+    slots : Vec<Option<Slot<ValueType>>>,
+    hand : usize,
+}
+
+impl<ValueType : CacheValue> ClockCache<ValueType> {
+    pub fn new(capacity : usize) -> ClockCache<ValueType> {
+        assert!(capacity > 0);
+        let mut slots = Vec::with_capacity(capacity);
+        for _ in 0..capacity {
+            slots.push(None);
+        }
+        ClockCache{
+            capacity : capacity,
+            hash_map : HashMap::with_capacity(capacity),
+            slots : slots,
+            hand : 0,
+        }
+    }
+    fn advance(&mut self) {
+        self.hand = (self.hand + 1) % self.capacity;
+    }
+}
+
+impl<ValueType : CacheValue> CachePolicy<ValueType> for ClockCache<ValueType> {
+    fn capacity(&self) -> usize { self.capacity }
+    fn get_load(&self) -> usize { self.hash_map.len() }
+    fn get(&mut self, key : &ValueType::KeyType) -> Option<ValueType> {
+        let i = match self.hash_map.get(key) {
+            Some(i) => *i,
+            None => return None,
+        };
+        let slot = self.slots[i].as_mut().unwrap();
+        slot.referenced = true;
+        Some(slot.value.clone())
+    }
+    fn put(&mut self, key : &ValueType::KeyType, value : ValueType) {
+        assert!(self.hash_map.get(key).is_none());
+        // the slot under the hand is the one just replaced
+        let i = (0..self.capacity).map(|i| (self.hand + i) % self.capacity)
+            .find(|i| self.slots[*i].is_none()).unwrap();
+        self.slots[i] = Some(Slot{
+            key : key.clone(),
+            value : value,
+            referenced : true,
+        });
+        self.hash_map.insert(key.clone(), i);
+    }
+    fn remove(&mut self, key : &ValueType::KeyType) -> Option<ValueType> {
+        match self.hash_map.remove(key) {
+            Some(i) => self.slots[i].take().map(|slot| slot.value),
+            None => None,
+        }
+    }
+    fn prepare_page(&mut self) -> Result<Option<ValueType>, String> {
+        if self.hash_map.len() < self.capacity {
+            return Ok(None);
+        }
+        // the referenced bits are all cleared after the first round
+        for _ in 0..2 * self.capacity {
+            let hand = self.hand;
+            let slot = self.slots[hand].as_mut().unwrap();
+            if !slot.value.is_pinned() {
+                if !slot.referenced {
+                    return Ok(Some(slot.value.clone()));
+                }
+                slot.referenced = false;
+            }
+            self.advance();
+        }
+        Err(ALL_PINNED.to_string())
+    }
+    fn remove_victim(&mut self) {
+        // call this function if returned value of prepare_page is not None
+        let slot = self.slots[self.hand].take().unwrap();
+        assert!(!slot.value.is_pinned());
+        assert!(self.hash_map.remove(&slot.key).is_some());
+    }
+}
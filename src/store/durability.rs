@@ -0,0 +1,33 @@
+use ::utils::config::Config;
+
+
+// When the changes are forced to the disk by fsync.
+// Without fsync the changes are still safe when only the server is killed,
+// but may be lost or torn when the machine crashes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Durability {
+    None,  // never fsync
+    PerStatement,  // fsync the log when a statement commits, and the files after every statement
+    Periodic{ interval : u64 },  // fsync at most once every interval milliseconds
+}
+
+pub const DEFAULT_DURABILITY : &'static str = "per_statement";
+pub const DEFAULT_DURABILITY_INTERVAL : i64 = 1000;
+
+impl Durability {
+    pub fn from_config(config : &Config) -> Durability {
+        match &config.get_str_or("durability", DEFAULT_DURABILITY)[..] {
+            "none" => Durability::None,
+            "per_statement" => Durability::PerStatement,
+            "periodic" => {
+                let interval = config.get_int_or("durability_interval", DEFAULT_DURABILITY_INTERVAL);
+                assert!(interval > 0, "invalid durability_interval: {}", interval);
+                Durability::Periodic{ interval : interval as u64 }
+            }
+            durability => panic!("invalid durability: {}", durability),
+        }
+    }
+    pub fn need_sync(&self) -> bool {
+        *self != Durability::None
+    }
+}
@@ -1,611 +1,1890 @@
-use std::collections::HashMap;
-use std::mem::size_of;
-use std::ptr::{write, read, write_bytes, null_mut};
-use std::fs::{OpenOptions, File};
-use std::os::unix::io::AsRawFd;
-use std::slice::{from_raw_parts, from_raw_parts_mut};
-use std::io::{Read, Write, Seek, SeekFrom};
-use std::rc::Rc;
-use std::cell::RefCell;
-use ::utils::libwrapper::get_page_size;
-use ::utils::pointer::{read_string, write_string, pointer_offset};
-use ::utils::config::Config;
-use ::utils::file::{path_join, ensure_dir_exist, assert_file_exist};
-use ::parser::common::{ValueList, ValueType};
-use super::buffer::{DataPtr, PageRef, PagePool};
-use super::table::{TableRef, AttrType, IndexMap};
-use super::tuple::{TupleDesc, TupleValue, TupleData};
-
-
-#[derive(Debug)]
-pub struct PageHeader {
-    pub slot_sum : usize,
-    pub first_free_slot : usize,
-    pub data : DataPtr,
-}
-
-impl PageHeader {
-    pub fn save_to_page_data(&mut self) {
-        unsafe{
-            write::<u32>(self.data as *mut u32, self.slot_sum as u32);
-            let next_data_ptr = pointer_offset(self.data, size_of::<u32>());
-            write::<u32>(next_data_ptr as *mut u32, self.first_free_slot as u32);
-        }
-    }
-
-    pub fn init_from_page_data(&mut self) {
-        unsafe{
-            let slot_sum = read::<u32>(self.data as *const u32) as usize;
-            assert_eq!(slot_sum, self.slot_sum);
-            let next_data_ptr = pointer_offset(self.data, size_of::<u32>());
-            self.first_free_slot = read::<u32>(next_data_ptr as *const u32) as usize;
-        }
-    }
-}
-
-#[derive(Debug)]
-pub struct BitMap {
-    pub data : DataPtr,
-    pub slot_sum : usize,
-}
-
-impl BitMap {
-    pub fn next_tuple_index(&self, from : usize) -> usize {
-        if from >= self.slot_sum {
-            return self.slot_sum;
-        }
-        let mut count = from / 8;
-        let mut bit_count = from % 8;
-        while count < (self.slot_sum + 7) / 8 {
-            let n = unsafe{ read::<u8>((self.data as *const u8).offset(count as isize)) };
-            let mut mask = 1 << bit_count;
-            if n < mask { count += 1; bit_count = 0; continue; }
-            loop {
-                // assume the bits whose index > slot_sum is 0
-                if mask & n > 0 {
-                    return count * 8 + bit_count;
-                }
-                mask *= 2;
-                bit_count += 1;
-            }
-        }
-        self.slot_sum
-    }
-    pub fn get_first_free_slot(&self) -> usize {
-        let mut count = 0;
-        while count < (self.slot_sum + 7) / 8 {
-            let n = unsafe{ read::<u8>((self.data as *const u8).offset(count as isize)) };
-            if n == 255 { count += 1; continue; }
-            let mut mask : u8 = 1;
-            let mut bit_count = 0;
-            loop {
-                // assume the bits whose index > slot_sum is 0
-                if (255 - mask) | n < 255 {
-                    return count * 8 + bit_count;
-                }
-                mask *= 2;
-                bit_count += 1;
-            }
-        }
-        self.slot_sum
-    }
-    pub fn get_byte_size(&self) -> usize {
-        (self.slot_sum + 7) / 8
-    }
-    pub fn clean(&mut self) {
-        unsafe{
-            write_bytes(self.data, 0, self.get_byte_size());
-        }
-    }
-    pub fn set_inuse(&mut self, index : usize, inuse : bool) {
-        assert!(index < self.slot_sum);
-        let byte_offset = index / 8;
-        let bit_offset = index % 8;
-        let n = 1 << bit_offset;
-        unsafe{
-            let p = pointer_offset(self.data, byte_offset);
-            let a = read::<u8>(p as *const u8);
-            let b = if inuse {
-                a | n
-            } else {
-                a & (255 - n)
-            };
-            write::<u8>(p as *mut u8, b);
-        }
-    }
-    pub fn is_inuse(&self, index : usize) -> bool {
-        assert!(index < self.slot_sum);
-        let byte_offset = index / 8;
-        let bit_offset = index % 8;
-        let n = 1 << bit_offset;
-        unsafe{
-            let p = pointer_offset(self.data, byte_offset);
-            let a = read::<u8>(p as *const u8);
-            (a & n) > 0
-        }
-    }
-}
-
-#[derive(Debug)]
-pub struct FilePage {
-    pub header : PageHeader,
-    pub bitmap : BitMap,
-    pub tuple_data : DataPtr,
-    pub mem_page : PageRef,
-    pub tuple_len : usize,
-}
-
-impl FilePage {
-    pub fn new(mem_page : PageRef, tuple_len : usize) -> FilePage {
-        let data = mem_page.borrow_mut().data;
-        let header_size = 2 * size_of::<u32>();  // PageHeader
-        let slot_sum = get_slot_sum(tuple_len);
-        let bitmap_data = unsafe{ data.offset(header_size as isize) };
-        let bitmap_size = (slot_sum + 7) / 8;
-        let tuple_data = unsafe{ bitmap_data.offset(bitmap_size as isize) };
-        FilePage{
-            header : PageHeader{
-                slot_sum : slot_sum,
-                first_free_slot : 0,
-                data : data,
-            },
-            bitmap : BitMap{
-                data : bitmap_data,
-                slot_sum : slot_sum,
-            },
-            tuple_data : tuple_data,
-            mem_page : mem_page,
-            tuple_len : tuple_len,
-        }
-    }
-    pub fn init_empty_page(&mut self) {
-        self.header.save_to_page_data();
-        self.bitmap.clean();
-    }
-    pub fn init_from_page_data(&mut self) {
-        self.header.init_from_page_data();
-    }
-    pub fn save_to_page(&mut self) {
-        self.header.save_to_page_data();
-    }
-    pub fn is_inuse(&self, index : usize) -> bool {
-        self.bitmap.is_inuse(index)
-    }
-    pub fn set_inuse(&mut self, index : usize, inuse : bool) {
-        self.bitmap.set_inuse(index, inuse);
-    }
-    pub fn insert(&mut self, value_list : &ValueList, tuple_desc : &TupleDesc) {
-        assert!(!self.is_inuse(self.header.first_free_slot));
-        assert_eq!(value_list.len(), tuple_desc.attr_desc.len());
-        assert!(self.header.first_free_slot < self.bitmap.slot_sum);
-
-        let first_free_slot = self.header.first_free_slot;
-        self.set_inuse(first_free_slot, true);
-        self.header.first_free_slot = self.bitmap.get_first_free_slot();
-        self.save_to_page();
-
-        let mut p = unsafe{
-            self.tuple_data.offset(
-                (tuple_desc.tuple_len * first_free_slot) as isize
-                )
-        };
-        for (v, d) in value_list.iter().zip(&tuple_desc.attr_desc) {
-            match (v.value_type, d) {
-                (ValueType::Integer, &AttrType::Int) => {
-                    let n : i32 = v.value.parse::<i32>().unwrap();
-                    unsafe{ write::<i32>(p as *mut i32, n) };
-                    p = pointer_offset(p, 4);
-                }
-                (ValueType::Float, &AttrType::Float) | (ValueType::Integer, &AttrType::Float) => {
-                    let n : f32 = v.value.parse::<f32>().unwrap();
-                    unsafe{ write::<f32>(p as *mut f32, n) };
-                    p = pointer_offset(p, 4);
-                }
-                (ValueType::String, &AttrType::Char{len}) => {
-                    let aligned_len = (len + 3) / 4 * 4;
-                    unsafe{ write_string(p, &v.value, len) };
-                    p = pointer_offset(p, aligned_len);
-                }
-                (ValueType::Null, &AttrType::Int) | (ValueType::Null, &AttrType::Float) => {
-                    unsafe{ write_bytes(p, 0, 4) };
-                    p = pointer_offset(p, 4);
-                }
-                (ValueType::Null, &AttrType::Char{len}) => {
-                    let aligned_len = (len + 3) / 4 * 4;
-                    unsafe{ write_bytes(p, 0, aligned_len) };
-                    p = pointer_offset(p, aligned_len);
-                }
-                _ => panic!("invalid value, expected {:?}, found {:?}", d, v),
-            }
-        }
-    }
-    pub fn get_tuple_value(&self, tuple_index : usize,
-            attr_position : usize,
-            tuple_desc : &TupleDesc) -> TupleValue {
-        assert!(self.is_inuse(tuple_index));
-        let mut p = pointer_offset(self.tuple_data, tuple_index * tuple_desc.tuple_len);
-        p = Self::attr_offset(p, tuple_desc, attr_position);
-        unsafe{
-            match tuple_desc.attr_desc[attr_position] {
-                AttrType::Int => TupleValue::Int(read::<i32>(p as *const i32)),
-                AttrType::Float => TupleValue::Float(read::<f32>(p as *const f32)),
-                AttrType::Char{len} => TupleValue::Char(read_string(p, len)),
-            }
-        }
-    }
-    pub fn get_tuple_data(&self, tuple_index : usize, tuple_desc : &TupleDesc) -> Option<TupleData> {
-        if tuple_index >= self.bitmap.slot_sum {
-            return None;
-        }
-        assert!(self.is_inuse(tuple_index));
-        let mut tuple_data = Vec::new();
-        let data = pointer_offset(self.tuple_data, tuple_index * tuple_desc.tuple_len);
-        let mut p = data;
-        for i in 1..tuple_desc.attr_desc.len() + 1 {
-            tuple_data.push(p);
-            p = Self::attr_offset(data, tuple_desc, i);
-        }
-        Some(tuple_data)
-    }
-    pub fn attr_offset(p : DataPtr, tuple_desc : &TupleDesc, attr_position : usize) -> DataPtr {
-        let mut offset = 0;
-        for (attr_type, _) in tuple_desc.attr_desc.iter().zip(0..attr_position) {
-            match attr_type {
-                &AttrType::Int | &AttrType::Float => offset += 4,
-                &AttrType::Char{len} => offset += (len + 3) / 4 * 4,
-            }
-        }
-        pointer_offset(p, offset)
-    }
-    pub fn is_full(&self) -> bool {
-        self.header.first_free_slot == self.bitmap.slot_sum
-    }
-    pub fn is_in_page(&self, ptr : DataPtr) -> bool {
-        let page_start = self.mem_page.borrow().data;
-        let page_end = pointer_offset(page_start, get_page_size());
-        page_start <= ptr && ptr < page_end
-    }
-    pub fn delete(&mut self, ptr : DataPtr) {
-        let d = ptr as usize - self.tuple_data as usize;
-        let index = d / self.tuple_len;
-        assert!(self.is_inuse(index));
-        self.set_inuse(index, false);
-    }
-}
-
-
-pub type TableFileRef = Rc<RefCell<TableFile>>;
-
-#[derive(Debug)]
-pub struct TableFile {
-    pub saved_name : String,
-    pub file : File,
-    pub loaded_pages : HashMap<usize, FilePage>,
-    pub page_sum : usize,  // including pages not loaded in memory
-    pub table : TableRef,
-    pub first_free_page : usize,
-    pub tuple_desc : TupleDesc,  // for FilePage
-}
-
-impl TableFile {
-    pub fn new(mut name : String, table : TableRef, dir : &String) -> TableFile {
-        name.push_str(".table");
-        name = path_join(dir, &name);
-        let file = OpenOptions::new().read(true).write(true).create(true).open(&name).unwrap();
-        let tuple_desc = table.borrow().gen_tuple_desc();
-        TableFile{
-            saved_name : name,
-            file : file,
-            loaded_pages : HashMap::new(),
-            page_sum : 0,
-            table : table,
-            first_free_page : 0,
-            tuple_desc : tuple_desc,
-        }
-    }
-    pub fn init_from_file(&mut self) {
-        is_match!(self.file.seek(SeekFrom::Start(0)), Ok(..));
-        let mut header = [0 as u32, 0 as u32];
-        is_match!(self.file.read_exact(unsafe{
-            from_raw_parts_mut::<u8>((&mut header).as_ptr() as *mut u8, 8)
-        }), Ok(..));
-        self.page_sum = header[0] as usize;
-        self.first_free_page = header[1] as usize;
-    }
-    pub fn read_page_from_file(&mut self, data : DataPtr, page_index : usize) {
-        assert!(page_index < self.page_sum);
-        let page_size = get_page_size();
-        let offset = page_size * (page_index + 1);
-        is_match!(self.file.seek(SeekFrom::Start(offset as u64)), Ok(..));
-        is_match!(self.file.read_exact(unsafe{
-            from_raw_parts_mut::<u8>(data as *mut u8, page_size)
-        }), Ok(..));
-    }
-    pub fn get_page_slot_sum(&self) -> usize {
-        get_slot_sum(self.tuple_desc.tuple_len)
-    }
-    pub fn save_to_file(&mut self) {
-        // the first page only save header for alignment
-        is_match!(self.file.seek(SeekFrom::Start(0)), Ok(..));
-        let header = [self.page_sum as u32, self.first_free_page as u32];
-        is_match!(self.file.write_all(unsafe{
-            from_raw_parts::<u8>((&header).as_ptr() as *const u8, 8)
-        }), Ok(..));
-        let index_list : Vec<_> = self.loaded_pages.iter().map(|(i, _)| *i).collect();
-        for i in index_list.iter() {
-            self.save_page(*i);
-        }
-    }
-    pub fn save_page(&mut self, page_index : usize) {
-        // the first page only save header for alignment
-        let page_size = get_page_size();
-        let offset = page_size * (page_index + 1);
-        let page = self.loaded_pages.get(&page_index).unwrap();
-        is_match!(self.file.seek(SeekFrom::Start(offset as u64)), Ok(..));
-        is_match!(self.file.write_all(unsafe{
-            from_raw_parts::<u8>(page.mem_page.borrow().data as *const u8, page_size)
-        }), Ok(..));
-    }
-    pub fn delete(&mut self, ptr : DataPtr) {
-        for (_, page) in &mut self.loaded_pages {
-            if page.is_in_page(ptr) {
-                page.delete(ptr);
-                return;
-            }
-        }
-    }
-    pub fn insert(&mut self, value_list : &ValueList) {
-        // must call add_page first if need_new_page() is true
-        let first_free_page = self.first_free_page;
-        self.insert_in_page(first_free_page, value_list)
-    }
-    pub fn insert_in_page(&mut self, page_index : usize, value_list : &ValueList) {
-        // for test
-        assert!(page_index < self.page_sum);
-        let file_page = self.loaded_pages.get_mut(&page_index).unwrap();
-        assert!(!file_page.is_full());
-        file_page.insert(value_list, &self.tuple_desc)
-    }
-    pub fn get_tuple_value(&self, position : usize, attr_position : usize) -> TupleValue {
-        // only for test
-        let page_index = position / self.get_page_slot_sum();
-        let tuple_index = position % self.get_page_slot_sum();
-        assert!(self.loaded_pages.get(&page_index).is_some());
-        let page = self.loaded_pages.get(&page_index).unwrap();
-        page.get_tuple_value(tuple_index, attr_position, &self.tuple_desc)
-    }
-    pub fn get_tuple_data(&self, position : usize) -> Option<TupleData> {
-        let page_index = position / self.get_page_slot_sum();
-        let tuple_index = position % self.get_page_slot_sum();
-        assert!(self.loaded_pages.get(&page_index).is_some());
-        let page = self.loaded_pages.get(&page_index).unwrap();
-        page.get_tuple_data(tuple_index, &self.tuple_desc)
-    }
-    pub fn next_tuple_index(&self, page_index : usize, tuple_index : usize) -> Option<usize> {
-        assert!(self.loaded_pages.get(&page_index).is_some());
-        let page = self.loaded_pages.get(&page_index).unwrap();
-        let next = page.bitmap.next_tuple_index(tuple_index);
-        if next == self.get_page_slot_sum() {
-            None
-        } else {
-            Some(next)
-        }
-    }
-    pub fn add_page(&mut self, mem_page : PageRef) {
-        let file_page = FilePage::new(mem_page, self.tuple_desc.tuple_len);
-        let index = file_page.mem_page.borrow().page_index as usize;
-        self.loaded_pages.insert(index, file_page);
-    }
-    pub fn get_fd(&self) -> i32 {
-        self.file.as_raw_fd()
-    }
-    pub fn is_inuse(&self, page_index : usize, tuple_index : usize) -> bool {
-        assert!(self.loaded_pages.get(&page_index).is_some());
-        let page = self.loaded_pages.get(&page_index).unwrap();
-        page.is_inuse(tuple_index)
-    }
-    pub fn gen_index_map(&self) -> IndexMap {
-        self.table.borrow().gen_index_map()
-    }
-}
-
-
-#[derive(Debug)]
-pub struct TableFileManager {
-    files : HashMap<String, TableFileRef>,  // key is table name
-    pub page_pool : PagePool,
-    table_file_dir : String,
-}
-
-impl TableFileManager {
-    pub fn new(config : &Config) -> TableFileManager {
-        let table_file_dir = config.get_str("table_file_dir");
-        ensure_dir_exist(&table_file_dir);
-        TableFileManager{
-            files : HashMap::new(),
-            page_pool : PagePool::new(config.get_int("max_memory_pool_page_num") as usize),
-            table_file_dir : table_file_dir,
-        }
-    }
-    pub fn init_from_file(&mut self, tables : Vec<TableRef>) {
-        for table in &tables {
-            let table_name = table.borrow().name.clone();
-            let mut file_name = table_name.clone();
-            file_name.push_str(".table");
-            let full_path = path_join(&self.table_file_dir, &file_name);
-            assert_file_exist(&full_path);
-            self.create_file(table_name.clone(), table.clone());
-            self.files.get_mut(&table_name).unwrap().borrow_mut().init_from_file();
-        }
-    }
-    pub fn save_all(&mut self) {
-        for (_, f)  in self.files.iter() {
-            f.borrow_mut().save_to_file();
-        }
-    }
-    pub fn delete(&mut self, table : &String, ptr : DataPtr) {
-        let file = self.get_file(table);
-        file.borrow_mut().delete(ptr);
-    }
-    pub fn insert(&mut self, table : &String, value_list : &ValueList) {
-        let file = self.get_file(table);
-        let is_new_page = self.need_new_page(&file);  // fight the borrow checker, RefCell
-        if is_new_page {
-            let new_page_index = file.borrow().page_sum;
-            self.ensure_page_loaded(&file, new_page_index);
-            file.borrow_mut().loaded_pages.get_mut(&new_page_index).unwrap().init_empty_page();
-        } else {
-            let first_free_page = file.borrow().first_free_page;
-            self.ensure_page_loaded(&file, first_free_page);
-        }
-        file.borrow_mut().insert(value_list);
-    }
-    pub fn insert_in_page(&mut self, table : &String, page_index : usize, value_list : &ValueList) {
-        // for test
-        self.prepare_page(table, page_index);
-        let file = self.get_file(table);
-        file.borrow_mut().insert_in_page(page_index, value_list);
-    }
-    pub fn prepare_page(&mut self, table : &String, page_index : usize) {
-        // for test, will init empty page
-        let file = self.get_file(&table);
-        let page_exist = file.borrow().loaded_pages.get(&page_index).is_some();  // fight borrow checker
-        if !page_exist {
-            self.ensure_page_loaded(&file, page_index);
-            file.borrow_mut().loaded_pages.get_mut(&page_index).unwrap().init_empty_page();
-        }
-    }
-    pub fn need_new_page(&mut self, file : &TableFileRef) -> bool {
-        let page_sum = file.borrow().page_sum;
-        let mut first_free_page;
-        loop {
-            first_free_page = file.borrow().first_free_page;
-            assert!(first_free_page <= page_sum);
-            if first_free_page == page_sum { break; }
-            self.ensure_page_loaded(&file, first_free_page);
-            let is_full = file.borrow().loaded_pages.get(&first_free_page).unwrap().is_full();  // fight borrow checker
-            if is_full {
-                file.borrow_mut().first_free_page += 1;
-            } else {
-                return false;
-            }
-        }
-        true
-    }
-    pub fn get_file(&mut self, table : &String) -> TableFileRef {
-        self.files.get_mut(table).unwrap().clone()
-    }
-    pub fn get_tuple_value(&mut self, table : &String,
-            position : usize,
-            attr_position : usize) -> TupleValue{
-        // only for test
-        let file = self.files.get(table).unwrap().clone();
-        let page_index = {
-            let f = file.borrow_mut();
-            position / f.tuple_desc.tuple_len
-        };
-        self.prepare_page(table, page_index);
-        // declare v only to fight lifetime checker
-        let v = file.borrow().get_tuple_value(position, attr_position);
-        v
-    }
-    pub fn get_tuple_data(&mut self, table : &String, position : usize) -> Option<TupleData> {
-        let file = self.files.get(table).unwrap().clone();
-        let page_index = {
-            let f = file.borrow_mut();
-            position / f.get_page_slot_sum()
-        };
-        self.ensure_page_loaded(&file, page_index);
-        // declare v only to fight lifetime checker
-        let v = file.borrow().get_tuple_data(position);
-        v
-    }
-    pub fn get_next_tuple_data(&mut self, table : &String, from : usize) -> Option<(TupleData, usize)> {
-        match self.get_next_position(table, from) {
-            Some(position) => Some((self.get_tuple_data(table, position).unwrap(), position)),
-            None => None,
-        }
-    }
-    pub fn get_next_position(&mut self, table : &String, from : usize) -> Option<usize> {
-        let file = self.get_file(table);
-        let page_sum = file.borrow().page_sum;
-        let slot_sum = file.borrow().get_page_slot_sum();
-        let mut page_index = from / slot_sum;
-        let mut tuple_index = from % slot_sum;
-        while page_index < page_sum {
-            let next = file.borrow().next_tuple_index(page_index, tuple_index);
-            match next {
-                Some(i) => return Some(page_index * slot_sum + i),
-                None => {
-                   page_index += 1;
-                   tuple_index = 0;
-                }
-            }
-        }
-        None
-    }
-    pub fn ensure_page_loaded(&mut self, file : &TableFileRef, page_index : usize) {
-        let page_sum = file.borrow().page_sum;
-        assert!(page_index < page_sum || page_index == page_sum);  // old page or new page
-        let page_exist = file.borrow().loaded_pages.get(&page_index).is_some();  // fight borrow checker
-        if !page_exist {
-            let fd = file.borrow().get_fd();
-            let mut ptr = null_mut();
-            if let Some(page) = self.page_pool.prepare_page() {
-                // save tail page
-                let old_page_index = page.borrow().page_index;
-                ptr = page.borrow().data;
-                let old_fd = page.borrow().fd;
-                let old_file = self.get_file_by_fd(old_fd);
-                old_file.borrow_mut().save_page(old_page_index as usize);
-                page.borrow_mut().data = null_mut();
-                old_file.borrow_mut().loaded_pages.remove(&(old_page_index as usize));
-                self.page_pool.remove_tail();
-            }
-            self.page_pool.put_page(fd, page_index as u32, ptr);
-            {
-                let page = self.page_pool.get_page(fd, page_index as u32).unwrap();
-                ptr = page.borrow().data.clone();
-            }
-            if page_index < page_sum {
-                file.borrow_mut().read_page_from_file(ptr, page_index);
-                file.borrow_mut().add_page(self.page_pool.get_page(fd, page_index as u32).unwrap());
-                file.borrow_mut().loaded_pages.get_mut(&page_index).unwrap().init_from_page_data();
-            } else {
-                file.borrow_mut().page_sum += 1;
-                file.borrow_mut().add_page(self.page_pool.get_page(fd, page_index as u32).unwrap());
-            }
-        }
-    }
-    pub fn get_file_by_fd(&self, fd : i32) -> TableFileRef {
-        for (_, file) in self.files.iter() {
-            if file.borrow().get_fd() == fd {
-                return file.clone();
-            }
-        }
-        panic!("invalid fd");
-    }
-    pub fn create_file(&mut self, name : String, table : TableRef) {
-        let file = TableFile::new(name.clone(), table, &self.table_file_dir);
-        self.files.insert(name, Rc::new(RefCell::new(file)));
-    }
-    pub fn pin_page(&mut self, fd : i32, page_index : u32) {
-        self.page_pool.pin_page(fd, page_index);
-    }
-    pub fn unpin_page(&mut self, fd : i32, page_index : u32) {
-        self.page_pool.unpin_page(fd, page_index);
-    }
-    pub fn get_unpinned_num(&self) -> usize {
-        self.page_pool.get_unpinned_num()
-    }
-    pub fn get_file_fd(&self, name : &String) -> i32 {
-        self.files.get(name).unwrap().borrow().get_fd()
-    }
-}
-
-fn get_slot_sum(tuple_len : usize) -> usize {
-    let header_size = 2 * size_of::<u32>();  // PageHeader
-    let page_size = get_page_size();
-    // (n + 8 - 1) / 8 + tuple_len * n <= page_size - header_size
-    (8 * (page_size - header_size) - 7) / (8 * tuple_len + 1)
-}
+use std::collections::HashMap;
+use std::cmp::{max, min};
+use std::ptr::{write, read, write_bytes, null_mut};
+use std::fs::{OpenOptions, File, remove_file, rename};
+use std::os::unix::io::AsRawFd;
+use std::slice::{from_raw_parts, from_raw_parts_mut};
+use std::io::{self, Read, Write, Seek, SeekFrom};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use ::utils::pointer::{read_string, write_string, read_varchar, write_varchar, varchar_len,
+    pointer_offset, read_int, write_int, read_float, write_float,
+    read_bigint, write_bigint, read_double, write_double};
+use ::utils::config::Config;
+use ::utils::endian::{read_u16_le, write_u16_le, write_u32_le};
+use ::utils::file::{path_join, ensure_dir_exist, assert_file_exist, file_exist, sync_dir};
+use ::parser::common::ValueList;
+use super::buffer::{DataPtr, PageRef, PagePool};
+use super::table::{TableRef, AttrType, IndexMap, Index};
+use super::tuple::{TupleDesc, TupleValue, TupleData, get_tuple_ptr, attr_len, varchar_space, parse_tuple_value};
+use super::wal::{WalManager, LogRecord, find_losers};
+use super::fsm::FreeSpaceMap;
+use super::overflow::{large_value_space, large_value_len, read_large_value, write_inline_value, write_overflow_ref,
+    is_overflow_page, read_overflow_page, write_overflow_page, overflow_page_capacity, read_overflow_chain,
+    INLINE_VALUE_LEN};
+use super::durability::Durability;
+use super::policy::DEFAULT_POLICY;
+use super::format::{DbHeader, TableHeader, DEFAULT_PAGE_SIZE, TABLE_VERSION, TABLE_HEADER_SIZE,
+    PAGE_HEADER_SIZE, SLOT_ENTRY_SIZE, OVERFLOW_PAGE_MARK, set_page_checksum, get_page_checksum, page_checksum, read_u32_ne};
+use super::index::{IndexFile, IndexFileRef, gen_index_file_name,
+    btree_insert, btree_delete, btree_lookup, PRIMARY_INDEX};
+
+
+#[derive(Debug)]
+pub struct PageHeader {
+    pub slot_sum : usize,
+    pub first_free_slot : usize,
+    pub data : DataPtr,
+}
+
+impl PageHeader {
+    pub fn save_to_page_data(&mut self) {
+        // the checksum is only filled in the copy written to the file
+        let buf = unsafe{ from_raw_parts_mut::<u8>(self.data as *mut u8, 4) };
+        write_u16_le(buf, 0, self.slot_sum as u16);
+        write_u16_le(buf, 2, self.first_free_slot as u16);
+    }
+
+    pub fn init_from_page_data(&mut self) {
+        let buf = unsafe{ from_raw_parts::<u8>(self.data as *const u8, 4) };
+        let slot_sum = read_u16_le(buf, 0) as usize;
+        if slot_sum == OVERFLOW_PAGE_MARK as usize {
+            return;  // overflow pages have no slot
+        }
+        assert_eq!(slot_sum, self.slot_sum);
+        self.first_free_slot = read_u16_le(buf, 2) as usize;
+    }
+}
+
+#[derive(Debug)]
+pub struct BitMap {
+    pub data : DataPtr,
+    pub slot_sum : usize,
+}
+
+impl BitMap {
+    pub fn next_tuple_index(&self, from : usize) -> usize {
+        if from >= self.slot_sum {
+            return self.slot_sum;
+        }
+        let mut count = from / 8;
+        let mut bit_count = from % 8;
+        while count < (self.slot_sum + 7) / 8 {
+            let n = unsafe{ read::<u8>((self.data as *const u8).offset(count as isize)) };
+            let mut mask = 1 << bit_count;
+            if n < mask { count += 1; bit_count = 0; continue; }
+            loop {
+                // assume the bits whose index > slot_sum is 0
+                if mask & n > 0 {
+                    return count * 8 + bit_count;
+                }
+                mask *= 2;
+                bit_count += 1;
+            }
+        }
+        self.slot_sum
+    }
+    pub fn get_first_free_slot(&self) -> usize {
+        let mut count = 0;
+        while count < (self.slot_sum + 7) / 8 {
+            let n = unsafe{ read::<u8>((self.data as *const u8).offset(count as isize)) };
+            if n == 255 { count += 1; continue; }
+            let mut mask : u8 = 1;
+            let mut bit_count = 0;
+            loop {
+                // assume the bits whose index > slot_sum is 0
+                if (255 - mask) | n < 255 {
+                    return count * 8 + bit_count;
+                }
+                mask *= 2;
+                bit_count += 1;
+            }
+        }
+        self.slot_sum
+    }
+    pub fn get_byte_size(&self) -> usize {
+        (self.slot_sum + 7) / 8
+    }
+    pub fn clean(&mut self) {
+        unsafe{
+            write_bytes(self.data, 0, self.get_byte_size());
+        }
+    }
+    pub fn set_inuse(&mut self, index : usize, inuse : bool) {
+        assert!(index < self.slot_sum);
+        let byte_offset = index / 8;
+        let bit_offset = index % 8;
+        let n = 1 << bit_offset;
+        unsafe{
+            let p = pointer_offset(self.data, byte_offset);
+            let a = read::<u8>(p as *const u8);
+            let b = if inuse {
+                a | n
+            } else {
+                a & (255 - n)
+            };
+            write::<u8>(p as *mut u8, b);
+        }
+    }
+    pub fn is_inuse(&self, index : usize) -> bool {
+        assert!(index < self.slot_sum);
+        let byte_offset = index / 8;
+        let bit_offset = index % 8;
+        let n = 1 << bit_offset;
+        unsafe{
+            let p = pointer_offset(self.data, byte_offset);
+            let a = read::<u8>(p as *const u8);
+            (a & n) > 0
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FilePage {
+    pub header : PageHeader,
+    pub bitmap : BitMap,
+    pub tuple_data : DataPtr,  // the slot directory in slotted pages
+    pub mem_page : PageRef,
+    pub tuple_len : usize,
+    pub page_size : usize,
+    pub variable : bool,  // slotted page with variable-length tuples
+}
+
+// the pointers all point into mem_page, which is kept alive by the reference
+unsafe impl Send for FilePage {}
+unsafe impl Sync for FilePage {}
+
+impl FilePage {
+    pub fn new(mem_page : PageRef, tuple_desc : &TupleDesc, page_size : usize) -> FilePage {
+        let data = mem_page.read().unwrap().data;
+        let slot_sum = get_slot_sum(tuple_desc, page_size);
+        let bitmap_data = unsafe{ data.offset(PAGE_HEADER_SIZE as isize) };
+        let bitmap_size = (slot_sum + 7) / 8;
+        let tuple_data = unsafe{ bitmap_data.offset(bitmap_size as isize) };
+        FilePage{
+            header : PageHeader{
+                slot_sum : slot_sum,
+                first_free_slot : 0,
+                data : data,
+            },
+            bitmap : BitMap{
+                data : bitmap_data,
+                slot_sum : slot_sum,
+            },
+            tuple_data : tuple_data,
+            mem_page : mem_page,
+            tuple_len : tuple_desc.tuple_len,
+            page_size : page_size,
+            variable : tuple_desc.variable,
+        }
+    }
+    pub fn init_empty_page(&mut self) {
+        // may be a freed overflow page
+        self.header.slot_sum = self.bitmap.slot_sum;
+        self.header.first_free_slot = 0;
+        self.header.save_to_page_data();
+        self.bitmap.clean();
+        if self.variable {
+            unsafe{ write_bytes(self.tuple_data, 0, self.bitmap.slot_sum * SLOT_ENTRY_SIZE) };
+        }
+    }
+    pub fn init_from_page_data(&mut self) {
+        self.header.init_from_page_data();
+    }
+    pub fn is_overflow(&self) -> bool {
+        is_overflow_page(unsafe{ from_raw_parts::<u8>(self.header.data as *const u8, 2) })
+    }
+    pub fn is_initialized(&self) -> bool {
+        // pages created by a rolled back statement are restored to zero
+        read_u16_le(unsafe{ from_raw_parts::<u8>(self.header.data as *const u8, 2) }, 0) != 0
+    }
+    pub fn save_to_page(&mut self) {
+        self.header.save_to_page_data();
+    }
+    pub fn is_inuse(&self, index : usize) -> bool {
+        self.bitmap.is_inuse(index)
+    }
+    pub fn set_inuse(&mut self, index : usize, inuse : bool) {
+        self.bitmap.set_inuse(index, inuse);
+    }
+    fn alloc_slot(&mut self) -> usize {
+        assert!(self.header.first_free_slot < self.bitmap.slot_sum);
+        assert!(!self.is_inuse(self.header.first_free_slot));
+        let first_free_slot = self.header.first_free_slot;
+        self.set_inuse(first_free_slot, true);
+        self.header.first_free_slot = self.bitmap.get_first_free_slot();
+        self.save_to_page();
+        first_free_slot
+    }
+    pub fn insert(&mut self, value_list : &ValueList, tuple_desc : &TupleDesc) -> usize {
+        // return the slot of the inserted tuple, the texts and blobs should fit in the tuple
+        let values = Self::gen_values(value_list, tuple_desc);
+        let tuple_bytes = Self::encode_tuple(&values, tuple_desc);
+        self.insert_tuple_bytes(&tuple_bytes)
+    }
+    pub fn gen_values(value_list : &ValueList, tuple_desc : &TupleDesc) -> Vec<TupleValue> {
+        assert_eq!(value_list.len(), tuple_desc.attr_desc.len());
+        value_list.iter().zip(&tuple_desc.attr_desc).map(|(v, d)| parse_tuple_value(v, d).unwrap_or_else(
+            || panic!("invalid value, expected {:?}, found {:?}", d, v))).collect()
+    }
+    pub fn encode_tuple(values : &Vec<TupleValue>, tuple_desc : &TupleDesc) -> Vec<u8> {
+        // the varchars, texts and blobs only take the space they need,
+        // the long texts and blobs should have been saved in overflow pages
+        let mut buf = vec![0 as u8; tuple_desc.tuple_len];
+        let data = buf.as_mut_ptr() as DataPtr;
+        let mut p = pointer_offset(data, tuple_desc.null_bitmap_len);
+        for (i, (v, d)) in values.iter().zip(&tuple_desc.attr_desc).enumerate() {
+            let len = match (v, d) {
+                (&TupleValue::Char(ref s), &AttrType::Varchar{..}) => varchar_space(s.len()),
+                (&TupleValue::Null, &AttrType::Varchar{..}) => varchar_space(0),
+                (&TupleValue::Bytes(ref b), _) => large_value_space(b.len()),
+                (&TupleValue::Overflow{len, ..}, _) => large_value_space(len),
+                (&TupleValue::Null, &AttrType::Text) | (&TupleValue::Null, &AttrType::Blob) => large_value_space(0),
+                _ => attr_len(d),
+            };
+            unsafe{
+                match (v, d) {
+                    (&TupleValue::Int(n), &AttrType::Int) => write_int(p, n),
+                    (&TupleValue::Float(n), &AttrType::Float) => write_float(p, n),
+                    (&TupleValue::Int(n), &AttrType::Float) => write_float(p, n as f32),
+                    (&TupleValue::BigInt(n), &AttrType::BigInt) => write_bigint(p, n),
+                    (&TupleValue::Double(n), &AttrType::Double) => write_double(p, n),
+                    (&TupleValue::Decimal(n, s), &AttrType::Decimal{scale, ..}) if s == scale => write_bigint(p, n),
+                    (&TupleValue::Boolean(b), &AttrType::Boolean) => write_int(p, b as i32),
+                    (&TupleValue::Date(n), &AttrType::Date) => write_int(p, n),
+                    (&TupleValue::Time(n), &AttrType::Time) => write_bigint(p, n),
+                    (&TupleValue::Timestamp(n), &AttrType::Timestamp) => write_bigint(p, n),
+                    (&TupleValue::Char(ref s), &AttrType::Char{len}) => write_string(p, s, len),
+                    (&TupleValue::Char(ref s), &AttrType::Varchar{..}) => write_varchar(p, s),
+                    (&TupleValue::Bytes(ref b), &AttrType::Text) | (&TupleValue::Bytes(ref b), &AttrType::Blob) =>
+                        write_inline_value(p, b),
+                    (&TupleValue::Overflow{len, first_page}, &AttrType::Text)
+                    | (&TupleValue::Overflow{len, first_page}, &AttrType::Blob) =>
+                        write_overflow_ref(p, len, first_page),
+                    (&TupleValue::Null, _) => {
+                        assert!(tuple_desc.null_bitmap_len > 0);
+                        Self::set_null(data, i, true);
+                    }
+                    _ => panic!("invalid value, expected {:?}, found {:?}", d, v),
+                }
+            }
+            p = pointer_offset(p, len);
+        }
+        if tuple_desc.variable {
+            let len = p as usize - data as usize;
+            buf.truncate(len);
+        }
+        buf
+    }
+    pub fn get_tuple_bytes(&self, tuple_index : usize) -> Vec<u8> {
+        assert!(self.is_inuse(tuple_index));
+        let len = if self.variable { self.get_slot_entry(tuple_index).1 } else { self.tuple_len };
+        let data = self.get_tuple_ptr(tuple_index);
+        unsafe{ from_raw_parts::<u8>(data as *const u8, len) }.to_vec()
+    }
+    pub fn insert_tuple_bytes(&mut self, tuple_bytes : &[u8]) -> usize {
+        // copy a tuple from another page, return the slot
+        if !self.variable {
+            assert_eq!(tuple_bytes.len(), self.tuple_len);
+            let slot = self.alloc_slot();
+            self.write_tuple_bytes(slot, tuple_bytes);
+            return slot;
+        }
+        // the space is found before the slot is taken, whose entry may be left by a deleted tuple
+        let offset = self.alloc_space(tuple_bytes.len());
+        let slot = self.alloc_slot();
+        self.set_slot_entry(slot, offset, tuple_bytes.len());
+        self.write_tuple_bytes(slot, tuple_bytes);
+        slot
+    }
+    fn write_tuple_bytes(&mut self, tuple_index : usize, tuple_bytes : &[u8]) {
+        let data = self.get_tuple_ptr(tuple_index);
+        unsafe{ from_raw_parts_mut::<u8>(data as *mut u8, tuple_bytes.len()) }.copy_from_slice(tuple_bytes);
+    }
+    pub fn update(&mut self, ptr : DataPtr, set_values : &HashMap<usize, TupleValue>,
+            tuple_desc : &TupleDesc) -> bool {
+        // return false and leave the tuple unchanged if the grown tuple doesn't fit in the page
+        let index = self.get_tuple_index(ptr);
+        assert!(self.is_inuse(index));
+        let mut values : Vec<TupleValue> = (0..tuple_desc.attr_desc.len()).map(
+            |i| self.get_tuple_value(index, i, tuple_desc)).collect();
+        for (i, v) in set_values.iter() {
+            values[*i] = v.clone();
+        }
+        let tuple_bytes = Self::encode_tuple(&values, tuple_desc);
+        if self.variable {
+            let (offset, len) = self.get_slot_entry(index);
+            if tuple_bytes.len() > len {
+                if self.get_free_space() + len < tuple_bytes.len() {
+                    return false;
+                }
+                self.set_slot_entry(index, 0, 0);  // the old space can be reused
+                let offset = self.alloc_space(tuple_bytes.len());
+                self.set_slot_entry(index, offset, tuple_bytes.len());
+            } else {
+                self.set_slot_entry(index, offset, tuple_bytes.len());
+            }
+        }
+        self.write_tuple_bytes(index, &tuple_bytes);
+        true
+    }
+    pub fn get_tuple_value(&self, tuple_index : usize,
+            attr_position : usize,
+            tuple_desc : &TupleDesc) -> TupleValue {
+        assert!(self.is_inuse(tuple_index));
+        let data = self.get_tuple_ptr(tuple_index);
+        if tuple_desc.null_bitmap_len > 0 && Self::is_null(data, attr_position) {
+            return TupleValue::Null;
+        }
+        let p = Self::attr_offset(data, tuple_desc, attr_position);
+        unsafe{
+            match tuple_desc.attr_desc[attr_position] {
+                AttrType::Int => TupleValue::Int(read_int(p)),
+                AttrType::Float => TupleValue::Float(read_float(p)),
+                AttrType::BigInt => TupleValue::BigInt(read_bigint(p)),
+                AttrType::Double => TupleValue::Double(read_double(p)),
+                AttrType::Decimal{scale, ..} => TupleValue::Decimal(read_bigint(p), scale),
+                AttrType::Boolean => TupleValue::Boolean(read_int(p) != 0),
+                AttrType::Date => TupleValue::Date(read_int(p)),
+                AttrType::Time => TupleValue::Time(read_bigint(p)),
+                AttrType::Timestamp => TupleValue::Timestamp(read_bigint(p)),
+                AttrType::Char{len} => TupleValue::Char(read_string(p, len)),
+                AttrType::Varchar{..} => TupleValue::Char(read_varchar(p)),
+                AttrType::Text | AttrType::Blob => read_large_value(p),
+            }
+        }
+    }
+    pub fn get_tuple_data(&self, tuple_index : usize, tuple_desc : &TupleDesc) -> Option<TupleData> {
+        if tuple_index >= self.bitmap.slot_sum {
+            return None;
+        }
+        assert!(self.is_inuse(tuple_index));
+        let mut tuple_data = Vec::new();
+        let data = self.get_tuple_ptr(tuple_index);
+        for i in 0..tuple_desc.attr_desc.len() {
+            if tuple_desc.null_bitmap_len > 0 && Self::is_null(data, i) {
+                tuple_data.push(null_mut());
+            } else {
+                tuple_data.push(Self::attr_offset(data, tuple_desc, i));
+            }
+        }
+        Some(tuple_data)
+    }
+    pub fn attr_offset(p : DataPtr, tuple_desc : &TupleDesc, attr_position : usize) -> DataPtr {
+        let mut offset = tuple_desc.null_bitmap_len;
+        for (attr_type, _) in tuple_desc.attr_desc.iter().zip(0..attr_position) {
+            match attr_type {
+                &AttrType::Varchar{..} => offset += varchar_space(unsafe{ varchar_len(pointer_offset(p, offset)) }),
+                &AttrType::Text | &AttrType::Blob =>
+                    offset += large_value_space(unsafe{ large_value_len(pointer_offset(p, offset)) }),
+                _ => offset += attr_len(attr_type),
+            }
+        }
+        pointer_offset(p, offset)
+    }
+    pub fn is_null(tuple : DataPtr, attr_position : usize) -> bool {
+        // tuple should have null bitmap
+        let p = pointer_offset(tuple, attr_position / 8);
+        let n = unsafe{ read::<u8>(p as *const u8) };
+        n & (1 << (attr_position % 8)) > 0
+    }
+    pub fn set_null(tuple : DataPtr, attr_position : usize, is_null : bool) {
+        let p = pointer_offset(tuple, attr_position / 8);
+        let mask = 1 << (attr_position % 8);
+        unsafe{
+            let n = read::<u8>(p as *const u8);
+            write::<u8>(p as *mut u8, if is_null { n | mask } else { n & !mask });
+        }
+    }
+    pub fn is_full(&self) -> bool {
+        // a slotted page should have the space for the longest tuple
+        self.is_overflow() || self.header.first_free_slot == self.bitmap.slot_sum
+            || (self.variable && self.get_free_space() < self.tuple_len)
+    }
+    pub fn is_in_page(&self, ptr : DataPtr) -> bool {
+        let page_start = self.mem_page.read().unwrap().data;
+        let page_end = pointer_offset(page_start, self.page_size);
+        page_start <= ptr && ptr < page_end
+    }
+    pub fn get_tuple_index(&self, ptr : DataPtr) -> usize {
+        if !self.variable {
+            let d = ptr as usize - self.tuple_data as usize;
+            return d / self.tuple_len;
+        }
+        let d = ptr as usize - self.header.data as usize;
+        let mut slot = self.bitmap.next_tuple_index(0);
+        while slot < self.bitmap.slot_sum {
+            let (offset, len) = self.get_slot_entry(slot);
+            if offset <= d && d < offset + len {
+                return slot;
+            }
+            slot = self.bitmap.next_tuple_index(slot + 1);
+        }
+        panic!("pointer not in any tuple");
+    }
+    pub fn delete(&mut self, ptr : DataPtr) {
+        let index = self.get_tuple_index(ptr);
+        assert!(self.is_inuse(index));
+        self.set_inuse(index, false);
+        if self.variable {
+            self.set_slot_entry(index, 0, 0);
+        }
+        self.header.first_free_slot = min(self.header.first_free_slot, index);
+        self.save_to_page();
+    }
+    fn get_tuple_ptr(&self, tuple_index : usize) -> DataPtr {
+        if self.variable {
+            pointer_offset(self.header.data, self.get_slot_entry(tuple_index).0)
+        } else {
+            pointer_offset(self.tuple_data, tuple_index * self.tuple_len)
+        }
+    }
+
+    // A slotted page keeps an entry of offset and length (2 bytes each, little endian)
+    // for each slot after the bitmap, and the tuples are placed from the end of the page.
+    // The space of deleted or moved tuples is reclaimed by compact.
+    pub fn get_slot_entry(&self, tuple_index : usize) -> (usize, usize) {
+        let buf = unsafe{ from_raw_parts::<u8>(
+            pointer_offset(self.tuple_data, tuple_index * SLOT_ENTRY_SIZE) as *const u8, SLOT_ENTRY_SIZE) };
+        (read_u16_le(buf, 0) as usize, read_u16_le(buf, 2) as usize)
+    }
+    fn set_slot_entry(&mut self, tuple_index : usize, offset : usize, len : usize) {
+        let buf = unsafe{ from_raw_parts_mut::<u8>(
+            pointer_offset(self.tuple_data, tuple_index * SLOT_ENTRY_SIZE) as *mut u8, SLOT_ENTRY_SIZE) };
+        write_u16_le(buf, 0, offset as u16);
+        write_u16_le(buf, 2, len as u16);
+    }
+    fn get_directory_end(&self) -> usize {
+        self.tuple_data as usize - self.header.data as usize + self.bitmap.slot_sum * SLOT_ENTRY_SIZE
+    }
+    fn get_tuple_entries(&self) -> Vec<(usize, usize, usize)> {
+        // slot, offset and length of the tuples
+        let mut entries = Vec::new();
+        let mut slot = self.bitmap.next_tuple_index(0);
+        while slot < self.bitmap.slot_sum {
+            let (offset, len) = self.get_slot_entry(slot);
+            if len > 0 {
+                entries.push((slot, offset, len));
+            }
+            slot = self.bitmap.next_tuple_index(slot + 1);
+        }
+        entries
+    }
+    pub fn get_free_space(&self) -> usize {
+        // including the space reclaimed by compact
+        let used : usize = self.get_tuple_entries().iter().map(|&(_, _, len)| len).sum();
+        self.page_size - self.get_directory_end() - used
+    }
+    fn alloc_space(&mut self, len : usize) -> usize {
+        // return the offset of the space, which should be available
+        let lowest = self.get_tuple_entries().iter().map(|&(_, offset, _)| offset).min().unwrap_or(self.page_size);
+        if lowest - self.get_directory_end() >= len {
+            return lowest - len;
+        }
+        let lowest = self.compact();
+        assert!(lowest - self.get_directory_end() >= len);
+        lowest - len
+    }
+    fn compact(&mut self) -> usize {
+        // move the tuples to the end of the page, return the lowest offset
+        let entries = self.get_tuple_entries();
+        let tuples : Vec<Vec<u8>> = entries.iter().map(|&(slot, _, _)| self.get_tuple_bytes(slot)).collect();
+        let mut offset = self.page_size;
+        for (&(slot, _, len), tuple_bytes) in entries.iter().zip(tuples.iter()) {
+            offset -= len;
+            self.set_slot_entry(slot, offset, len);
+            self.write_tuple_bytes(slot, tuple_bytes);
+        }
+        offset
+    }
+}
+
+
+pub type TableFileRef = Arc<RwLock<TableFile>>;
+
+#[derive(Debug)]
+pub struct TableFile {
+    pub saved_name : String,
+    pub file : File,
+    pub loaded_pages : HashMap<usize, FilePage>,
+    pub page_sum : usize,  // including pages not loaded in memory
+    pub table : TableRef,
+    pub first_free_page : usize,  // pages before it are full
+    pub fsm : FreeSpaceMap,
+    pub tuple_desc : TupleDesc,  // for FilePage
+    pub page_size : usize,
+    pub version : u32,  // format version, older files are upgraded after recovery
+}
+
+impl TableFile {
+    pub fn new(mut name : String, table : TableRef, dir : &String, truncate : bool,
+            page_size : usize) -> TableFile {
+        let fsm = FreeSpaceMap::new(&name, dir, truncate);
+        name.push_str(".table");
+        name = path_join(dir, &name);
+        let file = OpenOptions::new().read(true).write(true).create(true)
+            .truncate(truncate).open(&name).unwrap();
+        let tuple_desc = table.read().unwrap().gen_tuple_desc();
+        TableFile{
+            saved_name : name,
+            file : file,
+            loaded_pages : HashMap::new(),
+            page_sum : 0,
+            table : table,
+            first_free_page : 0,
+            fsm : fsm,
+            tuple_desc : tuple_desc,
+            page_size : page_size,
+            version : TABLE_VERSION,
+        }
+    }
+    pub fn init_from_file(&mut self) -> Result<(), String> {
+        // return the error naming the table if the header can not be read
+        let mut buf = vec![0; TABLE_HEADER_SIZE];
+        let file = &mut self.file;
+        let result = file.seek(SeekFrom::Start(0)).and_then(|_| file.read(&mut buf))
+            .map_err(|err| format!("{}", err))
+            .and_then(|len| TableHeader::decode(&buf[..len]));
+        let header = try!(result.map_err(|err| format!("table {} is corrupted: {}",
+            self.table.read().unwrap().name, err)));
+        self.version = header.version;
+        self.page_sum = header.page_sum;
+        self.first_free_page = header.first_free_page;
+        self.fsm.init_from_file(self.page_sum);
+        Ok(())
+    }
+    pub fn upgrade(&mut self) -> Result<bool, String> {
+        // convert a file of older version page by page into a new file, which then replaces it,
+        // the log should have been checkpointed since it refers to the old layout
+        if self.version == TABLE_VERSION {
+            return Ok(false);
+        }
+        assert!(self.loaded_pages.is_empty());
+        let table_name = self.table.read().unwrap().name.clone();
+        let io_error = |err : io::Error| format!("can not upgrade table {}: {}", table_name, err);
+        let tmp_name = format!("{}.upgrade", self.saved_name);
+        let mut tmp = try!(OpenOptions::new().read(true).write(true).create(true)
+            .truncate(true).open(&tmp_name).map_err(&io_error));
+        let mut page = TableHeader{
+            version : TABLE_VERSION,
+            page_sum : self.page_sum,
+            first_free_page : self.first_free_page,
+        }.encode();
+        page.resize(self.page_size, 0);
+        try!(tmp.write_all(&page).map_err(&io_error));
+        for page_index in 0..self.page_sum {
+            let result = self.read_raw_page(page_index, &mut page).map_err(|err| format!("{}", err))
+                .and_then(|_| upgrade_page(&mut page, self.version, &self.tuple_desc, self.page_size));
+            try!(result.map_err(|err| format!("page {} of table {} is corrupted: {}", page_index, table_name, err)));
+            set_page_checksum(&mut page);
+            try!(tmp.write_all(&page).map_err(&io_error));
+        }
+        try!(tmp.sync_all().and_then(|_| rename(&tmp_name, &self.saved_name)).map_err(&io_error));
+        self.file = tmp;
+        self.version = TABLE_VERSION;
+        Ok(true)
+    }
+    fn read_raw_page(&mut self, page_index : usize, page : &mut Vec<u8>) -> io::Result<()> {
+        // the missing part of a short file is read as zero
+        let offset = self.page_size * (page_index + 1);
+        try!(self.file.seek(SeekFrom::Start(offset as u64)));
+        page.clear();
+        try!((&mut self.file).take(self.page_size as u64).read_to_end(page));
+        page.resize(self.page_size, 0);
+        Ok(())
+    }
+    pub fn refresh_checksum(&mut self, page_index : usize) {
+        // recovery writes the pages directly, leaving their checksums out of date
+        if self.version != TABLE_VERSION || page_index >= self.page_sum {
+            return;
+        }
+        let mut page = Vec::new();
+        check_ok!(self.read_raw_page(page_index, &mut page));
+        set_page_checksum(&mut page);
+        self.write_page_range(page_index, 0, &page);
+    }
+    pub fn read_page_from_file(&mut self, data : DataPtr, page_index : usize) -> Result<(), String> {
+        // return the error naming the table and the page if the page is corrupted
+        assert!(page_index < self.page_sum);
+        let page_size = self.page_size;
+        let offset = page_size * (page_index + 1);
+        is_match!(self.file.seek(SeekFrom::Start(offset as u64)), Ok(..));
+        let page = unsafe{ from_raw_parts_mut::<u8>(data as *mut u8, page_size) };
+        let result = match self.file.read_exact(page) {
+            Ok(..) => self.check_page(page),
+            Err(err) => Err(format!("{}", err)),
+        };
+        result.map_err(|err| format!("page {} of table {} is corrupted: {}",
+            page_index, self.table.read().unwrap().name, err))
+    }
+    pub fn check_page(&self, page : &[u8]) -> Result<(), String> {
+        verify_page(page, self.get_page_slot_sum())
+    }
+    pub fn get_page_slot_sum(&self) -> usize {
+        get_slot_sum(&self.tuple_desc, self.page_size)
+    }
+    pub fn save_header(&mut self) {
+        // the first page only save header for alignment
+        is_match!(self.file.seek(SeekFrom::Start(0)), Ok(..));
+        let header = TableHeader{
+            version : self.version,
+            page_sum : self.page_sum,
+            first_free_page : self.first_free_page,
+        };
+        is_match!(self.file.write_all(&header.encode()), Ok(..));
+    }
+    pub fn save_to_file(&mut self) -> usize {
+        // return the number of pages written
+        self.save_header();
+        self.fsm.save_to_file();
+        let index_list : Vec<_> = self.loaded_pages.iter().map(|(i, _)| *i).collect();
+        index_list.iter().filter(|i| self.save_page(**i)).count()
+    }
+    pub fn save_page(&mut self, page_index : usize) -> bool {
+        // the first page only save header for alignment, return false if the page is clean
+        let page_size = self.page_size;
+        let offset = page_size * (page_index + 1);
+        let page = self.loaded_pages.get(&page_index).unwrap();
+        if !page.mem_page.read().unwrap().dirty {
+            return false;
+        }
+        let mut data = unsafe{
+            from_raw_parts::<u8>(page.mem_page.read().unwrap().data as *const u8, page_size)
+        }.to_vec();
+        set_page_checksum(&mut data);
+        is_match!(self.file.seek(SeekFrom::Start(offset as u64)), Ok(..));
+        is_match!(self.file.write_all(&data), Ok(..));
+        page.mem_page.write().unwrap().mark_clean();
+        true
+    }
+    pub fn sync(&mut self) {
+        check_ok!(self.file.sync_data());
+        self.fsm.sync();
+    }
+    pub fn shrink(&mut self, page_sum : usize) {
+        // drop the empty pages at the end, the file is cut off by truncate_file
+        assert!(page_sum <= self.page_sum);
+        self.page_sum = page_sum;
+        self.first_free_page = min(self.first_free_page, page_sum);
+        self.fsm.truncate(page_sum);
+    }
+    pub fn truncate_file(&mut self) {
+        // should be called when the log no longer refers to the dropped pages
+        let file_len = (self.page_size * (self.page_sum + 1)) as u64;
+        if check_ok!(self.file.metadata()).len() > file_len {
+            check_ok!(self.file.set_len(file_len));
+        }
+    }
+    pub fn mark_page_free(&mut self, page_index : usize) {
+        // the page may have free slots now
+        self.fsm.set_free(page_index, true);
+        self.first_free_page = min(self.first_free_page, page_index);
+    }
+    pub fn write_page_range(&mut self, page_index : usize, offset : usize, data : &[u8]) {
+        // write to the file directly, only used in recovery
+        let page_size = self.page_size;
+        assert!(offset + data.len() <= page_size);
+        let file_offset = page_size * (page_index + 1) + offset;
+        is_match!(self.file.seek(SeekFrom::Start(file_offset as u64)), Ok(..));
+        is_match!(self.file.write_all(data), Ok(..));
+    }
+    pub fn recover_header(&mut self, committed_page_sum : usize) {
+        // pages only touched by uncommitted statements are dropped
+        self.page_sum = max(self.page_sum, committed_page_sum);
+        self.first_free_page = min(self.first_free_page, self.page_sum);
+        let file_len = (self.page_size * (self.page_sum + 1)) as u64;
+        if check_ok!(self.file.metadata()).len() < file_len {
+            check_ok!(self.file.set_len(file_len));
+        }
+        self.save_header();
+    }
+    pub fn get_page_data(&self, page_index : usize) -> DataPtr {
+        self.loaded_pages.get(&page_index).unwrap().mem_page.read().unwrap().data
+    }
+    pub fn page_snapshot(&self, page_index : usize) -> Vec<u8> {
+        let data = self.get_page_data(page_index);
+        unsafe{ from_raw_parts::<u8>(data as *const u8, self.page_size) }.to_vec()
+    }
+    pub fn apply_page_range(&mut self, page_index : usize, offset : usize, data : &[u8]) {
+        let page_data = pointer_offset(self.get_page_data(page_index), offset);
+        unsafe{ from_raw_parts_mut::<u8>(page_data as *mut u8, data.len()) }.copy_from_slice(data);
+    }
+    pub fn get_position(&self, page_index : usize, ptr : DataPtr) -> usize {
+        let page = self.loaded_pages.get(&page_index).unwrap();
+        page_index * self.get_page_slot_sum() + page.get_tuple_index(ptr)
+    }
+    pub fn find_page_index(&self, ptr : DataPtr) -> Option<usize> {
+        for (i, page) in self.loaded_pages.iter() {
+            if page.is_in_page(ptr) {
+                return Some(*i);
+            }
+        }
+        None
+    }
+    pub fn delete(&mut self, ptr : DataPtr) {
+        for (_, page) in &mut self.loaded_pages {
+            if page.is_in_page(ptr) {
+                page.delete(ptr);
+                return;
+            }
+        }
+    }
+    pub fn update(&mut self, ptr : DataPtr, set_values : &HashMap<usize, TupleValue>,
+            tuple_desc : &TupleDesc) -> bool {
+        // return false if the grown tuple doesn't fit in its page
+        for (_, page) in &mut self.loaded_pages {
+            if page.is_in_page(ptr) {
+                return page.update(ptr, set_values, tuple_desc);
+            }
+        }
+        unreachable!()
+    }
+    pub fn insert(&mut self, value_list : &ValueList) -> usize {
+        // must call add_page first if need_new_page() is true
+        let first_free_page = self.first_free_page;
+        self.insert_in_page(first_free_page, value_list)
+    }
+    pub fn insert_in_page(&mut self, page_index : usize, value_list : &ValueList) -> usize {
+        // return the position of the inserted tuple
+        assert!(page_index < self.page_sum);
+        let slot_sum = self.get_page_slot_sum();
+        let file_page = self.loaded_pages.get_mut(&page_index).unwrap();
+        assert!(!file_page.is_full());
+        let slot = file_page.insert(value_list, &self.tuple_desc);
+        if file_page.is_full() {
+            self.fsm.set_free(page_index, false);
+        }
+        page_index * slot_sum + slot
+    }
+    pub fn get_tuple_bytes(&self, position : usize) -> Vec<u8> {
+        let page_index = position / self.get_page_slot_sum();
+        let tuple_index = position % self.get_page_slot_sum();
+        self.loaded_pages.get(&page_index).unwrap().get_tuple_bytes(tuple_index)
+    }
+    pub fn insert_tuple_bytes(&mut self, page_index : usize, tuple_bytes : &[u8]) -> usize {
+        // return the position of the copied tuple
+        let slot_sum = self.get_page_slot_sum();
+        let file_page = self.loaded_pages.get_mut(&page_index).unwrap();
+        let slot = file_page.insert_tuple_bytes(tuple_bytes);
+        if file_page.is_full() {
+            self.fsm.set_free(page_index, false);
+        }
+        page_index * slot_sum + slot
+    }
+    pub fn get_tuple_value(&self, position : usize, attr_position : usize) -> TupleValue {
+        let page_index = position / self.get_page_slot_sum();
+        let tuple_index = position % self.get_page_slot_sum();
+        assert!(self.loaded_pages.get(&page_index).is_some());
+        let page = self.loaded_pages.get(&page_index).unwrap();
+        page.get_tuple_value(tuple_index, attr_position, &self.tuple_desc)
+    }
+    pub fn get_tuple_data(&self, position : usize) -> Option<TupleData> {
+        let page_index = position / self.get_page_slot_sum();
+        let tuple_index = position % self.get_page_slot_sum();
+        assert!(self.loaded_pages.get(&page_index).is_some());
+        let page = self.loaded_pages.get(&page_index).unwrap();
+        page.get_tuple_data(tuple_index, &self.tuple_desc)
+    }
+    pub fn next_tuple_index(&self, page_index : usize, tuple_index : usize) -> Option<usize> {
+        assert!(self.loaded_pages.get(&page_index).is_some());
+        let page = self.loaded_pages.get(&page_index).unwrap();
+        if page.is_overflow() {
+            return None;
+        }
+        let next = page.bitmap.next_tuple_index(tuple_index);
+        if next == self.get_page_slot_sum() {
+            None
+        } else {
+            Some(next)
+        }
+    }
+    pub fn write_overflow_page(&mut self, page_index : usize, data : &[u8], next_page : Option<usize>) {
+        let page_data = self.get_page_data(page_index);
+        write_overflow_page(unsafe{ from_raw_parts_mut::<u8>(page_data as *mut u8, self.page_size) },
+            data, next_page);
+        self.fsm.set_free(page_index, false);
+    }
+    pub fn read_overflow_page(&self, page_index : usize) -> Result<(Vec<u8>, Option<usize>), String> {
+        // return the data and the next page
+        let page_data = self.get_page_data(page_index);
+        let page = unsafe{ from_raw_parts::<u8>(page_data as *const u8, self.page_size) };
+        read_overflow_page(page).map(|(data, next_page)| (data.to_vec(), next_page)).map_err(|err|
+            format!("page {} of table {} is corrupted: {}", page_index, self.table.read().unwrap().name, err))
+    }
+    pub fn add_page(&mut self, mem_page : PageRef) {
+        let file_page = FilePage::new(mem_page, &self.tuple_desc, self.page_size);
+        let index = file_page.mem_page.read().unwrap().page_index as usize;
+        self.loaded_pages.insert(index, file_page);
+    }
+    pub fn get_fd(&self) -> i32 {
+        self.file.as_raw_fd()
+    }
+    pub fn is_inuse(&self, page_index : usize, tuple_index : usize) -> bool {
+        assert!(self.loaded_pages.get(&page_index).is_some());
+        let page = self.loaded_pages.get(&page_index).unwrap();
+        page.is_inuse(tuple_index)
+    }
+    pub fn gen_index_map(&self) -> IndexMap {
+        self.table.read().unwrap().gen_index_map()
+    }
+}
+
+
+#[derive(Debug)]
+pub struct TableFileManager {
+    files : HashMap<String, TableFileRef>,  // key is table name
+    indexes : HashMap<String, IndexFileRef>,  // key is table.index
+    unbuilt_indexes : Vec<IndexFileRef>,  // index files not found, built after recovery
+    obsolete_files : Vec<String>,  // replaced by alter table, removed after the catalog saved
+    pub page_pool : PagePool,
+    pub wal : WalManager,
+    table_file_dir : String,
+    durability : Durability,
+    last_save : Instant,
+}
+
+impl TableFileManager {
+    pub fn new(config : &Config) -> Result<TableFileManager, String> {
+        let table_file_dir = config.get_str("table_file_dir");
+        ensure_dir_exist(&table_file_dir);
+        let page_size = config.get_int_or("page_size", DEFAULT_PAGE_SIZE as i64) as usize;
+        let header = try!(DbHeader::open(&table_file_dir, page_size));
+        let durability = Durability::from_config(config);
+        Ok(TableFileManager{
+            files : HashMap::new(),
+            indexes : HashMap::new(),
+            unbuilt_indexes : Vec::new(),
+            obsolete_files : Vec::new(),
+            page_pool : PagePool::with_policy(&config.get_str_or("buffer_policy", DEFAULT_POLICY),
+                config.get_int("max_memory_pool_page_num") as usize, header.page_size),
+            wal : WalManager::new(&table_file_dir, durability),
+            table_file_dir : table_file_dir,
+            durability : durability,
+            last_save : Instant::now(),
+        })
+    }
+    pub fn init_from_file(&mut self, tables : Vec<TableRef>) -> Result<(), String> {
+        for table in &tables {
+            let table_name = table.read().unwrap().name.clone();
+            let file_base = table.read().unwrap().file.clone();
+            let full_path = path_join(&self.table_file_dir, &format!("{}.table", file_base));
+            assert_file_exist(&full_path);
+            let file = TableFile::new(file_base.clone(), table.clone(), &self.table_file_dir, false,
+                self.page_pool.get_page_size());
+            self.files.insert(table_name.clone(), Arc::new(RwLock::new(file)));
+            try!(self.files.get_mut(&table_name).unwrap().write().unwrap().init_from_file());
+            let index_list = table.read().unwrap().gen_index_list();
+            for index in index_list.iter() {
+                let index_path = path_join(&self.table_file_dir,
+                    &gen_index_file_name(&file_base, &index.name));
+                let index_exist = file_exist(&index_path);
+                let index = self.open_index(table, index, false);
+                if !index_exist {
+                    self.unbuilt_indexes.push(index);
+                }
+            }
+        }
+        Ok(())
+    }
+    pub fn sync_file_dir(&self) {
+        // the new files should be found after a crash before the catalog refers to them
+        if self.durability.need_sync() {
+            sync_dir(&self.table_file_dir);
+        }
+    }
+    pub fn get_durability(&self) -> Durability {
+        self.durability
+    }
+    pub fn need_save(&self) -> bool {
+        // periodic durability leaves the dirty pages in memory and the changes in the log until the interval passes
+        match self.durability {
+            Durability::Periodic{interval} => {
+                self.last_save.elapsed().as_millis() as u64 >= interval
+            }
+            _ => true,
+        }
+    }
+    pub fn save_all(&mut self) {
+        let need_sync = self.durability.need_sync();
+        self.wal.sync();
+        for (_, f)  in self.files.iter() {
+            let written = f.write().unwrap().save_to_file();
+            self.page_pool.update_stats(f.read().unwrap().get_fd(), |stats| stats.write_backs += written);
+            if need_sync {
+                f.write().unwrap().sync();
+            }
+        }
+        for (_, index) in self.indexes.iter() {
+            let written = index.write().unwrap().save_to_file();
+            self.page_pool.update_stats(index.read().unwrap().get_fd(), |stats| stats.write_backs += written);
+            if need_sync {
+                index.write().unwrap().sync();
+            }
+        }
+        self.last_save = Instant::now();
+    }
+    pub fn buffer_status(&self) -> String {
+        let capacity = self.page_pool.get_capacity();
+        let pinned = capacity - self.page_pool.get_unpinned_num();
+        let mut file_list = Vec::new();  // (name, fd, loaded, pinned)
+        for (name, file) in self.files.iter() {
+            let f = file.read().unwrap();
+            let pinned = f.loaded_pages.values().filter(|p| p.mem_page.read().unwrap().is_pinned()).count();
+            file_list.push((name.clone(), f.get_fd(), f.loaded_pages.len(), pinned));
+        }
+        for (name, index) in self.indexes.iter() {
+            let i = index.read().unwrap();
+            let pinned = i.loaded_pages.values().filter(|p| p.read().unwrap().is_pinned()).count();
+            file_list.push((name.clone(), i.get_fd(), i.loaded_pages.len(), pinned));
+        }
+        file_list.sort();
+        let (mut hits, mut misses) = (0, 0);
+        let mut file_status = String::new();
+        for &(ref name, fd, loaded, pinned) in file_list.iter() {
+            let stats = self.page_pool.get_stats(fd);
+            hits += stats.hits;
+            misses += stats.misses;
+            file_status.push_str(&format!("{}: {}, loaded {}, pinned {}\n",
+                name, stats.desc(), loaded, pinned));
+        }
+        let hit_ratio = if hits + misses == 0 { 0.0 } else { hits as f64 / (hits + misses) as f64 };
+        format!("buffer: capacity {}, loaded {}, pinned {}, hit ratio {:.3}\n{}",
+            capacity, self.page_pool.get_load(), pinned, hit_ratio, file_status)
+    }
+    pub fn checkpoint(&mut self) {
+        // should be called after save_all
+        self.wal.checkpoint();
+        for (_, f) in self.files.iter() {
+            f.write().unwrap().truncate_file();
+        }
+    }
+    pub fn recover(&mut self) -> Result<(), String> {
+        // redo all the changes in the log, then undo the statements not committed
+        let records = self.wal.read_records();
+        let losers = find_losers(&records);
+        let mut committed_page_sum = HashMap::new();
+        let mut written_pages = Vec::new();
+        for record in records.iter() {
+            if let &LogRecord::PageWrite{txn, ref table, page_index, offset, ref after, ..} = record {
+                if let Some(index) = self.indexes.get(table) {
+                    index.write().unwrap().write_page_range(page_index, offset, after);
+                    continue;
+                }
+                let file = match self.files.get(table) {
+                    Some(file) => file,
+                    None => continue,  // dropped table
+                };
+                file.write().unwrap().write_page_range(page_index, offset, after);
+                file.write().unwrap().mark_page_free(page_index);
+                written_pages.push((file.clone(), page_index));
+                let page_sum = committed_page_sum.entry(table.clone()).or_insert(0);
+                if !losers.contains(&txn) {
+                    *page_sum = max(*page_sum, page_index + 1);
+                }
+            }
+        }
+        for record in records.iter().rev() {
+            if let &LogRecord::PageWrite{txn, ref table, page_index, offset, ref before, ..} = record {
+                if !losers.contains(&txn) { continue; }
+                if let Some(file) = self.files.get(table) {
+                    file.write().unwrap().write_page_range(page_index, offset, before);
+                }
+                if let Some(index) = self.indexes.get(table) {
+                    index.write().unwrap().write_page_range(page_index, offset, before);
+                }
+            }
+        }
+        for (table, page_sum) in committed_page_sum.iter() {
+            let file = self.get_file(table);
+            file.write().unwrap().recover_header(*page_sum);
+        }
+        for &(ref file, page_index) in written_pages.iter() {
+            file.write().unwrap().refresh_checksum(page_index);
+        }
+        for (table, _) in committed_page_sum.iter() {
+            let file = self.get_file(table);
+            file.write().unwrap().fsm.save_to_file();
+            file.write().unwrap().sync();
+        }
+        for (_, index) in self.indexes.iter() {
+            index.write().unwrap().sync();
+        }
+        try!(self.upgrade_files());
+        let unbuilt_indexes : Vec<_> = self.unbuilt_indexes.drain(..).collect();
+        for index in unbuilt_indexes.iter() {
+            assert!(check_ok!(self.build_index(index)));
+            index.write().unwrap().save_to_file();
+            index.write().unwrap().sync();
+        }
+        self.wal.checkpoint();
+        Ok(())
+    }
+    fn upgrade_files(&mut self) -> Result<(), String> {
+        // files of older version are converted after all the changes in the log reach them
+        let old_files : Vec<_> = self.files.values()
+            .filter(|f| f.read().unwrap().version != TABLE_VERSION).cloned().collect();
+        if old_files.is_empty() {
+            return Ok(());
+        }
+        self.wal.checkpoint();
+        for file in old_files.iter() {
+            assert!(try!(file.write().unwrap().upgrade()));
+            file.write().unwrap().fsm.save_to_file();
+            file.write().unwrap().sync();
+        }
+        Ok(())
+    }
+    pub fn rollback(&mut self) {
+        // undo the changes of current statement in memory
+        let records = self.wal.take_curr_records();
+        for record in records.iter().rev() {
+            let (table, page_index, offset, before) = extract!(record,
+                &LogRecord::PageWrite{ref table, page_index, offset, ref before, ..},
+                (table, page_index, offset, before));
+            if let Some(index) = self.indexes.get(table).cloned() {
+                self.change_index_page(&index, page_index, |data| unsafe{
+                    from_raw_parts_mut::<u8>(pointer_offset(data, offset) as *mut u8, before.len())
+                }.copy_from_slice(before));
+                continue;
+            }
+            let file = match self.files.get(table) {
+                Some(file) => file.clone(),
+                None => continue,  // index dropped in this statement
+            };
+            check_ok!(self.ensure_page_loaded(&file, page_index));  // written by this statement
+            // the undo is logged as a new change, so a crash during rollback undoes it again
+            self.change_page(&file, page_index, |f| {
+                f.apply_page_range(page_index, offset, before);
+                {
+                    let page = f.loaded_pages.get_mut(&page_index).unwrap();
+                    if page.is_initialized() {
+                        page.init_from_page_data();
+                    } else {
+                        page.init_empty_page();
+                    }
+                }
+                f.mark_page_free(page_index);
+            });
+        }
+        self.wal.commit();
+    }
+    fn change_page<F>(&mut self, file : &TableFileRef, page_index : usize, change : F)
+            where F : FnOnce(&mut TableFile) {
+        // every change to pages should go through here to be logged
+        let (table, before) = {
+            let f = file.read().unwrap();
+            let table = f.table.read().unwrap().name.clone();
+            (table, f.page_snapshot(page_index))
+        };
+        change(&mut file.write().unwrap());
+        let data = file.read().unwrap().get_page_data(page_index);
+        file.read().unwrap().loaded_pages.get(&page_index).unwrap().mem_page.write().unwrap().mark_dirty();
+        self.wal.log_page_write(&table, page_index, &before, data);
+    }
+    pub fn delete(&mut self, table : &String, ptr : DataPtr) -> Result<(), String> {
+        // the overflow pages of the tuple are freed
+        let file = self.get_file(table);
+        let page_index = file.read().unwrap().find_page_index(ptr).unwrap();
+        let position = file.read().unwrap().get_position(page_index, ptr);
+        let keys = self.gen_index_keys(&file, position);
+        let attr_num = file.read().unwrap().tuple_desc.attr_desc.len();
+        let values : Vec<TupleValue> = (0..attr_num).map(
+            |i| file.read().unwrap().get_tuple_value(position, i)).collect();
+        self.change_page(&file, page_index, |f| {
+            f.delete(ptr);
+            f.mark_page_free(page_index);
+        });
+        for &(ref index, ref key) in keys.iter() {
+            assert!(btree_delete(self, index, key));
+        }
+        self.free_overflow_values(&file, values.iter())
+    }
+    pub fn update(&mut self, table : &String, tuple_data : &TupleData,
+            set_values : &HashMap<usize, TupleValue>, tuple_desc : &TupleDesc) -> Result<Option<String>, String> {
+        // return the name of the unique index which already has the new key and leave the tuple unchanged,
+        // the overflow pages of the replaced values are freed
+        let file = self.get_file(table);
+        let ptr = get_tuple_ptr(tuple_data);
+        let page_index = file.read().unwrap().find_page_index(ptr).unwrap();
+        let position = file.read().unwrap().get_position(page_index, ptr);
+        let old_values : HashMap<usize, TupleValue> = set_values.keys().map(
+            |i| (*i, file.read().unwrap().get_tuple_value(position, *i))).collect();
+        let mut new_values = set_values.clone();
+        for (_, v) in new_values.iter_mut() {
+            try!(self.save_overflow_value(&file, v));
+        }
+        // the page may be swapped out by the overflow pages
+        try!(self.ensure_page_loaded(&file, page_index));
+        let collision = self.update_tuple(&file, position, &old_values, &new_values, tuple_desc);
+        if collision.is_none() {
+            try!(self.free_overflow_values(&file, old_values.values()));
+        } else {
+            try!(self.free_overflow_values(&file, new_values.values()));
+        }
+        Ok(collision)
+    }
+    fn update_tuple(&mut self, file : &TableFileRef, position : usize, old_values : &HashMap<usize, TupleValue>,
+            set_values : &HashMap<usize, TupleValue>, tuple_desc : &TupleDesc) -> Option<String> {
+        let file = file.clone();
+        let page_index = position / file.read().unwrap().get_page_slot_sum();
+        let ptr = get_tuple_ptr(&file.read().unwrap().get_tuple_data(position).unwrap());
+        let old_keys = self.gen_index_keys(&file, position);
+        let mut in_place = true;
+        self.change_page(&file, page_index, |f| in_place = f.update(ptr, set_values, tuple_desc));
+        if !in_place {
+            return self.move_updated_tuple(&file, position, set_values, old_keys);
+        }
+        let new_keys = self.gen_index_keys(&file, position);
+        let changed_keys : Vec<_> = old_keys.iter().zip(new_keys.iter())
+            .filter(|&(&(_, ref old_key), &(_, ref new_key))| old_key != new_key).collect();
+        for &(_, &(ref index, ref new_key)) in changed_keys.iter() {
+            if index.read().unwrap().unique && !btree_lookup(self, index, new_key).is_empty() {
+                // the page may be swapped out by the index
+                check_ok!(self.ensure_page_loaded(&file, page_index));
+                let ptr = get_tuple_ptr(&file.read().unwrap().get_tuple_data(position).unwrap());
+                // the old tuple always fits in the space it just had
+                self.change_page(&file, page_index, |f| assert!(f.update(ptr, old_values, tuple_desc)));
+                return Some(index.read().unwrap().index_name().to_string());
+            }
+        }
+        for (&(ref index, ref old_key), &(_, ref new_key)) in changed_keys {
+            assert!(btree_delete(self, index, old_key));
+            assert!(btree_insert(self, index, new_key, position));
+        }
+        None
+    }
+    fn move_updated_tuple(&mut self, file : &TableFileRef, position : usize,
+            set_values : &HashMap<usize, TupleValue>, old_keys : Vec<(IndexFileRef, Vec<u8>)>) -> Option<String> {
+        // the grown tuple doesn't fit in its page, so it's moved to another page like vacuum does,
+        // return the name of the unique index which already has the new key and leave the tuple unchanged
+        let mut values : Vec<TupleValue> = {
+            let f = file.read().unwrap();
+            (0..f.tuple_desc.attr_desc.len()).map(|i| f.get_tuple_value(position, i)).collect()
+        };
+        for (i, v) in set_values.iter() {
+            values[*i] = v.clone();
+        }
+        for &(ref index, ref old_key) in old_keys.iter() {
+            let new_key = {
+                let index = index.read().unwrap();
+                index.gen_entry_key(&index.attr_index.iter().map(|i| values[*i].clone()).collect(), position)
+            };
+            if new_key != *old_key && index.read().unwrap().unique && !btree_lookup(self, index, &new_key).is_empty() {
+                return Some(index.read().unwrap().index_name().to_string());
+            }
+        }
+        let tuple_bytes = FilePage::encode_tuple(&values, &file.read().unwrap().tuple_desc);
+        let page_index = position / file.read().unwrap().get_page_slot_sum();
+        // the page may be swapped out by the index
+        check_ok!(self.ensure_page_loaded(file, page_index));
+        let ptr = get_tuple_ptr(&file.read().unwrap().get_tuple_data(position).unwrap());
+        self.change_page(file, page_index, |f| {
+            f.delete(ptr);
+            f.mark_page_free(page_index);
+        });
+        let new_position = check_ok!(self.insert_into_free_page(file,
+            |f, page_index| f.insert_tuple_bytes(page_index, &tuple_bytes)));
+        let new_keys = self.gen_index_keys(file, new_position);
+        for (&(ref index, ref old_key), &(_, ref new_key)) in old_keys.iter().zip(new_keys.iter()) {
+            assert!(btree_delete(self, index, old_key));
+            assert!(btree_insert(self, index, new_key, new_position));
+        }
+        None
+    }
+    pub fn insert(&mut self, table : &String, value_list : &ValueList) -> Result<(), String> {
+        let file = self.get_file(table);
+        let mut values = FilePage::gen_values(value_list, &file.read().unwrap().tuple_desc);
+        for v in values.iter_mut() {
+            try!(self.save_overflow_value(&file, v));
+        }
+        let tuple_bytes = FilePage::encode_tuple(&values, &file.read().unwrap().tuple_desc);
+        let position = try!(self.insert_into_free_page(&file,
+            |f, page_index| f.insert_tuple_bytes(page_index, &tuple_bytes)));
+        self.insert_index_entries(&file, position);
+        Ok(())
+    }
+    fn save_overflow_value(&mut self, file : &TableFileRef, value : &mut TupleValue) -> Result<(), String> {
+        // a text or blob too long for the tuple is saved in a chain of new pages at the end of the file
+        let bytes = match value {
+            &mut TupleValue::Bytes(ref b) if b.len() > INLINE_VALUE_LEN => b.clone(),
+            _ => return Ok(()),
+        };
+        let first_page = file.read().unwrap().page_sum;
+        let capacity = overflow_page_capacity(file.read().unwrap().page_size);
+        let page_num = (bytes.len() + capacity - 1) / capacity;
+        for (i, data) in bytes.chunks(capacity).enumerate() {
+            let page_index = first_page + i;
+            try!(self.ensure_page_loaded(file, page_index));
+            let next_page = if i + 1 < page_num { Some(page_index + 1) } else { None };
+            self.change_page(file, page_index, |f| f.write_overflow_page(page_index, data, next_page));
+        }
+        *value = TupleValue::Overflow{ len : bytes.len(), first_page : first_page };
+        Ok(())
+    }
+    fn free_overflow_values<'a, I>(&mut self, file : &TableFileRef, values : I) -> Result<(), String>
+            where I : Iterator<Item=&'a TupleValue> {
+        // the freed overflow pages become empty pages for tuples
+        for value in values {
+            let (len, first_page) = match value {
+                &TupleValue::Overflow{len, first_page} => (len, first_page),
+                _ => continue,
+            };
+            let mut pages = Vec::new();
+            try!(read_overflow_chain(len, first_page, |page_index| {
+                pages.push(page_index);
+                self.load_overflow_page(file, page_index)
+            }, |_| ()));
+            for page_index in pages {
+                try!(self.ensure_page_loaded(file, page_index));
+                self.change_page(file, page_index, |f| {
+                    f.loaded_pages.get_mut(&page_index).unwrap().init_empty_page();
+                    f.mark_page_free(page_index);
+                });
+            }
+        }
+        Ok(())
+    }
+    fn load_overflow_page(&mut self, file : &TableFileRef, page_index : usize)
+            -> Result<(Vec<u8>, Option<usize>), String> {
+        if page_index >= file.read().unwrap().page_sum {
+            return Err(format!("overflow page {} of table {} not exist",
+                page_index, file.read().unwrap().table.read().unwrap().name));
+        }
+        try!(self.ensure_page_loaded(file, page_index));
+        let result = file.read().unwrap().read_overflow_page(page_index);
+        result
+    }
+    pub fn read_overflow_page(&mut self, table : &String, page_index : usize)
+            -> Result<(Vec<u8>, Option<usize>), String> {
+        // return the data and the next page of the chain
+        let file = self.get_file(table);
+        self.load_overflow_page(&file, page_index)
+    }
+    pub fn load_overflow_value(&mut self, table : &String, value : TupleValue) -> Result<TupleValue, String> {
+        // read the whole value left in the overflow pages
+        match value {
+            TupleValue::Overflow{len, first_page} => {
+                let file = self.get_file(table);
+                let mut bytes = Vec::with_capacity(len);
+                try!(read_overflow_chain(len, first_page, |page_index| self.load_overflow_page(&file, page_index),
+                    |data| bytes.extend_from_slice(data)));
+                Ok(TupleValue::Bytes(bytes))
+            }
+            value => Ok(value),
+        }
+    }
+    fn insert_into_free_page<F>(&mut self, file : &TableFileRef, insert : F) -> Result<usize, String>
+            where F : FnOnce(&mut TableFile, usize) -> usize {
+        // insert into the first page with free space, return the position
+        let is_new_page = try!(self.need_new_page(file));  // fight the borrow checker
+        let page_index = if is_new_page {
+            file.read().unwrap().page_sum
+        } else {
+            file.read().unwrap().first_free_page
+        };
+        try!(self.ensure_page_loaded(file, page_index));
+        let mut position = 0;
+        self.change_page(file, page_index, |f| {
+            if is_new_page {
+                f.loaded_pages.get_mut(&page_index).unwrap().init_empty_page();
+            }
+            position = insert(f, page_index);
+        });
+        Ok(position)
+    }
+    pub fn insert_in_page(&mut self, table : &String, page_index : usize, value_list : &ValueList) {
+        // for test
+        self.prepare_page(table, page_index);
+        let file = self.get_file(table);
+        let mut position = 0;
+        self.change_page(&file, page_index, |f| position = f.insert_in_page(page_index, value_list));
+        self.insert_index_entries(&file, position);
+    }
+    fn insert_index_entries(&mut self, file : &TableFileRef, position : usize) {
+        for &(ref index, ref key) in self.gen_index_keys(file, position).iter() {
+            assert!(btree_insert(self, index, key, position), "duplicate key in {}", index.read().unwrap().name);
+        }
+    }
+    fn gen_index_keys(&self, file : &TableFileRef, position : usize) -> Vec<(IndexFileRef, Vec<u8>)> {
+        // the page of the tuple should be loaded
+        let table = file.read().unwrap().table.read().unwrap().name.clone();
+        self.get_index_list(&table).iter().map(|index| {
+            let key = index.read().unwrap().gen_tuple_key(&file.read().unwrap(), position);
+            (index.clone(), key)
+        }).collect()
+    }
+    pub fn get_index_list(&self, table : &String) -> Vec<IndexFileRef> {
+        // the primary index comes first so that its collisions are reported first
+        let mut index_list : Vec<IndexFileRef> = self.indexes.values()
+            .filter(|index| index.read().unwrap().table == *table).cloned().collect();
+        index_list.sort_by_key(|index| index.read().unwrap().index_name() != PRIMARY_INDEX);
+        index_list
+    }
+    pub fn get_index(&self, table : &String, index : &String) -> Option<IndexFileRef> {
+        self.indexes.get(&format!("{}.{}", table, index)).cloned()
+    }
+    pub fn index_lookup(&mut self, table : &String, index : &String,
+            values : &Vec<TupleValue>) -> Vec<usize> {
+        // return the positions of tuples whose indexed attributes equal to values
+        let index = self.get_index(table, index).unwrap();
+        let key = index.read().unwrap().gen_key(values);
+        btree_lookup(self, &index, &key)
+    }
+    pub fn build_index(&mut self, index : &IndexFileRef) -> Result<bool, String> {
+        // insert all the tuples of the table into an empty index,
+        // return false if duplicate keys found for unique index
+        let table = index.read().unwrap().table.clone();
+        let file = self.get_file(&table);
+        let page_sum = file.read().unwrap().page_sum;
+        for page_index in 0..page_sum {
+            try!(self.ensure_page_loaded(&file, page_index));
+            let mut keys = Vec::new();
+            let mut tuple_index = 0;
+            while let Some(i) = file.read().unwrap().next_tuple_index(page_index, tuple_index) {
+                let position = page_index * file.read().unwrap().get_page_slot_sum() + i;
+                keys.push((index.read().unwrap().gen_tuple_key(&file.read().unwrap(), position), position));
+                tuple_index = i + 1;
+            }
+            // the page may be swapped out by the index
+            for (key, position) in keys.drain(..) {
+                if !btree_insert(self, index, &key, position) {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+    pub fn create_index(&mut self, table : &TableRef, index : &Index) -> Result<bool, String> {
+        // return false if the table has duplicate keys for the unique index
+        let index_file = self.open_index(table, index, true);
+        let result = self.build_index(&index_file);
+        if let Ok(true) = result {
+            return result;
+        }
+        let table_name = table.read().unwrap().name.clone();
+        self.drop_index(&table_name, &index.name);
+        result
+    }
+    pub fn drop_index(&mut self, table : &String, index : &String) {
+        self.remove_index_file(&format!("{}.{}", table, index));
+    }
+    fn remove_index_file(&mut self, name : &String) {
+        let path = self.detach_index_file(name);
+        is_match!(remove_file(&path), Ok(..));
+    }
+    fn detach_index_file(&mut self, name : &String) -> String {
+        // the buffered pages are discarded without writing back, return the path of the file
+        let index = self.indexes.remove(name).unwrap();
+        let fd = index.read().unwrap().get_fd();
+        self.page_pool.remove_stats(fd);
+        let page_list : Vec<usize> = index.write().unwrap().loaded_pages.drain().map(|(i, _)| i).collect();
+        for page_index in page_list {
+            self.page_pool.remove_page(fd, page_index as u32);
+        }
+        let path = index.read().unwrap().saved_name.clone();
+        path
+    }
+    pub fn load_index_page(&mut self, index : &IndexFileRef, page_index : usize) -> DataPtr {
+        let page_exist = index.read().unwrap().loaded_pages.get(&page_index).is_some();  // fight borrow checker
+        let fd = index.read().unwrap().get_fd();
+        self.record_access(fd, page_exist);
+        if !page_exist {
+            // there is always an unpinned page, see PagePool::pin_page
+            let ptr = check_ok!(self.alloc_pool_page(fd, page_index));
+            index.write().unwrap().read_page_from_file(ptr, page_index);
+            index.write().unwrap().add_page(self.page_pool.get_page(fd, page_index as u32).unwrap());
+        }
+        index.read().unwrap().get_page_data(page_index)
+    }
+    pub fn change_index_page<F>(&mut self, index : &IndexFileRef, page_index : usize, change : F)
+            where F : FnOnce(DataPtr) {
+        // every change to index pages should go through here to be logged
+        let data = self.load_index_page(index, page_index);
+        let (name, before) = {
+            let i = index.read().unwrap();
+            (i.name.clone(), i.page_snapshot(page_index))
+        };
+        change(data);
+        index.read().unwrap().loaded_pages.get(&page_index).unwrap().write().unwrap().mark_dirty();
+        self.wal.log_page_write(&name, page_index, &before, data);
+    }
+    pub fn prepare_page(&mut self, table : &String, page_index : usize) {
+        // for test, will init empty page
+        let file = self.get_file(&table);
+        let page_exist = file.read().unwrap().loaded_pages.get(&page_index).is_some();  // fight borrow checker
+        if !page_exist {
+            check_ok!(self.ensure_page_loaded(&file, page_index));
+            self.change_page(&file, page_index,
+                |f| f.loaded_pages.get_mut(&page_index).unwrap().init_empty_page());
+        }
+    }
+    pub fn vacuum(&mut self, table : &String) -> Result<(), String> {
+        // move the tuples in the last pages to the free slots of the front pages,
+        // then drop the empty pages at the end
+        let file = self.get_file(table);
+        let slot_sum = file.read().unwrap().get_page_slot_sum();
+        let mut page_sum = file.read().unwrap().page_sum;
+        // the tuples of the pages from src_page on have all been moved
+        let mut src_page = page_sum;
+        let mut dest_page = 0;
+        while dest_page < src_page {
+            let last_page = src_page - 1;
+            try!(self.ensure_page_loaded(&file, last_page));
+            // overflow pages are referred to by their position and can't be moved
+            if file.read().unwrap().loaded_pages.get(&last_page).unwrap().is_overflow() {
+                src_page -= 1;
+                continue;
+            }
+            let tuple_index = file.read().unwrap().next_tuple_index(last_page, 0);  // fight borrow checker
+            let tuple_index = match tuple_index {
+                Some(i) => i,
+                None => {
+                    src_page -= 1;
+                    continue;
+                }
+            };
+            if dest_page == last_page {
+                break;
+            }
+            try!(self.ensure_page_loaded(&file, dest_page));
+            // overflow pages are always full
+            let is_full = file.read().unwrap().loaded_pages.get(&dest_page).unwrap().is_full();
+            if is_full {
+                dest_page += 1;
+                continue;
+            }
+            self.move_tuple(&file, last_page * slot_sum + tuple_index, dest_page);
+        }
+        // only the empty pages after the last overflow page can be dropped
+        while page_sum > 0 {
+            let last_page = page_sum - 1;
+            try!(self.ensure_page_loaded(&file, last_page));
+            let is_empty = {
+                let f = file.read().unwrap();
+                !f.loaded_pages.get(&last_page).unwrap().is_overflow()
+                    && f.next_tuple_index(last_page, 0).is_none()
+            };
+            if !is_empty {
+                break;
+            }
+            page_sum -= 1;
+        }
+        // the dropped pages are discarded without writing back
+        let fd = file.read().unwrap().get_fd();
+        let dropped : Vec<usize> = file.read().unwrap().loaded_pages.keys()
+            .filter(|i| **i >= page_sum).cloned().collect();
+        for page_index in dropped {
+            file.write().unwrap().loaded_pages.remove(&page_index);
+            self.page_pool.remove_page(fd, page_index as u32);
+        }
+        file.write().unwrap().shrink(page_sum);
+        Ok(())
+    }
+    fn move_tuple(&mut self, file : &TableFileRef, position : usize, page_index : usize) {
+        // loading the destination page may have evicted the page of the tuple
+        let old_page_index = position / file.read().unwrap().get_page_slot_sum();
+        check_ok!(self.ensure_page_loaded(file, old_page_index));  // checked by vacuum
+        let old_keys = self.gen_index_keys(file, position);
+        let tuple_bytes = file.read().unwrap().get_tuple_bytes(position);
+        let ptr = get_tuple_ptr(&file.read().unwrap().get_tuple_data(position).unwrap());
+        self.change_page(file, old_page_index, |f| f.delete(ptr));
+        check_ok!(self.ensure_page_loaded(file, page_index));  // checked by vacuum
+        let mut new_position = 0;
+        self.change_page(file, page_index, |f| new_position = f.insert_tuple_bytes(page_index, &tuple_bytes));
+        let new_keys = self.gen_index_keys(file, new_position);
+        for (&(ref index, ref old_key), &(_, ref new_key)) in old_keys.iter().zip(new_keys.iter()) {
+            assert!(btree_delete(self, index, old_key));
+            assert!(btree_insert(self, index, new_key, new_position));
+        }
+    }
+    pub fn need_new_page(&mut self, file : &TableFileRef) -> Result<bool, String> {
+        // move first_free_page to the first page with free slots according to the free-space map
+        let page_sum = file.read().unwrap().page_sum;
+        let mut first_free_page;
+        loop {
+            first_free_page = {
+                let f = file.read().unwrap();
+                f.fsm.next_free_page(f.first_free_page, page_sum)
+            };
+            file.write().unwrap().first_free_page = first_free_page;
+            if first_free_page == page_sum { break; }
+            try!(self.ensure_page_loaded(&file, first_free_page));
+            let is_full = file.read().unwrap().loaded_pages.get(&first_free_page).unwrap().is_full();  // fight borrow checker
+            if is_full {
+                let mut f = file.write().unwrap();
+                f.fsm.set_free(first_free_page, false);
+                f.first_free_page += 1;
+            } else {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+    pub fn get_file(&self, table : &String) -> TableFileRef {
+        self.files.get(table).unwrap().clone()
+    }
+    pub fn get_tuple_value(&mut self, table : &String,
+            position : usize,
+            attr_position : usize) -> TupleValue{
+        // only for test
+        let file = self.files.get(table).unwrap().clone();
+        let page_index = {
+            let f = file.write().unwrap();
+            position / f.get_page_slot_sum()
+        };
+        check_ok!(self.ensure_page_loaded(&file, page_index));
+        // declare v only to fight lifetime checker
+        let v = file.read().unwrap().get_tuple_value(position, attr_position);
+        v
+    }
+    pub fn get_tuple_data(&mut self, table : &String, position : usize) -> Option<TupleData> {
+        let file = self.files.get(table).unwrap().clone();
+        let page_index = {
+            let f = file.write().unwrap();
+            position / f.get_page_slot_sum()
+        };
+        check_ok!(self.ensure_page_loaded(&file, page_index));
+        // declare v only to fight lifetime checker
+        let v = file.read().unwrap().get_tuple_data(position);
+        v
+    }
+    pub fn get_loaded_tuple_data(&self, file : &TableFileRef, position : usize) -> Option<TupleData> {
+        // the page should be pinned by the caller, so only the read lock of the table manager is needed
+        let f = file.read().unwrap();
+        self.record_access(f.get_fd(), true);
+        f.get_tuple_data(position)
+    }
+    pub fn get_next_tuple_data(&mut self, table : &String, from : usize) -> Option<(TupleData, usize)> {
+        match self.get_next_position(table, from) {
+            Some(position) => Some((self.get_tuple_data(table, position).unwrap(), position)),
+            None => None,
+        }
+    }
+    pub fn get_next_position(&mut self, table : &String, from : usize) -> Option<usize> {
+        let file = self.get_file(table);
+        let page_sum = file.read().unwrap().page_sum;
+        let slot_sum = file.read().unwrap().get_page_slot_sum();
+        let mut page_index = from / slot_sum;
+        let mut tuple_index = from % slot_sum;
+        while page_index < page_sum {
+            let next = file.read().unwrap().next_tuple_index(page_index, tuple_index);
+            match next {
+                Some(i) => return Some(page_index * slot_sum + i),
+                None => {
+                   page_index += 1;
+                   tuple_index = 0;
+                }
+            }
+        }
+        None
+    }
+    pub fn ensure_page_loaded(&mut self, file : &TableFileRef, page_index : usize) -> Result<(), String> {
+        // the corrupted page is not kept in the pool
+        let page_sum = file.read().unwrap().page_sum;
+        assert!(page_index < page_sum || page_index == page_sum);  // old page or new page
+        let page_exist = file.read().unwrap().loaded_pages.get(&page_index).is_some();  // fight borrow checker
+        let fd = file.read().unwrap().get_fd();
+        self.record_access(fd, page_exist);
+        if !page_exist {
+            let ptr = try!(self.alloc_pool_page(fd, page_index));
+            if page_index < page_sum {
+                let result = file.write().unwrap().read_page_from_file(ptr, page_index);
+                if let Err(err) = result {
+                    self.page_pool.remove_page(fd, page_index as u32);
+                    return Err(err);
+                }
+                file.write().unwrap().add_page(self.page_pool.get_page(fd, page_index as u32).unwrap());
+                file.write().unwrap().loaded_pages.get_mut(&page_index).unwrap().init_from_page_data();
+            } else {
+                unsafe{ write_bytes(ptr, 0, self.page_pool.get_page_size()) };  // logged as the before image
+                file.write().unwrap().page_sum += 1;
+                file.write().unwrap().fsm.set_free(page_index, true);
+                file.write().unwrap().add_page(self.page_pool.get_page(fd, page_index as u32).unwrap());
+            }
+        }
+        Ok(())
+    }
+    fn record_access(&self, fd : i32, hit : bool) {
+        self.page_pool.update_stats(fd, |stats| if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        });
+    }
+    fn alloc_pool_page(&mut self, fd : i32, page_index : usize) -> Result<DataPtr, String> {
+        let mut ptr = null_mut();
+        if let Some(page) = try!(self.page_pool.prepare_page()) {
+            // save tail page
+            let old_page_index = page.read().unwrap().page_index as usize;
+            ptr = page.read().unwrap().data;
+            let old_fd = page.read().unwrap().fd;
+            let dirty = page.read().unwrap().dirty;
+            if dirty {
+                self.wal.sync();  // log should reach the disk before the page
+            }
+            self.write_back_page(old_fd, old_page_index);  // clean page is only dropped
+            self.page_pool.update_stats(old_fd, |stats| {
+                stats.evictions += 1;
+                if dirty {
+                    stats.write_backs += 1;
+                }
+            });
+            page.write().unwrap().data = null_mut();
+            self.page_pool.remove_victim();
+        }
+        self.page_pool.put_page(fd, page_index as u32, ptr);
+        let page = self.page_pool.get_page(fd, page_index as u32).unwrap();
+        let data = page.read().unwrap().data;
+        Ok(data)
+    }
+    fn write_back_page(&mut self, fd : i32, page_index : usize) {
+        // save the page swapped out of the pool, which may belong to a table or an index
+        for (_, file) in self.files.iter() {
+            if file.read().unwrap().get_fd() == fd {
+                file.write().unwrap().save_page(page_index);
+                file.write().unwrap().loaded_pages.remove(&page_index);
+                return;
+            }
+        }
+        for (_, index) in self.indexes.iter() {
+            if index.read().unwrap().get_fd() == fd {
+                index.write().unwrap().save_page(page_index);
+                index.write().unwrap().loaded_pages.remove(&page_index);
+                return;
+            }
+        }
+        panic!("invalid fd");
+    }
+    pub fn get_file_by_fd(&self, fd : i32) -> TableFileRef {
+        for (_, file) in self.files.iter() {
+            if file.read().unwrap().get_fd() == fd {
+                return file.clone();
+            }
+        }
+        panic!("invalid fd");
+    }
+    pub fn create_file(&mut self, name : String, table : TableRef) {
+        // files left by a dropped table with the same name are truncated
+        let file_base = table.read().unwrap().file.clone();
+        let file = TableFile::new(file_base, table.clone(), &self.table_file_dir, true,
+            self.page_pool.get_page_size());
+        self.files.insert(name, Arc::new(RwLock::new(file)));
+        let index_list = table.read().unwrap().gen_index_list();
+        for index in index_list.iter() {
+            self.open_index(&table, index, true);
+        }
+    }
+    pub fn remove_file(&mut self, table : &String) {
+        // remove the table file and its indexes
+        for path in self.detach_file(table).iter() {
+            is_match!(remove_file(path), Ok(..));
+        }
+    }
+    fn detach_file(&mut self, table : &String) -> Vec<String> {
+        // forget the table file and its indexes, the buffered pages are discarded without writing back,
+        // return the paths of the files
+        let index_list : Vec<String> = self.get_index_list(table).iter()
+            .map(|index| index.read().unwrap().name.clone()).collect();
+        let mut paths : Vec<String> = index_list.iter().map(|name| self.detach_index_file(name)).collect();
+        let file = self.files.remove(table).unwrap();
+        let fd = file.read().unwrap().get_fd();
+        self.page_pool.remove_stats(fd);
+        let page_list : Vec<usize> = file.write().unwrap().loaded_pages.drain().map(|(i, _)| i).collect();
+        for page_index in page_list {
+            self.page_pool.remove_page(fd, page_index as u32);
+        }
+        let f = file.read().unwrap();
+        paths.push(f.saved_name.clone());
+        paths.push(f.fsm.saved_name.clone());
+        paths
+    }
+    pub fn rename_table(&mut self, table : &String, new_name : &String) {
+        // only the names in memory are changed, the files keep their names
+        let file = self.files.remove(table).unwrap();
+        file.read().unwrap().table.write().unwrap().name = new_name.clone();
+        self.files.insert(new_name.clone(), file);
+        for index in self.get_index_list(table).iter() {
+            let old_name = index.read().unwrap().name.clone();
+            self.indexes.remove(&old_name);
+            index.write().unwrap().set_table(new_name);
+            self.indexes.insert(index.read().unwrap().name.clone(), index.clone());
+        }
+    }
+    pub fn file_base_exist(&self, file_base : &String) -> bool {
+        file_exist(&path_join(&self.table_file_dir, &format!("{}.table", file_base)))
+    }
+    pub fn rewrite_file<F>(&mut self, table : &String, new_table : TableRef, convert : F) -> Result<(), String>
+            where F : Fn(Vec<TupleValue>) -> ValueList {
+        // copy the tuples into the new files of new_table, which then replace the files of the table,
+        // the old files are removed by remove_obsolete_files after the new catalog is saved
+        let tmp_name = new_table.read().unwrap().file.clone();  // named after its files until the copy is done
+        new_table.write().unwrap().name = tmp_name.clone();
+        self.create_file(tmp_name.clone(), new_table);
+        if let Err(err) = self.copy_tuples(table, &tmp_name, convert) {
+            self.remove_file(&tmp_name);
+            return Err(err);
+        }
+        let paths = self.detach_file(table);
+        self.obsolete_files.extend(paths);
+        self.rename_table(&tmp_name, table);
+        Ok(())
+    }
+    fn copy_tuples<F>(&mut self, from : &String, to : &String, convert : F) -> Result<(), String>
+            where F : Fn(Vec<TupleValue>) -> ValueList {
+        let file = self.get_file(from);
+        let page_sum = file.read().unwrap().page_sum;
+        let slot_sum = file.read().unwrap().get_page_slot_sum();
+        let attr_num = file.read().unwrap().tuple_desc.attr_desc.len();
+        for page_index in 0..page_sum {
+            try!(self.ensure_page_loaded(&file, page_index));
+            let mut tuples : Vec<Vec<TupleValue>> = Vec::new();
+            let mut tuple_index = 0;
+            while let Some(i) = file.read().unwrap().next_tuple_index(page_index, tuple_index) {
+                let position = page_index * slot_sum + i;
+                let f = file.read().unwrap();
+                tuples.push((0..attr_num).map(|attr| f.get_tuple_value(position, attr)).collect());
+                tuple_index = i + 1;
+            }
+            // the page may be swapped out by the new file
+            for values in tuples.drain(..) {
+                let mut loaded = Vec::new();
+                for value in values {
+                    loaded.push(try!(self.load_overflow_value(from, value)));
+                }
+                try!(self.insert(to, &convert(loaded)));
+            }
+        }
+        Ok(())
+    }
+    pub fn remove_obsolete_files(&mut self) {
+        // called after the catalog no longer refers to them
+        for path in self.obsolete_files.drain(..) {
+            is_match!(remove_file(&path), Ok(..));
+        }
+    }
+    fn open_index(&mut self, table : &TableRef, index : &Index, truncate : bool) -> IndexFileRef {
+        let table = table.read().unwrap();
+        let attr_index = table.get_index_attr_list(index);
+        let key_desc = attr_index.iter().map(|i| table.attr_list[*i].attr_type).collect();
+        let index = IndexFile::new(&table.name, &table.file, &index.name, attr_index,
+            key_desc, index.unique, &self.table_file_dir, truncate,
+            self.page_pool.get_page_size());
+        let index = Arc::new(RwLock::new(index));
+        self.indexes.insert(index.read().unwrap().name.clone(), index.clone());
+        index
+    }
+    pub fn pin_loaded_page(&self, file : &TableFileRef, page_index : usize) -> Result<bool, String> {
+        // return false if the page should be loaded with ensure_page_loaded first
+        let f = file.read().unwrap();
+        if f.loaded_pages.get(&page_index).is_none() {
+            return Ok(false);
+        }
+        self.record_access(f.get_fd(), true);
+        try!(self.page_pool.pin_page(f.get_fd(), page_index as u32));
+        Ok(true)
+    }
+    pub fn pin_page(&self, fd : i32, page_index : u32) -> Result<(), String> {
+        // the page should be loaded, pinning it keeps the tuple data valid
+        // after the lock of the table manager is released
+        self.page_pool.pin_page(fd, page_index)
+    }
+    pub fn unpin_page(&self, fd : i32, page_index : u32) {
+        self.page_pool.unpin_page(fd, page_index);
+    }
+    pub fn get_unpinned_num(&self) -> usize {
+        self.page_pool.get_unpinned_num()
+    }
+    pub fn get_file_fd(&self, name : &String) -> i32 {
+        self.files.get(name).unwrap().read().unwrap().get_fd()
+    }
+}
+
+pub fn get_slot_sum(tuple_desc : &TupleDesc, page_size : usize) -> usize {
+    // (n + 8 - 1) / 8 + slot_len * n <= page_size - PAGE_HEADER_SIZE,
+    // a slotted page has enough slots for the shortest tuples
+    let slot_len = if tuple_desc.variable {
+        tuple_desc.min_tuple_len + SLOT_ENTRY_SIZE
+    } else {
+        tuple_desc.tuple_len
+    };
+    (8 * (page_size - PAGE_HEADER_SIZE) - 7) / (8 * slot_len + 1)
+}
+
+pub fn tuple_fits_in_page(tuple_desc : &TupleDesc, page_size : usize) -> bool {
+    // an empty page should have the space for the longest tuple
+    let slot_sum = get_slot_sum(tuple_desc, page_size);
+    if slot_sum == 0 {
+        return false;
+    }
+    !tuple_desc.variable || PAGE_HEADER_SIZE + (slot_sum + 7) / 8 + slot_sum * SLOT_ENTRY_SIZE
+        + tuple_desc.tuple_len <= page_size
+}
+
+pub fn verify_page(page : &[u8], slot_sum : usize) -> Result<(), String> {
+    if get_page_checksum(page) != page_checksum(page) {
+        if page.iter().all(|b| *b == 0) {
+            return Ok(());  // the file was extended but the page never written
+        }
+        return Err("checksum mismatch".to_string());
+    }
+    if is_overflow_page(page) {
+        return read_overflow_page(page).map(|_| ());
+    }
+    let page_slot_sum = read_u16_le(page, 0) as usize;
+    let first_free_slot = read_u16_le(page, 2) as usize;
+    if page_slot_sum != 0 && (page_slot_sum != slot_sum || first_free_slot > slot_sum) {
+        return Err(format!("invalid page header, slot_sum {}, first_free_slot {}",
+            page_slot_sum, first_free_slot));
+    }
+    Ok(())
+}
+
+pub fn upgrade_page(page : &mut [u8], version : u32, tuple_desc : &TupleDesc,
+        page_size : usize) -> Result<(), String> {
+    // convert a page of older version step by step, the checksum is left to the caller
+    for v in version..TABLE_VERSION {
+        try!(match v {
+            1 => upgrade_page_from_v1(page, tuple_desc, page_size),
+            _ => unreachable!(),
+        });
+    }
+    Ok(())
+}
+
+fn upgrade_page_from_v1(page : &mut [u8], tuple_desc : &TupleDesc, page_size : usize) -> Result<(), String> {
+    // the header size is unchanged, only the numbers are converted to little endian
+    let slot_sum = read_u32_ne(page, 0) as usize;
+    let first_free_slot = read_u32_ne(page, 4) as usize;
+    if slot_sum == 0 {
+        return Ok(());  // never initialized
+    }
+    if slot_sum != get_slot_sum(tuple_desc, page_size) || first_free_slot > slot_sum {
+        return Err(format!("invalid page header {} {}", slot_sum, first_free_slot));
+    }
+    write_u16_le(page, 0, slot_sum as u16);
+    write_u16_le(page, 2, first_free_slot as u16);
+    write_u32_le(page, 4, 0);
+    let bitmap_size = (slot_sum + 7) / 8;
+    let mut attr_offsets = Vec::new();
+    let mut offset = tuple_desc.null_bitmap_len;
+    for attr_type in tuple_desc.attr_desc.iter() {
+        match attr_type {
+            &AttrType::Int | &AttrType::Float => {
+                attr_offsets.push(offset);
+                offset += 4;
+            }
+            _ => offset += attr_len(attr_type),  // no varchar in version 1
+        }
+    }
+    for slot in 0..slot_sum {
+        if page[PAGE_HEADER_SIZE + slot / 8] & (1 << (slot % 8)) == 0 {
+            continue;
+        }
+        let tuple = PAGE_HEADER_SIZE + bitmap_size + slot * tuple_desc.tuple_len;
+        for attr_offset in attr_offsets.iter() {
+            let n = read_u32_ne(page, tuple + attr_offset);
+            write_u32_le(page, tuple + attr_offset, n);
+        }
+    }
+    Ok(())
+}
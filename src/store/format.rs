@@ -0,0 +1,212 @@
+use std::vec::Vec;
+use std::fs::{OpenOptions, read_dir};
+use std::io::{Read, Write};
+use ::utils::file::{file_path, file_exist};
+use ::utils::libwrapper::get_page_size;
+use ::utils::endian::{push_u32_le, read_u32_le, write_u32_le};
+use ::utils::checksum::{crc32, crc32_init, crc32_update, crc32_finish};
+
+
+// The database header file, created together with the table file directory:
+// (1) magic "BLSTOISE" (8 bytes)
+// (2) format version (4 bytes, little endian)
+// (3) page size (4 bytes, little endian)
+// The page size is chosen by the config when the database is created,
+// all the table and index files use it afterwards whatever the host is.
+
+pub const DB_MAGIC : &'static [u8] = b"BLSTOISE";
+pub const DB_VERSION : u32 = 1;
+pub const DEFAULT_PAGE_SIZE : usize = 4096;
+pub const MIN_PAGE_SIZE : usize = 4096;
+pub const MAX_PAGE_SIZE : usize = 65536;
+const DB_HEADER_FILE : &'static str = "blastoise.db";
+const DB_HEADER_SIZE : usize = 16;
+
+// The first page of a table file only saves the header for alignment:
+// (1) magic "BLSTABLE" (8 bytes)
+// (2) format version (4 bytes, little endian)
+// (3) page_sum (4 bytes, little endian)
+// (4) first_free_page (4 bytes, little endian)
+// (5) crc32 of (1) to (4) (4 bytes, little endian)
+// Each of the following pages starts with the page header:
+// (1) slot_sum (2 bytes, little endian)
+// (2) first_free_slot (2 bytes, little endian)
+// (3) crc32 of the page with this field as zero (4 bytes, little endian)
+// then the slot bitmap and the tuples, whose ints and floats are little endian.
+// The tables with varchar attributes use slotted pages instead, which have an entry
+// of the offset and the length (2 bytes each, little endian) for each slot after the bitmap,
+// and the variable-length tuples are placed from the end of the page.
+// Text and blob values too long for the tuples are saved in chains of overflow pages,
+// which start with:
+// (1) 0xffff in place of slot_sum (2 bytes)
+// (2) the length of the data in this page (2 bytes, little endian)
+// (3) crc32 like the other pages (4 bytes, little endian)
+// (4) index of the next page in the chain, 0xffffffff for the last one (4 bytes, little endian)
+// then the data.
+// Version 1 files have no magic, the table header is page_sum and first_free_page
+// and the page header is slot_sum and first_free_slot, all native u32,
+// and the tuples are in native endian.
+
+pub const TABLE_MAGIC : &'static [u8] = b"BLSTABLE";
+pub const TABLE_VERSION : u32 = 2;
+pub const LEGACY_TABLE_VERSION : u32 = 1;
+pub const TABLE_HEADER_SIZE : usize = 24;
+pub const PAGE_HEADER_SIZE : usize = 8;
+pub const SLOT_ENTRY_SIZE : usize = 4;
+pub const OVERFLOW_PAGE_MARK : u16 = 0xffff;
+pub const OVERFLOW_PAGE_HEADER_SIZE : usize = 12;
+pub const NO_NEXT_PAGE : u32 = 0xffffffff;
+const PAGE_CHECKSUM_OFFSET : usize = 4;
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DbHeader {
+    pub version : u32,
+    pub page_size : usize,
+}
+
+impl DbHeader {
+    pub fn open(dir : &String, page_size : usize) -> Result<DbHeader, String> {
+        // create the header with the given page size for a new database
+        let full_path = file_path(dir, &DB_HEADER_FILE.to_string());
+        let header = try!(DbHeader::read(dir).map_err(|err|
+            format!("invalid database header {}: {}", full_path, err)));
+        if let Some(header) = header {
+            return Ok(header);
+        }
+        let header = if try!(has_data_file(dir)) {
+            // created before the header existed, using the page size of the host
+            DbHeader{ version : DB_VERSION, page_size : get_page_size() }
+        } else {
+            try!(check_page_size(page_size).map_err(|err| format!("invalid page_size in config: {}", err)));
+            DbHeader{ version : DB_VERSION, page_size : page_size }
+        };
+        let result = OpenOptions::new().write(true).create(true).truncate(true).open(&full_path)
+            .and_then(|mut file| file.write_all(&header.encode()).and_then(|_| file.sync_all()));
+        try!(result.map_err(|err| format!("can not create {}: {}", full_path, err)));
+        Ok(header)
+    }
+    pub fn read(dir : &String) -> Result<Option<DbHeader>, String> {
+        // return None if the header does not exist
+        let full_path = file_path(dir, &DB_HEADER_FILE.to_string());
+        if !file_exist(&full_path) {
+            return Ok(None);
+        }
+        let mut buf = Vec::new();
+        try!(OpenOptions::new().read(true).open(&full_path).and_then(|mut file| file.read_to_end(&mut buf))
+            .map_err(|err| format!("{}", err)));
+        DbHeader::decode(&buf).map(Some)
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(DB_HEADER_SIZE);
+        buf.extend_from_slice(DB_MAGIC);
+        push_u32_le(&mut buf, self.version);
+        push_u32_le(&mut buf, self.page_size as u32);
+        buf
+    }
+    pub fn decode(buf : &[u8]) -> Result<DbHeader, String> {
+        if buf.len() < DB_HEADER_SIZE || &buf[..DB_MAGIC.len()] != DB_MAGIC {
+            return Err("not a blastoise database".to_string());
+        }
+        let version = read_u32_le(buf, 8);
+        if version > DB_VERSION {
+            return Err(format!("unsupported version {}", version));
+        }
+        let page_size = read_u32_le(buf, 12) as usize;
+        try!(check_page_size(page_size));
+        Ok(DbHeader{ version : version, page_size : page_size })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TableHeader {
+    pub version : u32,
+    pub page_sum : usize,
+    pub first_free_page : usize,
+}
+
+impl TableHeader {
+    pub fn encode(&self) -> Vec<u8> {
+        // saved in the version of the file, which is only old before the file is upgraded
+        let mut buf = Vec::with_capacity(TABLE_HEADER_SIZE);
+        if self.version == LEGACY_TABLE_VERSION {
+            buf.extend_from_slice(&(self.page_sum as u32).to_ne_bytes());
+            buf.extend_from_slice(&(self.first_free_page as u32).to_ne_bytes());
+            return buf;
+        }
+        buf.extend_from_slice(TABLE_MAGIC);
+        push_u32_le(&mut buf, self.version);
+        push_u32_le(&mut buf, self.page_sum as u32);
+        push_u32_le(&mut buf, self.first_free_page as u32);
+        let sum = crc32(&buf);
+        push_u32_le(&mut buf, sum);
+        buf
+    }
+    pub fn decode(buf : &[u8]) -> Result<TableHeader, String> {
+        if buf.len() < 8 {
+            // the header has not been saved yet
+            return Ok(TableHeader{ version : TABLE_VERSION, page_sum : 0, first_free_page : 0 });
+        }
+        if &buf[..TABLE_MAGIC.len()] != TABLE_MAGIC {
+            return Ok(TableHeader{
+                version : LEGACY_TABLE_VERSION,
+                page_sum : read_u32_ne(buf, 0) as usize,
+                first_free_page : read_u32_ne(buf, 4) as usize,
+            });
+        }
+        if buf.len() < TABLE_HEADER_SIZE {
+            return Err("truncated header".to_string());
+        }
+        if crc32(&buf[..TABLE_HEADER_SIZE - 4]) != read_u32_le(buf, TABLE_HEADER_SIZE - 4) {
+            return Err("header checksum mismatch".to_string());
+        }
+        let version = read_u32_le(buf, 8);
+        if version <= LEGACY_TABLE_VERSION || version > TABLE_VERSION {
+            return Err(format!("unsupported version {}", version));
+        }
+        Ok(TableHeader{
+            version : version,
+            page_sum : read_u32_le(buf, 12) as usize,
+            first_free_page : read_u32_le(buf, 16) as usize,
+        })
+    }
+}
+
+pub fn page_checksum(page : &[u8]) -> u32 {
+    let crc = crc32_update(crc32_init(), &page[..PAGE_CHECKSUM_OFFSET]);
+    let crc = crc32_update(crc, &[0; 4]);
+    crc32_finish(crc32_update(crc, &page[PAGE_HEADER_SIZE..]))
+}
+
+pub fn set_page_checksum(page : &mut [u8]) {
+    let sum = page_checksum(page);
+    write_u32_le(page, PAGE_CHECKSUM_OFFSET, sum);
+}
+
+pub fn get_page_checksum(page : &[u8]) -> u32 {
+    read_u32_le(page, PAGE_CHECKSUM_OFFSET)
+}
+
+pub fn read_u32_ne(buf : &[u8], pos : usize) -> u32 {
+    u32::from_ne_bytes([buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]])
+}
+
+pub fn check_page_size(page_size : usize) -> Result<(), String> {
+    if page_size < MIN_PAGE_SIZE || page_size > MAX_PAGE_SIZE || !page_size.is_power_of_two() {
+        return Err(format!("page size {} should be a power of 2 from {} to {}",
+            page_size, MIN_PAGE_SIZE, MAX_PAGE_SIZE));
+    }
+    Ok(())
+}
+
+fn has_data_file(dir : &String) -> Result<bool, String> {
+    let entries = try!(read_dir(dir).map_err(|err| format!("can not read {}: {}", dir, err)));
+    for entry in entries {
+        let entry = try!(entry.map_err(|err| format!("can not read {}: {}", dir, err)));
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.ends_with(".table") || name.ends_with(".index") {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
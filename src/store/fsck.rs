@@ -0,0 +1,290 @@
+use std::vec::Vec;
+use std::cmp::min;
+use std::collections::{HashSet, HashMap};
+use std::fs::{OpenOptions, read_dir};
+use std::io::Read;
+use std::slice::from_raw_parts_mut;
+use std::sync::{Arc, RwLock};
+use ::utils::file::{file_path, file_exist};
+use ::utils::libwrapper::get_page_size;
+use super::table::{Table, TABLE_META_FILE, parse_catalog};
+use super::tuple::{TupleDesc, TupleValue};
+use super::buffer::Page;
+use super::file::{FilePage, get_slot_sum, verify_page, upgrade_page};
+use super::overflow::{is_overflow_page, read_overflow_page, read_overflow_chain};
+use super::format::{DbHeader, TableHeader, TABLE_VERSION, TABLE_HEADER_SIZE, set_page_checksum};
+use super::wal::WAL_FILE;
+
+
+// Offline consistency checker, which only reads the files and never recovers the database,
+// so the changes still in the log of a database not shut down cleanly are not seen.
+
+#[derive(Debug, Default)]
+pub struct FsckReport {
+    pub lines : Vec<String>,
+    pub problem_num : usize,
+}
+
+impl FsckReport {
+    fn info(&mut self, msg : String) {
+        self.lines.push(msg);
+    }
+    fn problem(&mut self, msg : String) {
+        self.lines.push(format!("  problem: {}", msg));
+        self.problem_num += 1;
+    }
+    pub fn is_ok(&self) -> bool {
+        self.problem_num == 0
+    }
+    pub fn desc(&self) -> String {
+        let mut result = String::new();
+        for line in self.lines.iter() {
+            result.push_str(line);
+            result.push('\n');
+        }
+        match self.problem_num {
+            0 => result.push_str("no problem found\n"),
+            n => result.push_str(&format!("{} problem(s) found\n", n)),
+        }
+        result
+    }
+}
+
+pub fn run_fsck(table_meta_dir : &String, table_file_dir : &String) -> bool {
+    // print the report, return false if any problem found
+    let report = check_database(table_meta_dir, table_file_dir);
+    print!("{}", report.desc());
+    report.is_ok()
+}
+
+pub fn check_database(table_meta_dir : &String, table_file_dir : &String) -> FsckReport {
+    let mut report = FsckReport::default();
+    report.info(format!("checking {} and {}", table_meta_dir, table_file_dir));
+    let page_size = match DbHeader::read(table_file_dir) {
+        Ok(Some(header)) => {
+            report.info(format!("database version {}, page size {}", header.version, header.page_size));
+            header.page_size
+        }
+        Ok(None) => {
+            report.info(format!("no database header, using the page size of the host {}", get_page_size()));
+            get_page_size()
+        }
+        Err(err) => {
+            report.problem(format!("invalid database header: {}", err));
+            return report;
+        }
+    };
+    let wal_path = file_path(table_file_dir, &WAL_FILE.to_string());
+    match read_file(&wal_path) {
+        Ok(Some(ref data)) if !data.is_empty() =>
+            report.info("the log is not empty, it will be applied when the server starts".to_string()),
+        Ok(..) => (),
+        Err(err) => report.problem(err),
+    }
+    let meta_path = file_path(table_meta_dir, &TABLE_META_FILE.to_string());
+    let json = match read_file(&meta_path) {
+        Ok(Some(data)) => String::from_utf8_lossy(&data).into_owned(),
+        Ok(None) => {
+            report.problem(format!("{} not found", meta_path));
+            return report;
+        }
+        Err(err) => {
+            report.problem(err);
+            return report;
+        }
+    };
+    let tables = match parse_catalog(&json) {
+        Ok(catalog) => {
+            report.info(format!("catalog version {}", catalog.version));
+            catalog.tables
+        }
+        Err(err) => {
+            report.problem(format!("invalid {}: {}", meta_path, err));
+            return report;
+        }
+    };
+    for (_, table) in tables.iter() {
+        check_table(&mut report, table, table_file_dir, page_size);
+    }
+    let mut file_list = match list_dir(table_file_dir) {
+        Ok(file_list) => file_list,
+        Err(err) => {
+            report.problem(err);
+            return report;
+        }
+    };
+    file_list.sort();
+    let file_bases : HashSet<&String> = tables.values().map(|table| &table.file).collect();
+    for name in file_list.iter().filter(|name| name.ends_with(".table")) {
+        if !file_bases.contains(&name[..name.len() - ".table".len()].to_string()) {
+            report.problem(format!("{} has no table in {}", name, TABLE_META_FILE));
+        }
+    }
+    report
+}
+
+fn check_table(report : &mut FsckReport, table : &Table, table_file_dir : &String, page_size : usize) {
+    report.info(format!("table {}", table.name));
+    let path = file_path(table_file_dir, &format!("{}.table", table.file));
+    let data = match read_file(&path) {
+        Ok(Some(data)) => data,
+        Ok(None) => {
+            report.problem(format!("{} not found", path));
+            return;
+        }
+        Err(err) => {
+            report.problem(err);
+            return;
+        }
+    };
+    let header = match TableHeader::decode(&data[..min(data.len(), TABLE_HEADER_SIZE)]) {
+        Ok(header) => header,
+        Err(err) => {
+            report.problem(format!("invalid header: {}", err));
+            return;
+        }
+    };
+    if header.version != TABLE_VERSION {
+        report.info(format!("  format version {}, upgraded when the server starts", header.version));
+    }
+    // the header alone is saved before any page is added
+    let expected_len = page_size * (header.page_sum + 1);
+    if (header.page_sum == 0 && data.len() > page_size) || (header.page_sum > 0 && data.len() != expected_len) {
+        report.problem(format!("file length {} does not match page_sum {}, expected {}",
+            data.len(), header.page_sum, expected_len));
+    }
+    let tuple_desc = table.gen_tuple_desc();
+    let mut keys = HashSet::new();
+    let mut tuple_num = 0;
+    let mut overflow_pages = HashSet::new();
+    let mut overflow_values = Vec::new();
+    for page_index in 0..header.page_sum {
+        let begin = page_size * (page_index + 1);
+        if begin + page_size > data.len() {
+            break;  // reported by the length check
+        }
+        let mut page = data[begin..begin + page_size].to_vec();
+        if header.version != TABLE_VERSION {
+            if let Err(err) = upgrade_page(&mut page, header.version, &tuple_desc, page_size) {
+                report.problem(format!("page {}: {}", page_index, err));
+                continue;
+            }
+            set_page_checksum(&mut page);  // older versions have no checksum
+        }
+        if is_overflow_page(&page) {
+            match verify_page(&page, 0) {
+                Ok(()) => { overflow_pages.insert(page_index); }
+                Err(err) => report.problem(format!("page {}: {}", page_index, err)),
+            }
+            continue;
+        }
+        tuple_num += check_page(report, table, &tuple_desc, page_index, &page, &mut keys, &mut overflow_values);
+    }
+    check_overflow_chains(report, &data, page_size, &overflow_pages, &overflow_values);
+    report.info(format!("  {} pages, {} tuples", header.page_sum, tuple_num));
+    if !overflow_pages.is_empty() {
+        report.info(format!("  {} overflow pages", overflow_pages.len()));
+    }
+}
+
+fn check_overflow_chains(report : &mut FsckReport, data : &[u8], page_size : usize,
+        overflow_pages : &HashSet<usize>, overflow_values : &Vec<(String, usize, usize)>) {
+    // every overflow page should be in the chain of exactly one value
+    let mut owners : HashMap<usize, &String> = HashMap::new();
+    for &(ref value, len, first_page) in overflow_values.iter() {
+        let mut chain = Vec::new();
+        let result = read_overflow_chain(len, first_page, |page_index| {
+            if !overflow_pages.contains(&page_index) {
+                return Err(format!("page {} is not a valid overflow page", page_index));
+            }
+            chain.push(page_index);
+            let begin = page_size * (page_index + 1);
+            read_overflow_page(&data[begin..begin + page_size]).map(|(d, next)| (d.to_vec(), next))
+        }, |_| ());
+        if let Err(err) = result {
+            report.problem(format!("{}: {}", value, err));
+        }
+        for page_index in chain {
+            if let Some(owner) = owners.insert(page_index, value) {
+                report.problem(format!("overflow page {} is used by both {} and {}", page_index, owner, value));
+            }
+        }
+    }
+    let mut unused : Vec<&usize> = overflow_pages.iter().filter(|i| !owners.contains_key(i)).collect();
+    unused.sort();
+    for page_index in unused {
+        report.problem(format!("overflow page {} is not used by any tuple", page_index));
+    }
+}
+
+fn check_page(report : &mut FsckReport, table : &Table, tuple_desc : &TupleDesc,
+        page_index : usize, page : &[u8], keys : &mut HashSet<String>,
+        overflow_values : &mut Vec<(String, usize, usize)>) -> usize {
+    // return the number of tuples in the page, the values in overflow pages are checked later
+    let slot_sum = get_slot_sum(tuple_desc, page.len());
+    if let Err(err) = verify_page(page, slot_sum) {
+        report.problem(format!("page {}: {}", page_index, err));
+        return 0;
+    }
+    let mut mem_page = Page::new(0, page_index as u32);
+    mem_page.alloc(page.len());
+    unsafe{ from_raw_parts_mut::<u8>(mem_page.data as *mut u8, page.len()) }.copy_from_slice(page);
+    let mut file_page = FilePage::new(Arc::new(RwLock::new(mem_page)), tuple_desc, page.len());
+    if !file_page.is_initialized() {
+        return 0;
+    }
+    file_page.init_from_page_data();
+    let first_free_slot = file_page.bitmap.get_first_free_slot();
+    if file_page.header.first_free_slot != first_free_slot {
+        report.problem(format!("page {}: first_free_slot is {}, but the first free slot in the bitmap is {}",
+            page_index, file_page.header.first_free_slot, first_free_slot));
+    }
+    let mut tuple_num = 0;
+    let mut slot = file_page.bitmap.next_tuple_index(0);
+    while slot < slot_sum {
+        tuple_num += 1;
+        let mut key = Vec::new();
+        for (i, attr) in table.attr_list.iter().enumerate() {
+            let value = file_page.get_tuple_value(slot, i, tuple_desc);
+            if !attr.nullable && is_match!(value, TupleValue::Null) {
+                report.problem(format!("page {} slot {}: null in not null attribute {}",
+                    page_index, slot, attr.name));
+            }
+            if attr.primary {
+                key.push(format!("{:?}", value));
+            }
+            if let TupleValue::Overflow{len, first_page} = value {
+                overflow_values.push((format!("page {} slot {} attribute {}", page_index, slot, attr.name),
+                    len, first_page));
+            }
+        }
+        let key = key.join(", ");
+        if !keys.insert(key.clone()) {
+            report.problem(format!("page {} slot {}: duplicate primary key {}",
+                page_index, slot, key));
+        }
+        slot = file_page.bitmap.next_tuple_index(slot + 1);
+    }
+    tuple_num
+}
+
+fn read_file(path : &String) -> Result<Option<Vec<u8>>, String> {
+    // return None if the file does not exist
+    if !file_exist(path) {
+        return Ok(None);
+    }
+    let mut data = Vec::new();
+    try!(OpenOptions::new().read(true).open(path).and_then(|mut file| file.read_to_end(&mut data))
+        .map_err(|err| format!("can not read {}: {}", path, err)));
+    Ok(Some(data))
+}
+
+fn list_dir(dir : &String) -> Result<Vec<String>, String> {
+    let mut file_list = Vec::new();
+    let entries = try!(read_dir(dir).map_err(|err| format!("can not read {}: {}", dir, err)));
+    for entry in entries {
+        let entry = try!(entry.map_err(|err| format!("can not read {}: {}", dir, err)));
+        file_list.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    Ok(file_list)
+}
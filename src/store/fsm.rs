@@ -0,0 +1,115 @@
+use std::vec::Vec;
+use std::fs::{OpenOptions, File};
+use std::io::{Read, Write, Seek, SeekFrom};
+use ::utils::file::path_join;
+
+
+// Free-space map of a table file, saved in `<table>.fsm` with one bit for each page.
+// A set bit means the page may have free slots, while a cleared bit means the page is full.
+// Bits are only hints: inserting clears the bit of the page found to be full,
+// so setting a bit for a full page only costs a page load, and is what we do
+// whenever we are not sure, e.g. after rollback or recovery.
+
+#[derive(Debug)]
+pub struct FreeSpaceMap {
+    pub saved_name : String,
+    pub file : File,
+    bits : Vec<u8>,
+    dirty : bool,
+}
+
+impl FreeSpaceMap {
+    pub fn new(table : &String, dir : &String, truncate : bool) -> FreeSpaceMap {
+        let saved_name = path_join(dir, &gen_fsm_file_name(table));
+        let file = check_ok!(OpenOptions::new().read(true).write(true).create(true)
+            .truncate(truncate).open(&saved_name));
+        FreeSpaceMap{
+            saved_name : saved_name,
+            file : file,
+            bits : Vec::new(),
+            dirty : false,
+        }
+    }
+    pub fn init_from_file(&mut self, page_sum : usize) {
+        is_match!(self.file.seek(SeekFrom::Start(0)), Ok(..));
+        self.bits.clear();
+        check_ok!(self.file.read_to_end(&mut self.bits));
+        if self.bits.is_empty() {
+            // file lost or created by older version
+            for i in 0..page_sum {
+                self.set_free(i, true);
+            }
+        }
+    }
+    pub fn save_to_file(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        is_match!(self.file.seek(SeekFrom::Start(0)), Ok(..));
+        is_match!(self.file.write_all(&self.bits), Ok(..));
+        check_ok!(self.file.set_len(self.bits.len() as u64));
+        self.dirty = false;
+    }
+    pub fn sync(&mut self) {
+        check_ok!(self.file.sync_data());
+    }
+    pub fn may_have_free_slot(&self, page_index : usize) -> bool {
+        match self.bits.get(page_index / 8) {
+            Some(b) => (b >> (page_index % 8)) & 1 == 1,
+            None => false,
+        }
+    }
+    pub fn set_free(&mut self, page_index : usize, free : bool) {
+        if self.may_have_free_slot(page_index) == free {
+            return;
+        }
+        let byte_offset = page_index / 8;
+        if byte_offset >= self.bits.len() {
+            self.bits.resize(byte_offset + 1, 0);
+        }
+        let mask = 1 << (page_index % 8);
+        if free {
+            self.bits[byte_offset] |= mask;
+        } else {
+            self.bits[byte_offset] &= !mask;
+        }
+        self.dirty = true;
+    }
+    pub fn truncate(&mut self, page_sum : usize) {
+        // forget the pages after page_sum
+        let byte_len = (page_sum + 7) / 8;
+        if self.bits.len() > byte_len {
+            self.bits.truncate(byte_len);
+            self.dirty = true;
+        }
+        if page_sum % 8 != 0 && self.bits.len() == byte_len {
+            let mask = (1 << (page_sum % 8)) - 1;
+            if self.bits[byte_len - 1] & !mask != 0 {
+                self.bits[byte_len - 1] &= mask;
+                self.dirty = true;
+            }
+        }
+    }
+    pub fn next_free_page(&self, from : usize, page_sum : usize) -> usize {
+        // return page_sum if no page after `from` may have free slots
+        let mut page_index = from;
+        while page_index < page_sum {
+            let byte = match self.bits.get(page_index / 8) {
+                Some(b) => *b >> (page_index % 8),
+                None => return page_sum,
+            };
+            if byte == 0 {
+                page_index = (page_index / 8 + 1) * 8;  // skip the whole byte
+            } else if byte & 1 == 1 {
+                return page_index;
+            } else {
+                page_index += 1;
+            }
+        }
+        page_sum
+    }
+}
+
+pub fn gen_fsm_file_name(table : &String) -> String {
+    format!("{}.fsm", table)
+}
@@ -0,0 +1,484 @@
+use std::vec::Vec;
+use std::collections::HashMap;
+use std::fs::{OpenOptions, File};
+use std::os::unix::io::AsRawFd;
+use std::slice::{from_raw_parts, from_raw_parts_mut};
+use std::io::{Read, Write, Seek, SeekFrom};
+use std::sync::{Arc, RwLock};
+use ::utils::file::path_join;
+use ::utils::endian::{push_u32_le, push_u32_be, push_u64_be, read_u32_le};
+use super::buffer::{DataPtr, PageRef};
+use super::table::AttrType;
+use super::tuple::TupleValue;
+use super::file::{TableFile, TableFileManager};
+
+
+// A B+tree stored in its own file, whose pages are cached in the PagePool as table pages are.
+// Page 0 is the meta page:
+// (1) root page index (4 bytes), 0 for empty tree
+// (2) page sum (4 bytes), including the meta page
+// The other pages are tree nodes:
+// (1) is_leaf (4 bytes)
+// (2) key_num (4 bytes)
+// (3) next leaf page index (4 bytes), 0 for the last leaf
+// (4) for leaf node: key_num * (key, tuple position)
+//     for internal node: child, key_num * (key, child)
+// All the numbers are little endian. Keys are compared with memcmp, see encode_key.
+// Every key ends with 4 bytes of tuple position in big endian to tell apart the tuples
+// with the same attribute values. For unique index it's 0 unless some attribute is null,
+// so that inserting a duplicate key fails while nulls never conflict.
+// Deleting only removes the entry from the leaf, nodes are never merged.
+
+pub const PRIMARY_INDEX : &'static str = "primary";
+const NODE_HEADER_SIZE : usize = 12;
+const NO_PAGE : usize = 0;
+
+
+pub type IndexFileRef = Arc<RwLock<IndexFile>>;
+
+#[derive(Debug)]
+pub struct IndexFile {
+    pub saved_name : String,
+    pub table : String,
+    pub name : String,  // table.index, used in log records
+    pub file : File,
+    pub loaded_pages : HashMap<usize, PageRef>,
+    pub attr_index : Vec<usize>,  // position of indexed attributes in tuple
+    pub key_desc : Vec<AttrType>,
+    pub key_len : usize,
+    pub unique : bool,
+    pub page_size : usize,
+}
+
+impl IndexFile {
+    pub fn new(table : &String, file_base : &String, index : &String, attr_index : Vec<usize>,
+            key_desc : Vec<AttrType>, unique : bool, dir : &String, truncate : bool,
+            page_size : usize) -> IndexFile {
+        // file_base is the base name of the table files
+        let saved_name = path_join(dir, &gen_index_file_name(file_base, index));
+        let file = check_ok!(OpenOptions::new().read(true).write(true).create(true)
+            .truncate(truncate).open(&saved_name));
+        let key_len : usize = key_desc.iter().map(|t| 1 + get_key_attr_len(t)).sum::<usize>() + 4;
+        IndexFile{
+            saved_name : saved_name,
+            table : table.clone(),
+            name : format!("{}.{}", table, index),
+            file : file,
+            loaded_pages : HashMap::new(),
+            attr_index : attr_index,
+            key_desc : key_desc,
+            key_len : key_len,
+            unique : unique,
+            page_size : page_size,
+        }
+    }
+    pub fn index_name(&self) -> &str {
+        // without the table
+        &self.name[self.table.len() + 1..]
+    }
+    pub fn set_table(&mut self, table : &String) {
+        // when the table is renamed, the file keeps its name
+        let index = self.index_name().to_string();
+        self.name = format!("{}.{}", table, index);
+        self.table = table.clone();
+    }
+    pub fn read_page_from_file(&mut self, data : DataPtr, page_index : usize) {
+        // pages which have never been written back are read as zero
+        let page_size = self.page_size;
+        let buf = unsafe{ from_raw_parts_mut::<u8>(data as *mut u8, page_size) };
+        is_match!(self.file.seek(SeekFrom::Start((page_size * page_index) as u64)), Ok(..));
+        let mut read_len = 0;
+        while read_len < page_size {
+            match check_ok!(self.file.read(&mut buf[read_len..])) {
+                0 => break,
+                n => read_len += n,
+            }
+        }
+        for b in buf[read_len..].iter_mut() {
+            *b = 0;
+        }
+    }
+    pub fn save_page(&mut self, page_index : usize) -> bool {
+        // return false if the page is clean
+        let page_size = self.page_size;
+        let page = self.loaded_pages.get(&page_index).unwrap();
+        if !page.read().unwrap().dirty {
+            return false;
+        }
+        let data = page.read().unwrap().data;
+        is_match!(self.file.seek(SeekFrom::Start((page_size * page_index) as u64)), Ok(..));
+        is_match!(self.file.write_all(unsafe{
+            from_raw_parts::<u8>(data as *const u8, page_size)
+        }), Ok(..));
+        page.write().unwrap().mark_clean();
+        true
+    }
+    pub fn save_to_file(&mut self) -> usize {
+        // return the number of pages written
+        let index_list : Vec<_> = self.loaded_pages.iter().map(|(i, _)| *i).collect();
+        index_list.iter().filter(|i| self.save_page(**i)).count()
+    }
+    pub fn sync(&mut self) {
+        check_ok!(self.file.sync_data());
+    }
+    pub fn write_page_range(&mut self, page_index : usize, offset : usize, data : &[u8]) {
+        // write to the file directly, only used in recovery
+        let page_size = self.page_size;
+        assert!(offset + data.len() <= page_size);
+        let file_offset = page_size * page_index + offset;
+        is_match!(self.file.seek(SeekFrom::Start(file_offset as u64)), Ok(..));
+        is_match!(self.file.write_all(data), Ok(..));
+    }
+    pub fn get_page_data(&self, page_index : usize) -> DataPtr {
+        self.loaded_pages.get(&page_index).unwrap().read().unwrap().data
+    }
+    pub fn page_snapshot(&self, page_index : usize) -> Vec<u8> {
+        let data = self.get_page_data(page_index);
+        unsafe{ from_raw_parts::<u8>(data as *const u8, self.page_size) }.to_vec()
+    }
+    pub fn add_page(&mut self, mem_page : PageRef) {
+        let index = mem_page.read().unwrap().page_index as usize;
+        self.loaded_pages.insert(index, mem_page);
+    }
+    pub fn get_fd(&self) -> i32 {
+        self.file.as_raw_fd()
+    }
+    pub fn max_key_num(&self) -> usize {
+        // an internal node stores one more child than keys
+        (self.page_size - NODE_HEADER_SIZE - 4) / (self.key_len + 4)
+    }
+    pub fn gen_key(&self, values : &Vec<TupleValue>) -> Vec<u8> {
+        // without tuple position, used as the prefix in lookup
+        encode_key(values, &self.key_desc)
+    }
+    pub fn gen_entry_key(&self, values : &Vec<TupleValue>, position : usize) -> Vec<u8> {
+        let mut key = self.gen_key(values);
+        let has_null = values.iter().any(|v| is_match!(v, &TupleValue::Null));
+        push_u32_be(&mut key, if self.unique && !has_null { 0 } else { position as u32 });
+        key
+    }
+    pub fn gen_tuple_key(&self, file : &TableFile, position : usize) -> Vec<u8> {
+        // the page of the tuple should be loaded
+        let values = self.attr_index.iter().map(|i| file.get_tuple_value(position, *i)).collect();
+        self.gen_entry_key(&values, position)
+    }
+}
+
+pub fn gen_index_file_name(table : &String, index : &String) -> String {
+    format!("{}.{}.index", table, index)
+}
+
+fn get_key_attr_len(attr_type : &AttrType) -> usize {
+    match attr_type {
+        &AttrType::Int | &AttrType::Float | &AttrType::Date => 4,
+        &AttrType::BigInt | &AttrType::Double | &AttrType::Decimal{..} | &AttrType::Time | &AttrType::Timestamp => 8,
+        &AttrType::Boolean => 1,
+        &AttrType::Char{len} | &AttrType::Varchar{len} => len,
+        &AttrType::Text | &AttrType::Blob => unreachable!(),  // rejected by sem_check
+    }
+}
+
+pub fn encode_key(values : &Vec<TupleValue>, key_desc : &Vec<AttrType>) -> Vec<u8> {
+    // Every attribute starts with a byte which is 0 for null, so null is the smallest.
+    // Numbers are saved in big endian with sign bit flipped so that the bytes keep the order.
+    assert_eq!(values.len(), key_desc.len());
+    let mut key = Vec::new();
+    for (v, attr_type) in values.iter().zip(key_desc.iter()) {
+        let len = get_key_attr_len(attr_type);
+        match v {
+            &TupleValue::Null => {
+                key.push(0);
+                key.extend((0..len).map(|_| 0));
+            }
+            &TupleValue::Int(n) | &TupleValue::Date(n) => {
+                key.push(1);
+                push_u32_be(&mut key, (n as u32) ^ (1 << 31));
+            }
+            &TupleValue::Boolean(b) => {
+                key.push(1);
+                key.push(b as u8);
+            }
+            &TupleValue::Float(n) => {
+                let n = if n == 0.0 { 0.0 } else { n };  // -0.0 == 0.0
+                let bits = n.to_bits();
+                key.push(1);
+                push_u32_be(&mut key, if bits >> 31 == 1 { !bits } else { bits ^ (1 << 31) });
+            }
+            &TupleValue::BigInt(n) | &TupleValue::Decimal(n, _) | &TupleValue::Time(n) | &TupleValue::Timestamp(n) => {
+                // decimals of an attribute have the same scale
+                key.push(1);
+                push_u64_be(&mut key, (n as u64) ^ (1 << 63));
+            }
+            &TupleValue::Double(n) => {
+                let n = if n == 0.0 { 0.0 } else { n };
+                let bits = n.to_bits();
+                key.push(1);
+                push_u64_be(&mut key, if bits >> 63 == 1 { !bits } else { bits ^ (1 << 63) });
+            }
+            &TupleValue::Char(ref s) => {
+                key.push(1);
+                let bytes = s.as_bytes();
+                let l = if bytes.len() < len { bytes.len() } else { len };
+                key.extend_from_slice(&bytes[..l]);
+                key.extend((l..len).map(|_| 0));
+            }
+            &TupleValue::Bytes(..) | &TupleValue::Overflow{..} => unreachable!(),
+        }
+    }
+    key
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IndexMeta {
+    pub root : usize,
+    pub page_sum : usize,
+}
+
+impl IndexMeta {
+    pub fn decode(data : DataPtr) -> IndexMeta {
+        let buf = unsafe{ from_raw_parts::<u8>(data as *const u8, 8) };
+        let page_sum = read_u32_le(buf, 4) as usize;
+        IndexMeta{
+            root : read_u32_le(buf, 0) as usize,
+            page_sum : if page_sum == 0 { 1 } else { page_sum },  // new file
+        }
+    }
+    pub fn encode(&self, data : DataPtr) {
+        let mut buf = Vec::new();
+        push_u32_le(&mut buf, self.root as u32);
+        push_u32_le(&mut buf, self.page_sum as u32);
+        unsafe{ from_raw_parts_mut::<u8>(data as *mut u8, buf.len()) }.copy_from_slice(&buf);
+    }
+    pub fn alloc_page(&mut self) -> usize {
+        self.page_sum += 1;
+        self.page_sum - 1
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub is_leaf : bool,
+    pub keys : Vec<Vec<u8>>,
+    pub values : Vec<usize>,  // tuple positions for leaf, children for internal node
+    pub next : usize,
+}
+
+impl Node {
+    pub fn decode(data : DataPtr, key_len : usize) -> Node {
+        let header = unsafe{ from_raw_parts::<u8>(data as *const u8, NODE_HEADER_SIZE) };
+        let is_leaf = read_u32_le(header, 0) == 1;
+        let key_num = read_u32_le(header, 4) as usize;
+        let len = NODE_HEADER_SIZE + 4 + key_num * (key_len + 4);
+        let buf = unsafe{ from_raw_parts::<u8>(data as *const u8, len) };
+        let mut node = Node{
+            is_leaf : is_leaf,
+            keys : Vec::with_capacity(key_num),
+            values : Vec::with_capacity(key_num + 1),
+            next : read_u32_le(buf, 8) as usize,
+        };
+        let mut pos = NODE_HEADER_SIZE;
+        if !is_leaf {
+            node.values.push(read_u32_le(buf, pos) as usize);
+            pos += 4;
+        }
+        for _ in 0..key_num {
+            node.keys.push(buf[pos..pos + key_len].to_vec());
+            node.values.push(read_u32_le(buf, pos + key_len) as usize);
+            pos += key_len + 4;
+        }
+        node
+    }
+    pub fn encode(&self, data : DataPtr, page_size : usize) {
+        let mut buf = Vec::new();
+        push_u32_le(&mut buf, if self.is_leaf { 1 } else { 0 });
+        push_u32_le(&mut buf, self.keys.len() as u32);
+        push_u32_le(&mut buf, self.next as u32);
+        let mut values = self.values.iter();
+        if !self.is_leaf {
+            push_u32_le(&mut buf, *values.next().unwrap() as u32);
+        }
+        for (key, value) in self.keys.iter().zip(values) {
+            buf.extend_from_slice(key);
+            push_u32_le(&mut buf, *value as u32);
+        }
+        assert!(buf.len() <= page_size);
+        unsafe{ from_raw_parts_mut::<u8>(data as *mut u8, buf.len()) }.copy_from_slice(&buf);
+    }
+}
+
+fn read_meta(manager : &mut TableFileManager, index : &IndexFileRef) -> IndexMeta {
+    let data = manager.load_index_page(index, 0);
+    IndexMeta::decode(data)
+}
+
+fn write_meta(manager : &mut TableFileManager, index : &IndexFileRef, meta : &IndexMeta) {
+    manager.change_index_page(index, 0, |data| meta.encode(data));
+}
+
+fn read_node(manager : &mut TableFileManager, index : &IndexFileRef, page_index : usize) -> Node {
+    let key_len = index.read().unwrap().key_len;
+    let data = manager.load_index_page(index, page_index);
+    Node::decode(data, key_len)
+}
+
+fn write_node(manager : &mut TableFileManager, index : &IndexFileRef, page_index : usize, node : &Node) {
+    let page_size = index.read().unwrap().page_size;
+    manager.change_index_page(index, page_index, |data| node.encode(data, page_size));
+}
+
+fn upper_bound(keys : &Vec<Vec<u8>>, key : &[u8]) -> usize {
+    keys.iter().position(|k| &k[..] > key).unwrap_or(keys.len())
+}
+
+fn lower_bound(keys : &Vec<Vec<u8>>, key : &[u8]) -> usize {
+    keys.iter().position(|k| &k[..] >= key).unwrap_or(keys.len())
+}
+
+fn find_leaf(manager : &mut TableFileManager, index : &IndexFileRef,
+        root : usize, key : &[u8], path : &mut Vec<(usize, usize)>) -> (usize, Node) {
+    // path records the internal nodes and the child chosen in each of them
+    let mut page_index = root;
+    let mut node = read_node(manager, index, page_index);
+    while !node.is_leaf {
+        let i = upper_bound(&node.keys, key);
+        path.push((page_index, i));
+        page_index = node.values[i];
+        node = read_node(manager, index, page_index);
+    }
+    (page_index, node)
+}
+
+pub fn btree_insert(manager : &mut TableFileManager, index : &IndexFileRef,
+        key : &[u8], position : usize) -> bool {
+    // return false if the key already exist
+    let mut meta = read_meta(manager, index);
+    if meta.root == NO_PAGE {
+        let root = meta.alloc_page();
+        write_node(manager, index, root, &Node{
+            is_leaf : true,
+            keys : vec![key.to_vec()],
+            values : vec![position],
+            next : NO_PAGE,
+        });
+        meta.root = root;
+        write_meta(manager, index, &meta);
+        return true;
+    }
+    let mut path = Vec::new();
+    let (mut left_index, mut node) = find_leaf(manager, index, meta.root, key, &mut path);
+    let i = lower_bound(&node.keys, key);
+    if i < node.keys.len() && node.keys[i] == key {
+        return false;
+    }
+    node.keys.insert(i, key.to_vec());
+    node.values.insert(i, position);
+    let max_key_num = index.read().unwrap().max_key_num();
+    if node.keys.len() <= max_key_num {
+        write_node(manager, index, left_index, &node);
+        return true;
+    }
+
+    // split leaf, the first key of the right node is copied to the parent
+    let mid = node.keys.len() / 2;
+    let mut right_index = meta.alloc_page();
+    let right = Node{
+        is_leaf : true,
+        keys : node.keys.split_off(mid),
+        values : node.values.split_off(mid),
+        next : node.next,
+    };
+    node.next = right_index;
+    let mut separator = right.keys[0].clone();
+    write_node(manager, index, left_index, &node);
+    write_node(manager, index, right_index, &right);
+
+    loop {
+        let (parent_index, i) = match path.pop() {
+            Some(p) => p,
+            None => {
+                let root = meta.alloc_page();
+                write_node(manager, index, root, &Node{
+                    is_leaf : false,
+                    keys : vec![separator],
+                    values : vec![left_index, right_index],
+                    next : NO_PAGE,
+                });
+                meta.root = root;
+                break;
+            }
+        };
+        let mut parent = read_node(manager, index, parent_index);
+        parent.keys.insert(i, separator);
+        parent.values.insert(i + 1, right_index);
+        if parent.keys.len() <= max_key_num {
+            write_node(manager, index, parent_index, &parent);
+            break;
+        }
+        // split internal node, the middle key is moved to the parent
+        let mid = parent.keys.len() / 2;
+        let mut right_keys = parent.keys.split_off(mid);
+        separator = right_keys.remove(0);
+        let right = Node{
+            is_leaf : false,
+            keys : right_keys,
+            values : parent.values.split_off(mid + 1),
+            next : NO_PAGE,
+        };
+        left_index = parent_index;
+        right_index = meta.alloc_page();
+        write_node(manager, index, left_index, &parent);
+        write_node(manager, index, right_index, &right);
+    }
+    write_meta(manager, index, &meta);
+    true
+}
+
+pub fn btree_delete(manager : &mut TableFileManager, index : &IndexFileRef, key : &[u8]) -> bool {
+    // return false if the key not exist
+    let meta = read_meta(manager, index);
+    if meta.root == NO_PAGE {
+        return false;
+    }
+    let (page_index, mut node) = find_leaf(manager, index, meta.root, key, &mut Vec::new());
+    match node.keys.iter().position(|k| &k[..] == key) {
+        Some(i) => {
+            node.keys.remove(i);
+            node.values.remove(i);
+            write_node(manager, index, page_index, &node);
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn btree_lookup(manager : &mut TableFileManager, index : &IndexFileRef, prefix : &[u8]) -> Vec<usize> {
+    // return the positions of all the keys starting with prefix
+    let mut result = Vec::new();
+    let meta = read_meta(manager, index);
+    if meta.root == NO_PAGE {
+        return result;
+    }
+    let l = prefix.len();
+    let mut node = read_node(manager, index, meta.root);
+    while !node.is_leaf {
+        // go to the leftmost child which may contain the prefix
+        let i = node.keys.iter().position(|k| &k[..l] >= prefix).unwrap_or(node.keys.len());
+        node = read_node(manager, index, node.values[i]);
+    }
+    loop {
+        for (k, v) in node.keys.iter().zip(node.values.iter()) {
+            if &k[..l] > prefix {
+                return result;
+            }
+            if &k[..l] == prefix {
+                result.push(*v);
+            }
+        }
+        if node.next == NO_PAGE {
+            return result;
+        }
+        node = read_node(manager, index, node.next);
+    }
+}
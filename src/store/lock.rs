@@ -0,0 +1,117 @@
+use std::sync::{Mutex, Condvar, Arc};
+use std::collections::HashMap;
+use std::vec::Vec;
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LockTarget {
+    Tables(Vec<String>),
+    All,  // vacuum without table name
+}
+
+impl LockTarget {
+    fn contains(&self, table : &String) -> bool {
+        match self {
+            &LockTarget::Tables(ref tables) => tables.contains(table),
+            &LockTarget::All => true,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LockState {
+    readers : HashMap<String, usize>,  // key is table name
+    writer : Option<LockTarget>,
+    waiting_writers : Vec<LockTarget>,
+}
+
+impl LockState {
+    fn can_read(&self, tables : &Vec<String>) -> bool {
+        // the waiting writers go first, or a stream of scans would starve them
+        let blocked = |target : &LockTarget| tables.iter().any(|t| target.contains(t));
+        !self.writer.as_ref().map_or(false, &blocked)
+            && !self.waiting_writers.iter().any(&blocked)
+    }
+    fn can_write(&self, target : &LockTarget) -> bool {
+        self.writer.is_none() && !self.readers.keys().any(|t| target.contains(t))
+    }
+}
+
+pub type TableLockManagerRef = Arc<TableLockManager>;
+
+// statement level locks, the statements reading the same tables run in parallel,
+// while the writing statements are serialized globally, even those of different tables,
+// since the wal only has one statement in progress, and they also exclude the readers of their tables
+#[derive(Debug, Default)]
+pub struct TableLockManager {
+    state : Mutex<LockState>,
+    condvar : Condvar,
+}
+
+impl TableLockManager {
+    pub fn new() -> TableLockManager {
+        Self::default()
+    }
+    pub fn lock_read<'a>(&'a self, tables : Vec<String>) -> TableLockGuard<'a> {
+        let mut state = self.state.lock().unwrap();
+        while !state.can_read(&tables) {
+            state = self.condvar.wait(state).unwrap();
+        }
+        for t in tables.iter() {
+            *state.readers.entry(t.clone()).or_insert(0) += 1;
+        }
+        TableLockGuard{ manager : self, write : false, read_tables : tables }
+    }
+    pub fn lock_write<'a>(&'a self, target : LockTarget) -> TableLockGuard<'a> {
+        let mut state = self.state.lock().unwrap();
+        if !state.can_write(&target) {
+            state.waiting_writers.push(target.clone());
+            while !state.can_write(&target) {
+                state = self.condvar.wait(state).unwrap();
+            }
+            let i = state.waiting_writers.iter().position(|t| *t == target).unwrap();
+            state.waiting_writers.remove(i);
+        }
+        state.writer = Some(target);
+        TableLockGuard{ manager : self, write : true, read_tables : Vec::new() }
+    }
+    pub fn get_reader_num(&self, table : &String) -> usize {
+        self.state.lock().unwrap().readers.get(table).cloned().unwrap_or(0)
+    }
+    pub fn is_writing(&self) -> bool {
+        self.state.lock().unwrap().writer.is_some()
+    }
+    pub fn get_waiting_writer_num(&self) -> usize {
+        self.state.lock().unwrap().waiting_writers.len()
+    }
+    fn unlock(&self, write : bool, read_tables : &Vec<String>) {
+        let mut state = self.state.lock().unwrap();
+        if write {
+            assert!(state.writer.take().is_some());
+        }
+        for t in read_tables.iter() {
+            let n = {
+                let n = state.readers.get_mut(t).unwrap();
+                *n -= 1;
+                *n
+            };
+            if n == 0 {
+                state.readers.remove(t);
+            }
+        }
+        self.condvar.notify_all();
+    }
+}
+
+#[derive(Debug)]
+pub struct TableLockGuard<'a> {
+    manager : &'a TableLockManager,
+    write : bool,
+    read_tables : Vec<String>,
+}
+
+impl<'a> Drop for TableLockGuard<'a> {
+    fn drop(&mut self) {
+        self.manager.unlock(self.write, &self.read_tables);
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::{HashMap, VecDeque};
+use std::option::Option::{Some, None};
+use super::lru::CacheValue;
+use super::policy::{CachePolicy, ALL_PINNED};
+
+
+#[derive(Debug)]
+struct Entry<ValueType> {
+    value : ValueType,
+    history : VecDeque<u64>,  // the last k access time, most recent first
+}
+
+// replace the page whose k-th most recent access is the oldest,
+// pages accessed less than k times are replaced first in lru order,
+// so pages only touched once by a scan don't flush the hot pages
+#[derive(Debug)]
+pub struct LruKCache<ValueType : CacheValue> {
+    capacity : usize,
+    k : usize,
+    time : u64,
+    entries : HashMap<ValueType::KeyType, Entry<ValueType>>,
+    victim : Option<ValueType::KeyType>,
+}
+
+impl<ValueType : CacheValue> LruKCache<ValueType> {
+    pub fn new(capacity : usize, k : usize) -> LruKCache<ValueType> {
+        assert!(capacity > 0);
+        assert!(k > 0);
+        LruKCache{
+            capacity : capacity,
+            k : k,
+            time : 0,
+            entries : HashMap::with_capacity(capacity),
+            victim : None,
+        }
+    }
+    fn tick(&mut self) -> u64 {
+        self.time += 1;
+        self.time
+    }
+}
+
+impl<ValueType : CacheValue> CachePolicy<ValueType> for LruKCache<ValueType> {
+    fn capacity(&self) -> usize { self.capacity }
+    fn get_load(&self) -> usize { self.entries.len() }
+    fn get(&mut self, key : &ValueType::KeyType) -> Option<ValueType> {
+        let now = self.tick();
+        let k = self.k;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.history.push_front(now);
+                entry.history.truncate(k);
+                Some(entry.value.clone())
+            }
+            None => None,
+        }
+    }
+    fn put(&mut self, key : &ValueType::KeyType, value : ValueType) {
+        assert!(self.entries.get(key).is_none());
+        assert!(self.entries.len() < self.capacity);
+        let now = self.tick();
+        let mut history = VecDeque::with_capacity(self.k);
+        history.push_front(now);
+        self.entries.insert(key.clone(), Entry{
+            value : value,
+            history : history,
+        });
+    }
+    fn remove(&mut self, key : &ValueType::KeyType) -> Option<ValueType> {
+        self.entries.remove(key).map(|entry| entry.value)
+    }
+    fn prepare_page(&mut self) -> Result<Option<ValueType>, String> {
+        if self.entries.len() < self.capacity {
+            return Ok(None);
+        }
+        let k = self.k;
+        let victim = self.entries.iter()
+            .filter(|&(_, entry)| !entry.value.is_pinned())
+            .min_by_key(|&(_, entry)| match entry.history.get(k - 1) {
+                Some(t) => (true, *t),
+                None => (false, *entry.history.front().unwrap()),
+            })
+            .map(|(key, entry)| (key.clone(), entry.value.clone()));
+        let (key, value) = try!(victim.ok_or(ALL_PINNED.to_string()));
+        self.victim = Some(key);
+        Ok(Some(value))
+    }
+    fn remove_victim(&mut self) {
+        // call this function if returned value of prepare_page is not None
+        let key = self.victim.take().unwrap();
+        let entry = self.entries.remove(&key).unwrap();
+        assert!(!entry.value.is_pinned());
+    }
+}
@@ -1,11 +1,35 @@
-#[allow(dead_code)]
-pub mod table;
-#[allow(dead_code)]
-#[allow(unused_unsafe)]
-pub mod lru;
-#[allow(dead_code)]
-pub mod buffer;
-#[allow(dead_code)]
-pub mod tuple;
-#[allow(dead_code)]
-pub mod file;
+#[allow(dead_code)]
+pub mod table;
+#[allow(dead_code)]
+#[allow(unused_unsafe)]
+pub mod lru;
+#[allow(dead_code)]
+pub mod policy;
+#[allow(dead_code)]
+pub mod clock;
+#[allow(dead_code)]
+pub mod lru_k;
+#[allow(dead_code)]
+pub mod two_queue;
+#[allow(dead_code)]
+pub mod buffer;
+#[allow(dead_code)]
+pub mod tuple;
+#[allow(dead_code)]
+pub mod file;
+#[allow(dead_code)]
+pub mod overflow;
+#[allow(dead_code)]
+pub mod wal;
+#[allow(dead_code)]
+pub mod index;
+#[allow(dead_code)]
+pub mod fsm;
+#[allow(dead_code)]
+pub mod lock;
+#[allow(dead_code)]
+pub mod format;
+#[allow(dead_code)]
+pub mod fsck;
+#[allow(dead_code)]
+pub mod durability;
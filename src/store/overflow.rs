@@ -0,0 +1,108 @@
+use std::vec::Vec;
+use std::slice::{from_raw_parts, from_raw_parts_mut};
+use ::utils::pointer::pointer_offset;
+use ::utils::endian::{read_u16_le, read_u32_le, write_u16_le, write_u32_le};
+use super::buffer::DataPtr;
+use super::tuple::TupleValue;
+use super::format::{OVERFLOW_PAGE_MARK, OVERFLOW_PAGE_HEADER_SIZE, NO_NEXT_PAGE};
+
+
+// A text or blob value in the tuple starts with its length (4 bytes, little endian),
+// followed by the bytes if it is not longer than INLINE_VALUE_LEN,
+// otherwise by the index of the first page of its overflow chain (4 bytes, little endian).
+
+pub const INLINE_VALUE_LEN : usize = 252;
+
+pub fn large_value_space(len : usize) -> usize {
+    // aligned to 4 bytes
+    if len <= INLINE_VALUE_LEN {
+        (4 + len + 3) / 4 * 4
+    } else {
+        8
+    }
+}
+
+pub unsafe fn large_value_len(ptr : DataPtr) -> usize {
+    read_u32_le(from_raw_parts::<u8>(ptr as *const u8, 4), 0) as usize
+}
+
+pub unsafe fn read_large_value(ptr : DataPtr) -> TupleValue {
+    // the value in the overflow pages is not read here
+    let len = large_value_len(ptr);
+    if len <= INLINE_VALUE_LEN {
+        TupleValue::Bytes(from_raw_parts::<u8>(pointer_offset(ptr, 4) as *const u8, len).to_vec())
+    } else {
+        let first_page = read_u32_le(from_raw_parts::<u8>(ptr as *const u8, 8), 4) as usize;
+        TupleValue::Overflow{ len : len, first_page : first_page }
+    }
+}
+
+pub unsafe fn write_inline_value(ptr : DataPtr, bytes : &[u8]) {
+    assert!(bytes.len() <= INLINE_VALUE_LEN);
+    let buf = from_raw_parts_mut::<u8>(ptr as *mut u8, 4 + bytes.len());
+    write_u32_le(buf, 0, bytes.len() as u32);
+    buf[4..].copy_from_slice(bytes);
+}
+
+pub unsafe fn write_overflow_ref(ptr : DataPtr, len : usize, first_page : usize) {
+    assert!(len > INLINE_VALUE_LEN);
+    let buf = from_raw_parts_mut::<u8>(ptr as *mut u8, 8);
+    write_u32_le(buf, 0, len as u32);
+    write_u32_le(buf, 4, first_page as u32);
+}
+
+pub fn overflow_page_capacity(page_size : usize) -> usize {
+    page_size - OVERFLOW_PAGE_HEADER_SIZE
+}
+
+pub fn is_overflow_page(page : &[u8]) -> bool {
+    read_u16_le(page, 0) == OVERFLOW_PAGE_MARK
+}
+
+pub fn write_overflow_page(page : &mut [u8], data : &[u8], next_page : Option<usize>) {
+    // the checksum is left to the saving of the page
+    assert!(data.len() <= overflow_page_capacity(page.len()));
+    write_u16_le(page, 0, OVERFLOW_PAGE_MARK);
+    write_u16_le(page, 2, data.len() as u16);
+    write_u32_le(page, 8, next_page.map_or(NO_NEXT_PAGE, |i| i as u32));
+    page[OVERFLOW_PAGE_HEADER_SIZE..OVERFLOW_PAGE_HEADER_SIZE + data.len()].copy_from_slice(data);
+}
+
+pub fn read_overflow_page(page : &[u8]) -> Result<(&[u8], Option<usize>), String> {
+    // return the data and the next page
+    if !is_overflow_page(page) {
+        return Err("not an overflow page".to_string());
+    }
+    let len = read_u16_le(page, 2) as usize;
+    if len == 0 || len > overflow_page_capacity(page.len()) {
+        return Err(format!("invalid data length {} of overflow page", len));
+    }
+    let next_page = match read_u32_le(page, 8) {
+        NO_NEXT_PAGE => None,
+        n => Some(n as usize),
+    };
+    Ok((&page[OVERFLOW_PAGE_HEADER_SIZE..OVERFLOW_PAGE_HEADER_SIZE + len], next_page))
+}
+
+pub fn read_overflow_chain<F, G>(len : usize, first_page : usize, mut read_page : F, mut handle_chunk : G)
+        -> Result<(), String>
+        where F : FnMut(usize) -> Result<(Vec<u8>, Option<usize>), String>, G : FnMut(&[u8]) {
+    // pass the data of the chain to handle_chunk page by page,
+    // the length of the value stops a broken chain from looping
+    let mut remaining = len;
+    let mut next_page = Some(first_page);
+    while remaining > 0 {
+        let page_index = match next_page {
+            Some(i) => i,
+            None => return Err(format!("overflow chain from page {} ends {} bytes early", first_page, remaining)),
+        };
+        let (data, next) = try!(read_page(page_index));
+        if data.len() > remaining {
+            return Err(format!("overflow chain from page {} is longer than {} bytes", first_page, len));
+        }
+        remaining -= data.len();
+        handle_chunk(&data);
+        next_page = next;
+    }
+    Ok(())
+}
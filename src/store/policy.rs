@@ -0,0 +1,49 @@
+use std::boxed::Box;
+use std::fmt::Debug;
+use std::option::Option;
+use super::lru::{CacheValue, LruCache};
+use super::clock::ClockCache;
+use super::lru_k::LruKCache;
+use super::two_queue::TwoQueueCache;
+
+
+// must be object-safe, and can be moved to another worker
+pub trait CachePolicy<ValueType : CacheValue> : Debug + Send {
+    fn capacity(&self) -> usize;
+    fn get_load(&self) -> usize;
+    fn get(&mut self, key : &ValueType::KeyType) -> Option<ValueType>;
+    // before call this function, you should call prepare_page and remove_victim first
+    fn put(&mut self, key : &ValueType::KeyType, value : ValueType);
+    fn remove(&mut self, key : &ValueType::KeyType) -> Option<ValueType>;
+    // return the value to be replaced if the cache is full, it should be flushed before remove_victim,
+    // or ALL_PINNED if every value is pinned
+    fn prepare_page(&mut self) -> Result<Option<ValueType>, String>;
+    fn remove_victim(&mut self);
+}
+
+pub type CachePolicyRef<ValueType> = Box<CachePolicy<ValueType>>;
+
+pub const DEFAULT_POLICY : &'static str = "lru";
+pub const POLICY_LIST : [&'static str; 4] = ["lru", "clock", "lru-k", "2q"];
+pub const ALL_PINNED : &'static str = "all pages in the buffer pool are pinned";
+pub const LRU_K : usize = 2;
+
+pub fn check_policy(policy : &str) -> Result<(), String> {
+    // should be called when the config is loaded, since gen_cache_policy panics
+    if POLICY_LIST.contains(&policy) {
+        Ok(())
+    } else {
+        Err(format!("invalid buffer_policy {:?}, should be one of {}", policy, POLICY_LIST.join(", ")))
+    }
+}
+
+pub fn gen_cache_policy<ValueType : CacheValue + 'static>(policy : &str, capacity : usize)
+        -> CachePolicyRef<ValueType> {
+    match policy {
+        "lru" => Box::new(LruCache::new(capacity)),
+        "clock" => Box::new(ClockCache::new(capacity)),
+        "lru-k" => Box::new(LruKCache::new(capacity, LRU_K)),
+        "2q" => Box::new(TwoQueueCache::new(capacity)),
+        _ => panic!("invalid buffer policy: {}", policy),
+    }
+}
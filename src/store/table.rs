@@ -1,289 +1,700 @@
-use std::vec::Vec;
-use std::collections::{BTreeMap, HashMap};
-use std::option::Option;
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::fs::OpenOptions;
-use std::io::{Read, Write};
-use rustc_serialize::{Encodable, Decodable, Encoder, Decoder};
-use rustc_serialize::json::{encode, decode};
-use ::parser::common::ValueList;
-use ::utils::config::Config;
-use ::utils::file::{path_join, ensure_dir_exist};
-use ::store::tuple::TupleValue;
-use super::tuple::TupleDesc;
-use super::file::TableFileManager;
-
-
-macro_rules! unwrap {
-    ($result:expr) => ({
-        match $result {
-            Err(err) => panic!("unexpected error: {:?}", err),
-            Ok(data) => data,
-        }
-    })
-}
-
-#[derive(Debug, Copy, Clone)]
-pub enum AttrType {
-    Int,
-    Float,
-    Char{ len : usize },
-}
-
-#[derive(Debug, Clone, RustcDecodable, RustcEncodable)]
-pub struct Attr {
-    pub name : String,
-    pub attr_type : AttrType,
-    pub primary : bool,
-    pub nullable : bool,
-}
-
-
-pub type TableRef = Rc<RefCell<Table>>;
-pub type IndexMap = HashMap<(String, String), usize>;
-
-#[derive(Debug, Clone, RustcDecodable, RustcEncodable)]
-pub struct Table {
-    pub name : String,
-    pub attr_list : Vec<Attr>,
-}
-
-impl Table {
-    pub fn gen_tuple_desc(&self) -> TupleDesc {
-        TupleDesc::new(&self.attr_list)
-    }
-    pub fn gen_index_map(&self) -> IndexMap {
-        let mut index_map = IndexMap::new();
-        for (i, attr) in self.attr_list.iter().enumerate() {
-            index_map.insert((self.name.clone(), attr.name.clone()), i);
-        }
-        index_map
-    }
-    pub fn get_primary_key_attr(&self) -> Attr {
-        self.attr_list.iter().filter(|a| a.primary).next().unwrap().clone()
-    }
-    pub fn get_primary_key_index(&self) -> usize {
-        let mut index = 0;
-        for attr in self.attr_list.iter() {
-            if attr.primary {
-                return index;
-            }
-            index += 1;
-        }
-        index
-    }
-    pub fn get_attr_name_list(&self) -> Vec<String> {
-        self.attr_list.iter().map(|a| a.name.clone()).collect()
-    }
-    pub fn desc(&self) -> String {
-        let mut result = format!("table: {}\n", self.name);
-        for attr in self.attr_list.iter() {
-            result.push_str(&format!("{} {:?} {} {}\n", attr.name, attr.attr_type,
-                if attr.nullable {"null"}else{"not null"}, if attr.primary {"primary"}else{""}))
-        }
-        result
-    }
-}
-
-
-pub struct TableSet {
-    pub tables : HashMap<String, Table>,
-}
-
-impl TableSet {
-    pub fn new() -> TableSet {
-        TableSet{
-            tables : HashMap::new(),
-        }
-    }
-    pub fn exist(&self, name : &str) -> bool {
-        match self.tables.get(name) {
-            Some(..) => true,
-            None => false,
-        }
-    }
-    pub fn get_attr(&self, table : &Option<String>, attr : &str) -> Option<Attr> {
-        let mut table_list = Vec::new();
-        for (name, t) in self.tables.iter() {
-            if let Some(attr) = t.attr_list.iter().filter(|a| a.name == attr).next() {
-                if let &Some(ref table_name) = table {
-                    if *table_name == name.to_string() {
-                        table_list.push(attr.clone());
-                    }
-                } else {
-                    table_list.push(attr.clone());
-                }
-            }
-        }
-        match table_list.len() {
-            1 => table_list.pop(),
-            _ => None,  // not found or multiple attribute found
-        }
-    }
-    pub fn complete_table_name(&self, table : &mut Option<String>, attr : &mut String) {
-        // should called after get_attr to confirm only one result exist
-        if table.is_some() { return; }
-        for (name, t) in self.tables.iter() {
-            if let Some(..) = t.attr_list.iter().filter(|a| a.name == *attr).next() {
-                *table = Some(name.clone());
-                return;
-            }
-        }
-        panic!("attribute not exist");
-    }
-    pub fn gen_attr_list(&self, table : &String) -> Vec<Attr> {
-        // table should exist
-        self.tables.get(table).unwrap().attr_list.clone()
-    }
-    pub fn add_table(&mut self, table : Table) {
-        self.tables.insert(table.name.clone(), table);
-    }
-}
-
-
-pub type TableManagerRef = Rc<RefCell<TableManager>>;
-
-#[derive(Debug)]
-pub struct TableManager {
-    tables : BTreeMap<String, TableRef>,
-    pub file_manager : TableFileManager,
-    table_meta_dir : String,
-}
-
-impl TableManager {
-    pub fn make_ref(config : &Config) -> TableManagerRef {
-        Rc::new(RefCell::new(TableManager::new(config)))
-    }
-    pub fn new(config : &Config) -> TableManager {
-        let table_meta_dir = config.get_str("table_meta_dir");
-        ensure_dir_exist(&table_meta_dir);
-        TableManager{
-            tables : BTreeMap::new(),
-            file_manager : TableFileManager::new(config),
-            table_meta_dir : table_meta_dir,
-        }
-    }
-    pub fn save_to_file(&mut self) {
-        self.file_manager.save_all();
-        let full_path = path_join(&self.table_meta_dir, &"table_meta.json".to_string());
-        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(
-            &full_path).unwrap();
-        let json_str = self.to_json();
-        is_match!(file.write_all(json_str.as_bytes()), Ok(..));
-    }
-    pub fn from_json_file(config : &Config) -> TableManager {
-        let table_meta_dir = config.get_str("table_meta_dir");
-        ensure_dir_exist(&table_meta_dir);
-        let full_path = path_join(&table_meta_dir, &"table_meta.json".to_string());
-        let mut file = OpenOptions::new().read(true).create(true).open(
-            &full_path).unwrap();
-        let mut json_str = String::new();
-        assert!(file.read_to_string(&mut json_str).is_ok());
-        if json_str.len() == 0 { return TableManager::new(config) }
-        Self::from_json(config, &json_str, true)
-    }
-    pub fn from_json(config : &Config, json : &String, init_file : bool) -> TableManager {
-        // setting init_file to false only for tests
-        let mut tables = BTreeMap::new();
-        let mut table_list = Vec::new();
-        let tree : BTreeMap<String, Table> = unwrap!(decode(json));
-        for (name, table) in tree.iter() {
-            let t = Rc::new(RefCell::new(table.clone()));
-            tables.insert(name.clone(), t.clone());
-            table_list.push(t);
-        }
-        let mut manager = Self::new(config);
-        manager.tables = tables;
-        if init_file {
-            manager.file_manager.init_from_file(table_list);
-        }
-        manager
-    }
-    pub fn to_json(&self) -> String {
-        let mut tree : BTreeMap<String, Table> = BTreeMap::new();
-        for (name, table) in self.tables.iter() {
-            tree.insert(name.clone(), table.borrow().clone());
-        }
-        unwrap!(encode(&tree))
-    }
-    pub fn add_table(&mut self, table : Table) {
-        // add new table and create empty file
-        let name = table.name.clone();
-        assert!(!self.tables.get(&name).is_some());
-        let table_ref = Rc::new(RefCell::new(table));
-        self.file_manager.create_file(name.clone(), table_ref.clone());
-        self.tables.insert(name, table_ref);
-    }
-    pub fn remove_table(&mut self, table : &String) {
-        self.tables.remove(table);
-    }
-    pub fn get_table(&self, name : &str) -> Option<TableRef> {
-        match self.tables.get(name) {
-            Some(ref mut table) => Some(table.clone()),
-            None => None,
-        }
-    }
-    pub fn gen_table_set(&self, used_table : &Vec<String>) -> TableSet {
-        let mut tables = HashMap::new();
-        for name in used_table.iter() {
-            if let Some(t) = self.tables.get(name) {
-                tables.insert(name.clone(), t.borrow().clone());
-            }
-        }
-        TableSet{ tables : tables }
-    }
-    pub fn get_tuple_value(&mut self, table : &String,
-            position : usize,
-            attr_position : usize) -> TupleValue{
-        self.file_manager.get_tuple_value(table, position, attr_position)
-    }
-    pub fn insert(&mut self, table : &String, value_list : &ValueList) {
-        self.file_manager.insert(table, value_list);
-    }
-    pub fn show_tables(&self) -> String {
-        let mut result = String::new();
-        for (_, t) in self.tables.iter() {
-            result.push_str(&t.borrow().desc());
-            result.push('\n');
-        }
-        result
-    }
-}
-
-impl Encodable for AttrType {
-    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
-        let mut t = BTreeMap::new();
-        match self {
-            &AttrType::Int => t.insert("type".to_string(), "Int".to_string()),
-            &AttrType::Float => t.insert("type".to_string(), "Float".to_string()),
-            &AttrType::Char{len} => {
-                t.insert("type".to_string(), "Char".to_string());
-                t.insert("len".to_string(), len.to_string())
-            }
-        };
-        t.encode(s)
-    }
-}
-
-impl Decodable for AttrType {
-    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
-        let t : BTreeMap<String, String> = try!(BTreeMap::decode(d));
-        let res = match t.get("type") {
-            None => panic!("can't find key 'type' in AttrType json data"),
-            Some(ref s) => match &s[..] {
-                "Int" => AttrType::Int,
-                "Float" => AttrType::Float,
-                "Char" => {
-                    let len = match t.get("len") {
-                        None => panic!("can't find key 'len' for Char in AttrType json data"),
-                        Some(len) => len.parse::<usize>().unwrap(),
-                    };
-                    AttrType::Char{ len : len }
-                }
-                _ => panic!("unexpected type {}", s),
-            }
-        };
-        Ok(res)
-    }
-}
+use std::vec::Vec;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::option::Option;
+use std::sync::{Arc, RwLock};
+use std::fs::{OpenOptions, copy, rename, read_dir};
+use std::io::{Read, Write};
+use rustc_serialize::{Encodable, Decodable, Encoder, Decoder};
+use rustc_serialize::json::{self, encode, Json};
+use ::parser::common::ValueList;
+use ::utils::config::Config;
+use ::utils::file::{path_join, ensure_dir_exist, file_exist, sync_dir};
+use ::store::tuple::TupleValue;
+use super::tuple::TupleDesc;
+use super::file::TableFileManager;
+use super::format::DEFAULT_PAGE_SIZE;
+use super::index::PRIMARY_INDEX;
+use super::lock::{TableLockManager, TableLockManagerRef};
+
+
+macro_rules! unwrap {
+    ($result:expr) => ({
+        match $result {
+            Err(err) => panic!("unexpected error: {:?}", err),
+            Ok(data) => data,
+        }
+    })
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum AttrType {
+    Int,
+    Float,
+    BigInt,
+    Double,
+    Decimal{ precision : usize, scale : usize },  // saved as an i64 of the value multiplied by 10^scale
+    Boolean,  // saved as an i32 of 0 or 1
+    Date,  // saved as an i32 of the days since 1970-01-01
+    Time,  // saved as an i64 of the microseconds since midnight
+    Timestamp,  // saved as an i64 of the microseconds since 1970-01-01 00:00:00
+    Char{ len : usize },
+    Varchar{ len : usize },
+    Text,
+    Blob,
+}
+
+impl AttrType {
+    pub fn is_large(&self) -> bool {
+        // text or blob, whose long values are saved in overflow pages
+        match self {
+            &AttrType::Text | &AttrType::Blob => true,
+            _ => false,
+        }
+    }
+    pub fn is_num(&self) -> bool {
+        match self {
+            &AttrType::Int | &AttrType::Float | &AttrType::BigInt | &AttrType::Double
+                | &AttrType::Decimal{..} => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Attr {
+    pub name : String,
+    pub attr_type : AttrType,
+    pub primary : bool,
+    pub nullable : bool,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Index {
+    pub name : String,
+    pub attr : String,
+    pub unique : bool,
+}
+
+
+pub const TABLE_META_FILE : &'static str = "table_meta.json";
+pub const TABLE_META_BACKUP_FILE : &'static str = "table_meta.json.bak";
+
+pub type TableRef = Arc<RwLock<Table>>;
+pub type IndexMap = HashMap<(String, String), usize>;
+
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub name : String,
+    pub attr_list : Vec<Attr>,
+    pub index_list : Vec<Index>,  // not including the primary key index
+    pub file : String,  // base name of the files, kept when the table is renamed
+}
+
+impl Table {
+    pub fn gen_tuple_desc(&self) -> TupleDesc {
+        TupleDesc::new(&self.attr_list)
+    }
+    pub fn gen_index_map(&self) -> IndexMap {
+        let mut index_map = IndexMap::new();
+        for (i, attr) in self.attr_list.iter().enumerate() {
+            index_map.insert((self.name.clone(), attr.name.clone()), i);
+        }
+        index_map
+    }
+    pub fn get_primary_key_attr_list(&self) -> Vec<Attr> {
+        self.attr_list.iter().filter(|a| a.primary).cloned().collect()
+    }
+    pub fn get_index_attr_list(&self, index : &Index) -> Vec<usize> {
+        // the primary key index covers all the primary attributes in the attribute order
+        if index.name == PRIMARY_INDEX {
+            (0..self.attr_list.len()).filter(|i| self.attr_list[*i].primary).collect()
+        } else {
+            vec![self.attr_list.iter().position(|a| a.name == index.attr).unwrap()]
+        }
+    }
+    pub fn gen_index_list(&self) -> Vec<Index> {
+        // all the indexes including the primary key index
+        let mut index_list = vec![Index{
+            name : PRIMARY_INDEX.to_string(),
+            attr : self.get_primary_key_attr_list().iter().map(|a| a.name.clone()).collect::<Vec<String>>().join(", "),
+            unique : true,
+        }];
+        index_list.extend_from_slice(&self.index_list);
+        index_list
+    }
+    pub fn get_index(&self, name : &str) -> Option<&Index> {
+        self.index_list.iter().filter(|i| i.name == name).next()
+    }
+    pub fn get_attr_name_list(&self) -> Vec<String> {
+        self.attr_list.iter().map(|a| a.name.clone()).collect()
+    }
+    pub fn desc(&self) -> String {
+        let mut result = format!("table: {}\n", self.name);
+        for attr in self.attr_list.iter() {
+            result.push_str(&format!("{} {:?} {} {}\n", attr.name, attr.attr_type,
+                if attr.nullable {"null"}else{"not null"}, if attr.primary {"primary"}else{""}))
+        }
+        for index in self.index_list.iter() {
+            result.push_str(&format!("{}index {} ({})\n",
+                if index.unique {"unique "}else{""}, index.name, index.attr))
+        }
+        result
+    }
+}
+
+
+pub struct TableSet {
+    pub tables : HashMap<String, Table>,
+    pub page_size : usize,  // the longest tuple of a table should fit in a page
+}
+
+impl TableSet {
+    pub fn new() -> TableSet {
+        TableSet{
+            tables : HashMap::new(),
+            page_size : DEFAULT_PAGE_SIZE,
+        }
+    }
+    pub fn exist(&self, name : &str) -> bool {
+        match self.tables.get(name) {
+            Some(..) => true,
+            None => false,
+        }
+    }
+    pub fn get_attr(&self, table : &Option<String>, attr : &str) -> Option<Attr> {
+        let mut table_list = Vec::new();
+        for (name, t) in self.tables.iter() {
+            if let Some(attr) = t.attr_list.iter().filter(|a| a.name == attr).next() {
+                if let &Some(ref table_name) = table {
+                    if *table_name == name.to_string() {
+                        table_list.push(attr.clone());
+                    }
+                } else {
+                    table_list.push(attr.clone());
+                }
+            }
+        }
+        match table_list.len() {
+            1 => table_list.pop(),
+            _ => None,  // not found or multiple attribute found
+        }
+    }
+    pub fn complete_table_name(&self, table : &mut Option<String>, attr : &mut String) {
+        // should called after get_attr to confirm only one result exist
+        if table.is_some() { return; }
+        for (name, t) in self.tables.iter() {
+            if let Some(..) = t.attr_list.iter().filter(|a| a.name == *attr).next() {
+                *table = Some(name.clone());
+                return;
+            }
+        }
+        panic!("attribute not exist");
+    }
+    pub fn gen_attr_list(&self, table : &String) -> Vec<Attr> {
+        // table should exist
+        self.tables.get(table).unwrap().attr_list.clone()
+    }
+    pub fn add_table(&mut self, table : Table) {
+        self.tables.insert(table.name.clone(), table);
+    }
+}
+
+
+pub type TableManagerRef = Arc<RwLock<TableManager>>;
+
+#[derive(Debug)]
+pub struct TableManager {
+    tables : BTreeMap<String, TableRef>,
+    pub file_manager : TableFileManager,
+    pub lock_manager : TableLockManagerRef,
+    table_meta_dir : String,
+    pub catalog_version : u64,
+    catalog_changed : bool,  // tables or indexes changed since the catalog saved
+    pub catalog_warning : Option<String>,  // why the backup of the catalog was loaded
+}
+
+impl TableManager {
+    pub fn make_ref(config : &Config) -> TableManagerRef {
+        Arc::new(RwLock::new(TableManager::new(config)))
+    }
+    pub fn new(config : &Config) -> TableManager {
+        // from_json_file reports the errors instead of panicking
+        unwrap!(Self::open(config))
+    }
+    fn open(config : &Config) -> Result<TableManager, String> {
+        let table_meta_dir = config.get_str("table_meta_dir");
+        ensure_dir_exist(&table_meta_dir);
+        let file_manager = try!(TableFileManager::new(config));
+        Ok(TableManager{
+            tables : BTreeMap::new(),
+            file_manager : file_manager,
+            lock_manager : Arc::new(TableLockManager::new()),
+            table_meta_dir : table_meta_dir,
+            catalog_version : 0,
+            catalog_changed : false,
+            catalog_warning : None,
+        })
+    }
+    pub fn save_to_file(&mut self) {
+        // the change of the catalog is saved at once whatever the durability is,
+        // together with the table files it refers to
+        if !self.catalog_changed && !self.file_manager.need_save() {
+            return;
+        }
+        self.flush();
+    }
+    pub fn flush(&mut self) {
+        // save all the changes regardless of the durability
+        self.file_manager.save_all();
+        if self.catalog_changed {
+            self.save_meta();
+            self.file_manager.remove_obsolete_files();
+        }
+        self.file_manager.checkpoint();
+    }
+    fn save_meta(&mut self) {
+        // the new catalog is written to a temporary file and renamed over the old one,
+        // which is kept as the backup, so a crash never leaves a partial catalog
+        self.catalog_version += 1;
+        self.catalog_changed = false;
+        let need_sync = self.file_manager.get_durability().need_sync();
+        self.file_manager.sync_file_dir();
+        let full_path = path_join(&self.table_meta_dir, &TABLE_META_FILE.to_string());
+        let tmp_path = format!("{}.tmp", full_path);
+        let backup_path = path_join(&self.table_meta_dir, &TABLE_META_BACKUP_FILE.to_string());
+        {
+            let mut file = check_ok!(OpenOptions::new().write(true).create(true).truncate(true).open(&tmp_path));
+            check_ok!(file.write_all(self.to_catalog_json().as_bytes()));
+            if need_sync {
+                check_ok!(file.sync_all());
+            }
+        }
+        if file_exist(&full_path) {
+            check_ok!(copy(&full_path, &backup_path));
+        }
+        check_ok!(rename(&tmp_path, &full_path));
+        if need_sync {
+            sync_dir(&self.table_meta_dir);
+        }
+    }
+    pub fn from_json_file(config : &Config) -> Result<TableManager, String> {
+        let table_meta_dir = config.get_str("table_meta_dir");
+        ensure_dir_exist(&table_meta_dir);
+        let (catalog, warning) = try!(load_catalog(&table_meta_dir, &config.get_str("table_file_dir")));
+        let mut manager = try!(Self::from_catalog(config, catalog, true));
+        manager.catalog_warning = warning;
+        try!(manager.file_manager.recover());
+        Ok(manager)
+    }
+    pub fn from_json(config : &Config, json : &String, init_file : bool) -> TableManager {
+        // setting init_file to false only for tests
+        unwrap!(Self::from_catalog(config, unwrap!(parse_catalog(json)), init_file))
+    }
+    fn from_catalog(config : &Config, catalog : Catalog, init_file : bool) -> Result<TableManager, String> {
+        let mut tables = BTreeMap::new();
+        let mut table_list = Vec::new();
+        for (name, table) in catalog.tables.into_iter() {
+            let t = Arc::new(RwLock::new(table));
+            tables.insert(name, t.clone());
+            table_list.push(t);
+        }
+        let mut manager = try!(Self::open(config));
+        manager.tables = tables;
+        manager.catalog_version = catalog.version;
+        if init_file {
+            try!(manager.file_manager.init_from_file(table_list));
+        }
+        Ok(manager)
+    }
+    pub fn to_json(&self) -> String {
+        unwrap!(encode(&self.gen_table_tree()))
+    }
+    pub fn to_catalog_json(&self) -> String {
+        // the content of table_meta.json
+        unwrap!(encode(&Catalog{
+            version : self.catalog_version,
+            tables : self.gen_table_tree(),
+        }))
+    }
+    fn gen_table_tree(&self) -> BTreeMap<String, Table> {
+        let mut tree : BTreeMap<String, Table> = BTreeMap::new();
+        for (name, table) in self.tables.iter() {
+            tree.insert(name.clone(), table.read().unwrap().clone());
+        }
+        tree
+    }
+    pub fn add_table(&mut self, table : Table) {
+        // add new table and create empty file
+        let name = table.name.clone();
+        assert!(!self.tables.get(&name).is_some());
+        let table_ref = Arc::new(RwLock::new(table));
+        self.file_manager.create_file(name.clone(), table_ref.clone());
+        self.tables.insert(name, table_ref);
+        self.catalog_changed = true;
+    }
+    pub fn remove_table(&mut self, table : &String) {
+        // the catalog is saved before removing the files,
+        // so a crash in between only leaves some unused files
+        self.tables.remove(table);
+        self.save_meta();
+        self.file_manager.remove_file(table);
+    }
+    pub fn rename_table(&mut self, table : &String, new_name : &String) {
+        let table_ref = self.tables.remove(table).unwrap();
+        self.file_manager.rename_table(table, new_name);
+        self.tables.insert(new_name.clone(), table_ref);
+        self.catalog_changed = true;
+    }
+    pub fn rename_attr(&mut self, table : &String, attr : &String, new_name : &String) {
+        // the tuples are not changed
+        let table_ref = self.tables.get(table).unwrap().clone();
+        let mut t = table_ref.write().unwrap();
+        for a in t.attr_list.iter_mut().filter(|a| a.name == *attr) {
+            a.name = new_name.clone();
+        }
+        for index in t.index_list.iter_mut().filter(|i| i.attr == *attr) {
+            index.attr = new_name.clone();
+        }
+        self.catalog_changed = true;
+    }
+    pub fn alter_table<F>(&mut self, table : &String, mut new_table : Table, convert : F) -> Result<(), String>
+            where F : Fn(Vec<TupleValue>) -> ValueList {
+        // the tuples converted by convert are written into new files,
+        // which are named after the catalog version saving them
+        let mut version = self.catalog_version + 1;
+        while self.file_manager.file_base_exist(&format!("{}-{}", table, version)) {
+            version += 1;  // altered again before the catalog is saved
+        }
+        new_table.file = format!("{}-{}", table, version);
+        let table_ref = Arc::new(RwLock::new(new_table));
+        try!(self.file_manager.rewrite_file(table, table_ref.clone(), convert));
+        self.tables.insert(table.clone(), table_ref);
+        self.catalog_changed = true;
+        Ok(())
+    }
+    pub fn add_index(&mut self, table : &String, index : Index) -> Result<bool, String> {
+        // build the index from the table, return false if a unique index found duplicate keys
+        let table = self.tables.get(table).unwrap().clone();
+        if !try!(self.file_manager.create_index(&table, &index)) {
+            return Ok(false);
+        }
+        table.write().unwrap().index_list.push(index);
+        self.catalog_changed = true;
+        Ok(true)
+    }
+    pub fn remove_index(&mut self, table : &String, index : &String) {
+        let table_ref = self.tables.get(table).unwrap().clone();
+        table_ref.write().unwrap().index_list.retain(|i| i.name != *index);
+        self.file_manager.drop_index(table, index);
+        self.catalog_changed = true;
+    }
+    pub fn vacuum(&mut self, table : &Option<String>) -> Result<(), String> {
+        // compact the given table or all the tables
+        let table_list : Vec<String> = match table {
+            &Some(ref name) => vec![name.clone()],
+            &None => self.tables.keys().cloned().collect(),
+        };
+        for name in table_list.iter() {
+            try!(self.file_manager.vacuum(name));
+        }
+        Ok(())
+    }
+    pub fn get_table(&self, name : &str) -> Option<TableRef> {
+        match self.tables.get(name) {
+            Some(ref mut table) => Some(table.clone()),
+            None => None,
+        }
+    }
+    pub fn gen_table_set(&self, used_table : &Vec<String>) -> TableSet {
+        let mut tables = HashMap::new();
+        for name in used_table.iter() {
+            if let Some(t) = self.tables.get(name) {
+                tables.insert(name.clone(), t.read().unwrap().clone());
+            }
+        }
+        TableSet{
+            tables : tables,
+            page_size : self.file_manager.page_pool.get_page_size(),
+        }
+    }
+    pub fn get_tuple_value(&mut self, table : &String,
+            position : usize,
+            attr_position : usize) -> TupleValue{
+        self.file_manager.get_tuple_value(table, position, attr_position)
+    }
+    pub fn insert(&mut self, table : &String, value_list : &ValueList) -> Result<(), String> {
+        self.file_manager.insert(table, value_list)
+    }
+    pub fn begin_statement(&mut self) {
+        self.file_manager.wal.begin();
+    }
+    pub fn commit_statement(&mut self) {
+        self.file_manager.wal.commit();
+    }
+    pub fn abort_statement(&mut self) {
+        self.file_manager.rollback();
+    }
+    pub fn show_buffer_status(&self) -> String {
+        self.file_manager.buffer_status()
+    }
+    pub fn show_tables(&self) -> String {
+        let mut result = String::new();
+        for (_, t) in self.tables.iter() {
+            result.push_str(&t.read().unwrap().desc());
+            result.push('\n');
+        }
+        result
+    }
+}
+
+impl Encodable for AttrType {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        let mut t = BTreeMap::new();
+        match self {
+            &AttrType::Int => t.insert("type".to_string(), "Int".to_string()),
+            &AttrType::Float => t.insert("type".to_string(), "Float".to_string()),
+            &AttrType::BigInt => t.insert("type".to_string(), "BigInt".to_string()),
+            &AttrType::Double => t.insert("type".to_string(), "Double".to_string()),
+            &AttrType::Decimal{precision, scale} => {
+                t.insert("type".to_string(), "Decimal".to_string());
+                t.insert("precision".to_string(), precision.to_string());
+                t.insert("scale".to_string(), scale.to_string())
+            }
+            &AttrType::Boolean => t.insert("type".to_string(), "Boolean".to_string()),
+            &AttrType::Date => t.insert("type".to_string(), "Date".to_string()),
+            &AttrType::Time => t.insert("type".to_string(), "Time".to_string()),
+            &AttrType::Timestamp => t.insert("type".to_string(), "Timestamp".to_string()),
+            &AttrType::Char{len} => {
+                t.insert("type".to_string(), "Char".to_string());
+                t.insert("len".to_string(), len.to_string())
+            }
+            &AttrType::Varchar{len} => {
+                t.insert("type".to_string(), "Varchar".to_string());
+                t.insert("len".to_string(), len.to_string())
+            }
+            &AttrType::Text => t.insert("type".to_string(), "Text".to_string()),
+            &AttrType::Blob => t.insert("type".to_string(), "Blob".to_string()),
+        };
+        t.encode(s)
+    }
+}
+
+impl Decodable for AttrType {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        let t : BTreeMap<String, String> = try!(BTreeMap::decode(d));
+        let res = match t.get("type") {
+            None => return Err(d.error("can't find key 'type' in AttrType json data")),
+            Some(ref s) => match &s[..] {
+                "Int" => AttrType::Int,
+                "Float" => AttrType::Float,
+                "BigInt" => AttrType::BigInt,
+                "Double" => AttrType::Double,
+                "Decimal" => {
+                    let get = |key : &str| t.get(key).and_then(|n| n.parse::<usize>().ok());
+                    match (get("precision"), get("scale")) {
+                        (Some(precision), Some(scale)) => AttrType::Decimal{ precision : precision, scale : scale },
+                        _ => return Err(d.error("can't find valid key 'precision' and 'scale' for Decimal in AttrType json data")),
+                    }
+                }
+                "Boolean" => AttrType::Boolean,
+                "Date" => AttrType::Date,
+                "Time" => AttrType::Time,
+                "Timestamp" => AttrType::Timestamp,
+                "Text" => AttrType::Text,
+                "Blob" => AttrType::Blob,
+                "Char" | "Varchar" => {
+                    let len = match t.get("len").and_then(|len| len.parse::<usize>().ok()) {
+                        None => return Err(d.error(&format!("can't find valid key 'len' for {} in AttrType json data", s))),
+                        Some(len) => len,
+                    };
+                    if &s[..] == "Char" { AttrType::Char{ len : len } } else { AttrType::Varchar{ len : len } }
+                }
+                _ => return Err(d.error(&format!("unexpected type {}", s))),
+            }
+        };
+        Ok(res)
+    }
+}
+
+// the json layout is the one of the derived implementations of the old compilers
+
+impl Encodable for Attr {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        s.emit_struct("Attr", 4, |s| {
+            try!(s.emit_struct_field("name", 0, |s| self.name.encode(s)));
+            try!(s.emit_struct_field("attr_type", 1, |s| self.attr_type.encode(s)));
+            try!(s.emit_struct_field("primary", 2, |s| self.primary.encode(s)));
+            s.emit_struct_field("nullable", 3, |s| self.nullable.encode(s))
+        })
+    }
+}
+
+impl Decodable for Attr {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("Attr", 4, |d| {
+            Ok(Attr{
+                name : try!(d.read_struct_field("name", 0, Decodable::decode)),
+                attr_type : try!(d.read_struct_field("attr_type", 1, Decodable::decode)),
+                primary : try!(d.read_struct_field("primary", 2, Decodable::decode)),
+                nullable : try!(d.read_struct_field("nullable", 3, Decodable::decode)),
+            })
+        })
+    }
+}
+
+impl Encodable for Index {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        s.emit_struct("Index", 3, |s| {
+            try!(s.emit_struct_field("name", 0, |s| self.name.encode(s)));
+            try!(s.emit_struct_field("attr", 1, |s| self.attr.encode(s)));
+            s.emit_struct_field("unique", 2, |s| self.unique.encode(s))
+        })
+    }
+}
+
+impl Decodable for Index {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("Index", 3, |d| {
+            Ok(Index{
+                name : try!(d.read_struct_field("name", 0, Decodable::decode)),
+                attr : try!(d.read_struct_field("attr", 1, Decodable::decode)),
+                unique : try!(d.read_struct_field("unique", 2, Decodable::decode)),
+            })
+        })
+    }
+}
+
+impl Encodable for Table {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        s.emit_struct("Table", 4, |s| {
+            try!(s.emit_struct_field("name", 0, |s| self.name.encode(s)));
+            try!(s.emit_struct_field("attr_list", 1, |s| self.attr_list.encode(s)));
+            try!(s.emit_struct_field("index_list", 2, |s| self.index_list.encode(s)));
+            s.emit_struct_field("file", 3, |s| self.file.encode(s))
+        })
+    }
+}
+
+impl Decodable for Table {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("Table", 4, |d| {
+            let name : String = try!(d.read_struct_field("name", 0, Decodable::decode));
+            let attr_list = try!(d.read_struct_field("attr_list", 1, Decodable::decode));
+            // the meta data saved before index supported has no index_list
+            let index_list : Option<Vec<Index>> =
+                try!(d.read_struct_field("index_list", 2, Decodable::decode));
+            // and the files are named after the table before alter table supported
+            let file : Option<String> = try!(d.read_struct_field("file", 3, Decodable::decode));
+            Ok(Table{
+                file : file.unwrap_or(name.clone()),
+                name : name,
+                attr_list : attr_list,
+                index_list : index_list.unwrap_or(vec![]),
+            })
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Catalog {
+    pub version : u64,  // increased every time the catalog is saved
+    pub tables : BTreeMap<String, Table>,  // key is table name
+}
+
+impl Encodable for Catalog {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        s.emit_struct("Catalog", 2, |s| {
+            try!(s.emit_struct_field("version", 0, |s| self.version.encode(s)));
+            s.emit_struct_field("tables", 1, |s| self.tables.encode(s))
+        })
+    }
+}
+
+impl Decodable for Catalog {
+    fn decode<D: Decoder>(d: &mut D) -> Result<Self, D::Error> {
+        d.read_struct("Catalog", 2, |d| {
+            Ok(Catalog{
+                version : try!(d.read_struct_field("version", 0, Decodable::decode)),
+                tables : try!(d.read_struct_field("tables", 1, Decodable::decode)),
+            })
+        })
+    }
+}
+
+pub fn parse_catalog(json : &String) -> Result<Catalog, String> {
+    // the content of table_meta.json
+    let data = try!(Json::from_str(json).map_err(|err| format!("{}", err)));
+    // the catalog saved before versioned is only the map of tables
+    let legacy = data.find("version").is_none() || data.find("tables").is_none();
+    let mut decoder = json::Decoder::new(data);
+    if legacy {
+        let tables = try!(Decodable::decode(&mut decoder).map_err(|err| format!("{}", err)));
+        Ok(Catalog{ version : 0, tables : tables })
+    } else {
+        Decodable::decode(&mut decoder).map_err(|err| format!("{}", err))
+    }
+}
+
+fn read_catalog(path : &String) -> Result<Option<Catalog>, String> {
+    if !file_exist(path) {
+        return Ok(None);
+    }
+    let mut json = String::new();
+    let mut file = try!(OpenOptions::new().read(true).open(path).map_err(|err| format!("{}", err)));
+    try!(file.read_to_string(&mut json).map_err(|err| format!("{}", err)));
+    if json.is_empty() {
+        return Ok(None);  // left by the old versions before any table created
+    }
+    parse_catalog(&json).map(Some)
+}
+
+fn check_backup_files(catalog : &Catalog, table_file_dir : &String) -> Result<(), String> {
+    // the backup may refer to the files removed by drop or alter table since then,
+    // and doesn't know the tables created since then
+    let file_names : BTreeSet<String> = catalog.tables.values().map(|t| format!("{}.table", t.file)).collect();
+    for name in file_names.iter() {
+        let path = path_join(table_file_dir, name);
+        if !file_exist(&path) {
+            return Err(format!("{} referred to by the backup is missing", path));
+        }
+    }
+    let entries = match read_dir(table_file_dir) {
+        Ok(entries) => entries,
+        Err(..) => return Ok(()),  // no table file at all
+    };
+    for entry in entries {
+        let name = try!(entry.map_err(|err| format!("{}", err))).file_name().to_string_lossy().into_owned();
+        if name.ends_with(".table") && !file_names.contains(&name) {
+            return Err(format!("{} is not in the backup", path_join(table_file_dir, &name)));
+        }
+    }
+    Ok(())
+}
+
+fn load_catalog(table_meta_dir : &String, table_file_dir : &String) -> Result<(Catalog, Option<String>), String> {
+    // fall back to the backup, the previous version of the catalog, when the catalog is broken,
+    // and return the reason as the warning
+    let full_path = path_join(table_meta_dir, &TABLE_META_FILE.to_string());
+    let backup_path = path_join(table_meta_dir, &TABLE_META_BACKUP_FILE.to_string());
+    let err = match read_catalog(&full_path) {
+        Ok(Some(catalog)) => return Ok((catalog, None)),
+        Ok(None) => None,
+        Err(err) => Some(format!("invalid {}: {}", full_path, err)),
+    };
+    match (read_catalog(&backup_path), err) {
+        (Ok(Some(catalog)), err) => {
+            let err = err.unwrap_or(format!("{} not found", full_path));
+            if let Err(file_err) = check_backup_files(&catalog, table_file_dir) {
+                return Err(format!("{}, and the backup {} of version {} doesn't match the table files: {}",
+                    err, backup_path, catalog.version, file_err));
+            }
+            let warning = format!("{}, using the backup {} of version {}", err, backup_path, catalog.version);
+            Ok((catalog, Some(warning)))
+        }
+        (Ok(None), None) => Ok((Catalog{ version : 0, tables : BTreeMap::new() }, None)),
+        (Ok(None), Some(err)) => Err(format!("{}, and no backup found", err)),
+        (Err(backup_err), err) => Err(format!("{}, and invalid backup {}: {}",
+            err.unwrap_or(format!("{} not found", full_path)), backup_path, backup_err)),
+    }
+}
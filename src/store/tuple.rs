@@ -1,57 +1,157 @@
-use std::vec::Vec;
-use std::ptr::read;
-use utils::pointer::read_string;
-use super::buffer::DataPtr;
-use super::table::{AttrType, Attr};
-
-
-#[derive(Debug, Clone)]
-pub enum TupleValue {
-    Int(i32),
-    Float(f32),
-    Char(String),
-}
-
-#[derive(Debug, Clone)]
-pub struct TupleDesc {
-    pub attr_desc : Vec<AttrType>,
-    pub tuple_len : usize,
-}
-
-impl TupleDesc {
-    pub fn new(attr_list : &Vec<Attr>) -> TupleDesc {
-        let attr_desc = attr_list.iter().map(|attr| attr.attr_type.clone()).collect();
-        let tuple_len = tuple_len(attr_list);
-        TupleDesc{
-            attr_desc : attr_desc,
-            tuple_len : tuple_len,
-        }
-    }
-}
-
-pub type TupleData = Vec<DataPtr>;
-
-pub fn tuple_len(attr_list : &Vec<Attr>) -> usize {
-    let mut l = 0;
-    for attr in attr_list {
-        l += match attr.attr_type {
-            AttrType::Int | AttrType::Float => 4,
-            AttrType::Char{len} => (len + 3) / 4 * 4,  // align to 4 bytes
-        }
-    }
-    l
-}
-
-pub fn gen_tuple_value(attr_desc : &Vec<AttrType>, tuple_data : TupleData) -> Vec<TupleValue> {
-    let mut value_list = Vec::new();
-    assert_eq!(attr_desc.len(), tuple_data.len());
-    for (attr, p) in attr_desc.iter().zip(tuple_data.iter()) {
-        let value = match attr {
-            &AttrType::Int => TupleValue::Int(unsafe{read::<i32>(*p as *const i32)}),
-            &AttrType::Float => TupleValue::Float(unsafe{read::<f32>(*p as *const f32)}),
-            &AttrType::Char{len} => TupleValue::Char(unsafe{read_string(*p, len)}),
-        };
-        value_list.push(value);
-    }
-    value_list
-}
+use std::vec::Vec;
+use utils::pointer::{read_string, read_varchar, read_int, read_float, read_bigint, read_double};
+use utils::decimal::{parse_decimal, decimal_fits};
+use utils::datetime::{parse_date, parse_time, parse_timestamp};
+use ::parser::common::{ValueExpr, ValueType};
+use super::buffer::DataPtr;
+use super::table::{AttrType, Attr};
+use super::overflow::{INLINE_VALUE_LEN, large_value_space, read_large_value};
+
+
+#[derive(Debug, Clone)]
+pub enum TupleValue {
+    Int(i32),
+    Float(f32),
+    BigInt(i64),
+    Double(f64),
+    Decimal(i64, usize),  // the value multiplied by 10^scale, and the scale
+    Boolean(bool),
+    Date(i32),
+    Time(i64),
+    Timestamp(i64),
+    Char(String),
+    Bytes(Vec<u8>),  // text or blob
+    Overflow{ len : usize, first_page : usize },  // text or blob left in the overflow pages
+    Null,
+}
+
+#[derive(Debug, Clone)]
+pub struct TupleDesc {
+    pub attr_desc : Vec<AttrType>,
+    pub null_bitmap_len : usize,  // 0 when no attribute is nullable
+    pub tuple_len : usize,  // the longest tuple if the tuples are variable-length
+    pub min_tuple_len : usize,
+    pub variable : bool,  // saved in slotted pages when any attribute is varchar, text or blob
+}
+
+impl TupleDesc {
+    pub fn new(attr_list : &Vec<Attr>) -> TupleDesc {
+        let attr_desc = attr_list.iter().map(|attr| attr.attr_type.clone()).collect();
+        let tuple_len = tuple_len(attr_list);
+        TupleDesc{
+            attr_desc : attr_desc,
+            null_bitmap_len : null_bitmap_len(attr_list),
+            tuple_len : tuple_len,
+            min_tuple_len : min_tuple_len(attr_list),
+            variable : attr_list.iter().any(
+                |attr| is_match!(attr.attr_type, AttrType::Varchar{..}) || attr.attr_type.is_large()),
+        }
+    }
+}
+
+// pointer of null value is null
+pub type TupleData = Vec<DataPtr>;
+
+pub fn get_tuple_ptr(tuple_data : &TupleData) -> DataPtr {
+    // any pointer inside the tuple, primary key is never null
+    *tuple_data.iter().filter(|p| !p.is_null()).next().unwrap()
+}
+
+pub fn null_bitmap_len(attr_list : &Vec<Attr>) -> usize {
+    // the null bitmap is placed before the attributes
+    if attr_list.iter().any(|attr| attr.nullable) {
+        ((attr_list.len() + 7) / 8 + 3) / 4 * 4  // align to 4 bytes
+    } else {
+        0
+    }
+}
+
+pub fn tuple_len(attr_list : &Vec<Attr>) -> usize {
+    let mut l = null_bitmap_len(attr_list);
+    for attr in attr_list {
+        l += attr_len(&attr.attr_type);
+    }
+    l
+}
+
+pub fn min_tuple_len(attr_list : &Vec<Attr>) -> usize {
+    // all the varchars, texts and blobs are empty
+    let mut l = null_bitmap_len(attr_list);
+    for attr in attr_list {
+        l += match attr.attr_type {
+            AttrType::Varchar{..} => varchar_space(0),
+            AttrType::Text | AttrType::Blob => large_value_space(0),
+            ref attr_type => attr_len(attr_type),
+        }
+    }
+    l
+}
+
+pub fn attr_len(attr_type : &AttrType) -> usize {
+    // the space for the longest value
+    match attr_type {
+        &AttrType::Int | &AttrType::Float | &AttrType::Boolean | &AttrType::Date => 4,
+        &AttrType::BigInt | &AttrType::Double | &AttrType::Decimal{..} | &AttrType::Time | &AttrType::Timestamp => 8,
+        &AttrType::Char{len} => (len + 3) / 4 * 4,  // align to 4 bytes
+        &AttrType::Varchar{len} => varchar_space(len),
+        &AttrType::Text | &AttrType::Blob => large_value_space(INLINE_VALUE_LEN),
+    }
+}
+
+pub fn varchar_space(len : usize) -> usize {
+    // the length and the bytes, aligned to 4 bytes
+    (2 + len + 3) / 4 * 4
+}
+
+pub fn gen_tuple_value(attr_desc : &Vec<AttrType>, tuple_data : TupleData) -> Vec<TupleValue> {
+    let mut value_list = Vec::new();
+    assert_eq!(attr_desc.len(), tuple_data.len());
+    for (attr, p) in attr_desc.iter().zip(tuple_data.iter()) {
+        if p.is_null() {
+            value_list.push(TupleValue::Null);
+            continue;
+        }
+        let value = match attr {
+            &AttrType::Int => TupleValue::Int(unsafe{read_int(*p)}),
+            &AttrType::Float => TupleValue::Float(unsafe{read_float(*p)}),
+            &AttrType::BigInt => TupleValue::BigInt(unsafe{read_bigint(*p)}),
+            &AttrType::Double => TupleValue::Double(unsafe{read_double(*p)}),
+            &AttrType::Decimal{scale, ..} => TupleValue::Decimal(unsafe{read_bigint(*p)}, scale),
+            &AttrType::Boolean => TupleValue::Boolean(unsafe{read_int(*p)} != 0),
+            &AttrType::Date => TupleValue::Date(unsafe{read_int(*p)}),
+            &AttrType::Time => TupleValue::Time(unsafe{read_bigint(*p)}),
+            &AttrType::Timestamp => TupleValue::Timestamp(unsafe{read_bigint(*p)}),
+            &AttrType::Char{len} => TupleValue::Char(unsafe{read_string(*p, len)}),
+            &AttrType::Varchar{..} => TupleValue::Char(unsafe{read_varchar(*p)}),
+            &AttrType::Text | &AttrType::Blob => unsafe{read_large_value(*p)},
+        };
+        value_list.push(value);
+    }
+    value_list
+}
+
+pub fn parse_tuple_value(value : &ValueExpr, attr_type : &AttrType) -> Option<TupleValue> {
+    // return None if the value can't be saved in the attribute, decimals are rounded to the scale
+    match (value.value_type, attr_type) {
+        (ValueType::Null, _) => Some(TupleValue::Null),
+        (ValueType::Integer, &AttrType::Int) => value.value.parse::<i32>().ok().map(TupleValue::Int),
+        (ValueType::Integer, &AttrType::BigInt) => value.value.parse::<i64>().ok().map(TupleValue::BigInt),
+        (ValueType::Integer, &AttrType::Float) | (ValueType::Float, &AttrType::Float) =>
+            value.value.parse::<f32>().ok().map(TupleValue::Float),
+        (ValueType::Integer, &AttrType::Double) | (ValueType::Float, &AttrType::Double) =>
+            value.value.parse::<f64>().ok().map(TupleValue::Double),
+        (ValueType::Integer, &AttrType::Decimal{precision, scale})
+            | (ValueType::Float, &AttrType::Decimal{precision, scale}) =>
+            parse_decimal(&value.value, scale).and_then(|n|
+                if decimal_fits(n, precision) { Some(TupleValue::Decimal(n, scale)) } else { None }),
+        (ValueType::Boolean, &AttrType::Boolean) => Some(TupleValue::Boolean(value.value == "true")),
+        (ValueType::Date, &AttrType::Date) => parse_date(&value.value).map(TupleValue::Date),
+        (ValueType::Time, &AttrType::Time) => parse_time(&value.value).map(TupleValue::Time),
+        (ValueType::Timestamp, &AttrType::Timestamp) => parse_timestamp(&value.value).map(TupleValue::Timestamp),
+        (ValueType::String, &AttrType::Char{..}) | (ValueType::String, &AttrType::Varchar{..}) =>
+            Some(TupleValue::Char(value.value.clone())),
+        (ValueType::String, &AttrType::Text) | (ValueType::String, &AttrType::Blob) =>
+            Some(TupleValue::Bytes(value.value.clone().into_bytes())),
+        _ => None,
+    }
+}
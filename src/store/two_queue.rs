@@ -0,0 +1,130 @@
+use std::collections::{HashMap, VecDeque};
+use std::cmp::max;
+use std::option::Option::{Some, None};
+use super::lru::CacheValue;
+use super::policy::{CachePolicy, ALL_PINNED};
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Queue {
+    In,    // fifo of pages accessed once
+    Main,  // lru of pages accessed again after leaving the fifo
+}
+
+// 2Q: new pages enter a small fifo, and only the pages referenced again after
+// being replaced from the fifo (remembered in the ghost queue) enter the main lru,
+// so a scan only flushes the fifo
+#[derive(Debug)]
+pub struct TwoQueueCache<ValueType : CacheValue> {
+    capacity : usize,
+    in_capacity : usize,
+    ghost_capacity : usize,
+    entries : HashMap<ValueType::KeyType, (ValueType, Queue)>,
+    in_queue : VecDeque<ValueType::KeyType>,     // front is the oldest
+    main_queue : VecDeque<ValueType::KeyType>,   // front is the least recently used
+    ghost_queue : VecDeque<ValueType::KeyType>,  // keys replaced from the fifo
+    victim : Option<ValueType::KeyType>,
+}
+
+impl<ValueType : CacheValue> TwoQueueCache<ValueType> {
+    pub fn new(capacity : usize) -> TwoQueueCache<ValueType> {
+        assert!(capacity > 0);
+        TwoQueueCache{
+            capacity : capacity,
+            in_capacity : max(1, capacity / 4),
+            ghost_capacity : max(1, capacity / 2),
+            entries : HashMap::with_capacity(capacity),
+            in_queue : VecDeque::new(),
+            main_queue : VecDeque::new(),
+            ghost_queue : VecDeque::new(),
+            victim : None,
+        }
+    }
+    fn find_unpinned(&self, queue : Queue) -> Option<ValueType::KeyType> {
+        let keys = match queue {
+            Queue::In => &self.in_queue,
+            Queue::Main => &self.main_queue,
+        };
+        keys.iter().find(|key| !self.entries.get(*key).unwrap().0.is_pinned()).cloned()
+    }
+    fn remove_from_queue(&mut self, key : &ValueType::KeyType, queue : Queue) {
+        let keys = match queue {
+            Queue::In => &mut self.in_queue,
+            Queue::Main => &mut self.main_queue,
+        };
+        let i = keys.iter().position(|k| k == key).unwrap();
+        keys.remove(i);
+    }
+}
+
+impl<ValueType : CacheValue> CachePolicy<ValueType> for TwoQueueCache<ValueType> {
+    fn capacity(&self) -> usize { self.capacity }
+    fn get_load(&self) -> usize { self.entries.len() }
+    fn get(&mut self, key : &ValueType::KeyType) -> Option<ValueType> {
+        let (value, queue) = match self.entries.get(key) {
+            Some(&(ref value, queue)) => (value.clone(), queue),
+            None => return None,
+        };
+        // pages in the fifo are not moved, correlated references are ignored
+        if queue == Queue::Main {
+            self.remove_from_queue(key, Queue::Main);
+            self.main_queue.push_back(key.clone());
+        }
+        Some(value)
+    }
+    fn put(&mut self, key : &ValueType::KeyType, value : ValueType) {
+        assert!(self.entries.get(key).is_none());
+        assert!(self.entries.len() < self.capacity);
+        let queue = match self.ghost_queue.iter().position(|k| k == key) {
+            Some(i) => {
+                self.ghost_queue.remove(i);
+                self.main_queue.push_back(key.clone());
+                Queue::Main
+            }
+            None => {
+                self.in_queue.push_back(key.clone());
+                Queue::In
+            }
+        };
+        self.entries.insert(key.clone(), (value, queue));
+    }
+    fn remove(&mut self, key : &ValueType::KeyType) -> Option<ValueType> {
+        match self.entries.remove(key) {
+            Some((value, queue)) => {
+                self.remove_from_queue(key, queue);
+                Some(value)
+            }
+            None => None,
+        }
+    }
+    fn prepare_page(&mut self) -> Result<Option<ValueType>, String> {
+        if self.entries.len() < self.capacity {
+            return Ok(None);
+        }
+        let (first, second) = if self.in_queue.len() >= self.in_capacity {
+            (Queue::In, Queue::Main)
+        } else {
+            (Queue::Main, Queue::In)
+        };
+        let key = match self.find_unpinned(first).or_else(|| self.find_unpinned(second)) {
+            Some(key) => key,
+            None => return Err(ALL_PINNED.to_string()),
+        };
+        let value = self.entries.get(&key).unwrap().0.clone();
+        self.victim = Some(key);
+        Ok(Some(value))
+    }
+    fn remove_victim(&mut self) {
+        // call this function if returned value of prepare_page is not None
+        let key = self.victim.take().unwrap();
+        let (value, queue) = self.entries.remove(&key).unwrap();
+        assert!(!value.is_pinned());
+        self.remove_from_queue(&key, queue);
+        if queue == Queue::In {
+            self.ghost_queue.push_back(key);
+            if self.ghost_queue.len() > self.ghost_capacity {
+                self.ghost_queue.pop_front();
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::vec::Vec;
+use std::collections::HashSet;
+use std::fs::{OpenOptions, File};
+use std::io::{Read, Write, Seek, SeekFrom};
+use std::slice::from_raw_parts;
+use ::utils::file::path_join;
+use ::utils::checksum::crc32;
+use ::utils::endian::{push_u16_le, push_u32_le, push_u64_le, read_u16_le, read_u32_le, read_u64_le};
+use super::buffer::DataPtr;
+use super::durability::Durability;
+
+
+// Every change made to a page is appended to the log as a before and after image
+// of the modified byte range, so the log can both redo committed statements
+// and undo the statement in progress when the server is killed.
+// Each record is framed as:
+// (1) body_len (4 bytes, little endian)
+// (2) crc32 of body (4 bytes, little endian)
+// (3) body, starting with the record tag
+
+const BEGIN_TAG : u8 = 1;
+const COMMIT_TAG : u8 = 2;
+const PAGE_WRITE_TAG : u8 = 3;
+
+// txn 0 is used for changes made outside of a statement, they are never undone
+pub const NO_TXN : u64 = 0;
+pub const WAL_FILE : &'static str = "blastoise.wal";
+
+#[derive(Debug, Clone)]
+pub enum LogRecord {
+    Begin{ txn : u64 },
+    Commit{ txn : u64 },
+    PageWrite{
+        txn : u64,
+        table : String,
+        page_index : usize,
+        offset : usize,
+        before : Vec<u8>,
+        after : Vec<u8>,
+    },
+}
+
+impl LogRecord {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        match self {
+            &LogRecord::Begin{txn} => {
+                body.push(BEGIN_TAG);
+                push_u64_le(&mut body, txn);
+            }
+            &LogRecord::Commit{txn} => {
+                body.push(COMMIT_TAG);
+                push_u64_le(&mut body, txn);
+            }
+            &LogRecord::PageWrite{txn, ref table, page_index, offset, ref before, ref after} => {
+                assert_eq!(before.len(), after.len());
+                body.push(PAGE_WRITE_TAG);
+                push_u64_le(&mut body, txn);
+                push_u16_le(&mut body, table.len() as u16);
+                body.extend_from_slice(table.as_bytes());
+                push_u32_le(&mut body, page_index as u32);
+                push_u32_le(&mut body, offset as u32);
+                push_u32_le(&mut body, before.len() as u32);
+                body.extend_from_slice(before);
+                body.extend_from_slice(after);
+            }
+        }
+        let mut record = Vec::with_capacity(body.len() + 8);
+        push_u32_le(&mut record, body.len() as u32);
+        push_u32_le(&mut record, crc32(&body));
+        record.extend_from_slice(&body);
+        record
+    }
+
+    pub fn decode(body : &[u8]) -> Option<LogRecord> {
+        if body.len() < 9 {
+            return None;
+        }
+        let txn = read_u64_le(body, 1);
+        match body[0] {
+            BEGIN_TAG => Some(LogRecord::Begin{ txn : txn }),
+            COMMIT_TAG => Some(LogRecord::Commit{ txn : txn }),
+            PAGE_WRITE_TAG => {
+                let mut pos = 9;
+                if body.len() < pos + 2 { return None; }
+                let table_len = read_u16_le(body, pos) as usize;
+                pos += 2;
+                if body.len() < pos + table_len + 12 { return None; }
+                let table = String::from_utf8_lossy(&body[pos..pos + table_len]).into_owned();
+                pos += table_len;
+                let page_index = read_u32_le(body, pos) as usize;
+                let offset = read_u32_le(body, pos + 4) as usize;
+                let len = read_u32_le(body, pos + 8) as usize;
+                pos += 12;
+                if body.len() != pos + 2 * len { return None; }
+                Some(LogRecord::PageWrite{
+                    txn : txn,
+                    table : table,
+                    page_index : page_index,
+                    offset : offset,
+                    before : body[pos..pos + len].to_vec(),
+                    after : body[pos + len..].to_vec(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_txn(&self) -> u64 {
+        match self {
+            &LogRecord::Begin{txn} | &LogRecord::Commit{txn} => txn,
+            &LogRecord::PageWrite{txn, ..} => txn,
+        }
+    }
+}
+
+
+#[derive(Debug)]
+pub struct WalManager {
+    file : File,
+    next_txn : u64,
+    curr_txn : u64,
+    curr_records : Vec<LogRecord>,  // page writes of curr_txn, used for rollback
+    unsynced : bool,
+    durability : Durability,
+}
+
+impl WalManager {
+    pub fn new(dir : &String, durability : Durability) -> WalManager {
+        let full_path = path_join(dir, &WAL_FILE.to_string());
+        let file = check_ok!(OpenOptions::new().read(true).append(true).create(true).open(&full_path));
+        WalManager{
+            file : file,
+            next_txn : NO_TXN + 1,
+            curr_txn : NO_TXN,
+            curr_records : Vec::new(),
+            unsynced : false,
+            durability : durability,
+        }
+    }
+    pub fn begin(&mut self) {
+        assert_eq!(self.curr_txn, NO_TXN);
+        self.curr_txn = self.next_txn;
+        self.next_txn += 1;
+        let txn = self.curr_txn;
+        self.append(&LogRecord::Begin{ txn : txn });
+    }
+    pub fn commit(&mut self) {
+        if self.curr_txn == NO_TXN {
+            return;
+        }
+        let txn = self.curr_txn;
+        self.append(&LogRecord::Commit{ txn : txn });
+        if self.durability == Durability::PerStatement {
+            self.sync();
+        }
+        self.curr_txn = NO_TXN;
+        self.curr_records.clear();
+    }
+    pub fn take_curr_records(&mut self) -> Vec<LogRecord> {
+        // for rollback, the returned page writes should be undone in reverse order
+        let mut records = Vec::new();
+        records.extend(self.curr_records.drain(..));
+        records
+    }
+    pub fn in_txn(&self) -> bool {
+        self.curr_txn != NO_TXN
+    }
+    pub fn log_page_write(&mut self, table : &String, page_index : usize,
+            before : &[u8], page_data : DataPtr) {
+        let after = unsafe{ from_raw_parts::<u8>(page_data as *const u8, before.len()) };
+        let (begin, end) = match diff_range(before, after) {
+            Some(range) => range,
+            None => return,
+        };
+        let record = LogRecord::PageWrite{
+            txn : self.curr_txn,
+            table : table.clone(),
+            page_index : page_index,
+            offset : begin,
+            before : before[begin..end].to_vec(),
+            after : after[begin..end].to_vec(),
+        };
+        self.append(&record);
+        if self.curr_txn != NO_TXN {
+            self.curr_records.push(record);
+        }
+    }
+    pub fn sync(&mut self) {
+        // must be called before any page is written back to the table file
+        if self.unsynced && self.durability.need_sync() {
+            check_ok!(self.file.sync_data());
+            self.unsynced = false;
+        }
+    }
+    pub fn checkpoint(&mut self) {
+        // all the changes have reached the table files, the log is no longer needed
+        assert!(!self.in_txn());
+        check_ok!(self.file.set_len(0));
+        if self.durability.need_sync() {
+            check_ok!(self.file.sync_data());
+        }
+        self.unsynced = false;
+    }
+    pub fn read_records(&mut self) -> Vec<LogRecord> {
+        let mut data = Vec::new();
+        check_ok!(self.file.seek(SeekFrom::Start(0)));
+        check_ok!(self.file.read_to_end(&mut data));
+        let mut records = Vec::new();
+        let mut pos = 0;
+        while pos + 8 <= data.len() {
+            let body_len = read_u32_le(&data, pos) as usize;
+            let sum = read_u32_le(&data, pos + 4);
+            if pos + 8 + body_len > data.len() {
+                break;  // torn record at the tail
+            }
+            let body = &data[pos + 8 .. pos + 8 + body_len];
+            if crc32(body) != sum {
+                break;
+            }
+            match LogRecord::decode(body) {
+                Some(record) => records.push(record),
+                None => break,
+            }
+            pos += 8 + body_len;
+        }
+        records
+    }
+    fn append(&mut self, record : &LogRecord) {
+        check_ok!(self.file.write_all(&record.encode()));
+        self.unsynced = true;
+    }
+}
+
+pub fn find_losers(records : &Vec<LogRecord>) -> HashSet<u64> {
+    // statements which began but never committed
+    let mut losers = HashSet::new();
+    for record in records.iter() {
+        match record {
+            &LogRecord::Begin{txn} => { losers.insert(txn); }
+            &LogRecord::Commit{txn} => { losers.remove(&txn); }
+            _ => (),
+        }
+    }
+    losers
+}
+
+fn diff_range(before : &[u8], after : &[u8]) -> Option<(usize, usize)> {
+    let begin = match before.iter().zip(after.iter()).position(|(a, b)| a != b) {
+        Some(i) => i,
+        None => return None,
+    };
+    let end = before.len() - before.iter().rev().zip(after.iter().rev())
+        .position(|(a, b)| a != b).unwrap();
+    Some((begin, end))
+}
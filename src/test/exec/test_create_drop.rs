@@ -1,47 +1,231 @@
-use ::parser::common::Statement;
-use ::store::table::{TableManager, Table, Attr, AttrType};
-use ::utils::config::Config;
-use ::exec::gen_plan::gen_plan;
-
-
-#[test]
-fn test_create_table() {
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 2
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let manager = TableManager::make_ref(&config);
-    assert_pattern!(manager.borrow().get_table("msg"), None);
-    let mut plan = gen_plan_helper!(
-        "create table msg(id int not null primary, content char(233))", &manager);
-    plan.open();
-    assert_pattern!(plan.get_next(), None);
-    let table = extract!(manager.borrow().get_table("msg"), Some(tab), tab);
-    let tab = table.borrow();
-    assert_eq!(tab.name, "msg");
-    assert_eq!(tab.attr_list.len(), 2);
-}
-
-#[test]
-fn test_drop_table() {
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 2
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let manager = TableManager::make_ref(&config);
-    let table = Table{
-        name : "msg".to_string(),
-        attr_list : vec![Attr{
-                name : "id".to_string(),
-                attr_type : AttrType::Int,
-                primary : true,
-                nullable : false,
-            }],
-    };
-    manager.borrow_mut().add_table(table);
-    let mut plan = gen_plan_helper!("drop table msg", &manager);
-    assert_pattern!(manager.borrow().get_table("msg"), Some(..));
-    plan.open();
-    assert_pattern!(plan.get_next(), None);
-    assert_pattern!(manager.borrow().get_table("msg"), None);
-}
+use std::sync::{Arc, RwLock};
+use ::parser::common::Statement;
+use ::store::table::{TableManager, TableManagerRef, Table, Attr, AttrType};
+use ::store::tuple::TupleValue;
+use ::utils::config::Config;
+use ::utils::file::{path_join, file_exist};
+use ::exec::gen_plan::gen_plan;
+use ::exec::error::ExecErrorType;
+use ::test::exec::test_query::gen_test_table;
+use ::test::fixture::{gen_test_config, run_plan, count_rows};
+
+
+#[test]
+fn test_create_table() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 2
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let manager = TableManager::make_ref(&config);
+    assert_pattern!(manager.read().unwrap().get_table("msg"), None);
+    let mut plan = gen_plan_helper!(
+        "create table msg(id int not null primary, content char(233))", &manager);
+    plan.open();
+    assert_pattern!(plan.get_next(), None);
+    let table = extract!(manager.read().unwrap().get_table("msg"), Some(tab), tab);
+    let tab = table.read().unwrap();
+    assert_eq!(tab.name, "msg");
+    assert_eq!(tab.attr_list.len(), 2);
+}
+
+#[test]
+fn test_drop_table() {
+    let config = gen_test_config("test_drop_table", 4);
+    let file_dir = config.get_str("table_file_dir");
+    let manager = TableManager::make_ref(&config);
+    let table = Table{
+        name : "msg".to_string(),
+        file : "msg".to_string(),
+        attr_list : vec![Attr{
+                name : "id".to_string(),
+                attr_type : AttrType::Int,
+                primary : true,
+                nullable : false,
+            }],
+        index_list : vec![],
+    };
+    manager.write().unwrap().add_table(table.clone());
+    assert_pattern!(run_plan("insert msg values(1)", &manager), None);
+    let fd = manager.read().unwrap().file_manager.get_file_fd(&"msg".to_string());
+    assert_pattern!(manager.write().unwrap().file_manager.page_pool.get_page(fd, 0), Some(..));
+    let mut plan = gen_plan_helper!("drop table msg", &manager);
+    assert_pattern!(manager.read().unwrap().get_table("msg"), Some(..));
+    plan.open();
+    assert_pattern!(plan.get_next(), None);
+    assert_pattern!(manager.read().unwrap().get_table("msg"), None);
+    assert_pattern!(manager.write().unwrap().file_manager.page_pool.get_page(fd, 0), None);
+    for name in ["msg.table", "msg.fsm", "msg.primary.index"].iter() {
+        assert!(!file_exist(&path_join(&file_dir, &name.to_string())));
+    }
+
+    // table created with the same name is empty
+    manager.write().unwrap().add_table(table);
+    let mut select = gen_plan_helper!("select * from msg", &manager);
+    select.open();
+    assert_pattern!(select.get_next(), None);
+    assert_pattern!(run_plan("insert msg values(1)", &manager), None);
+
+    // the catalog is saved by drop
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_pattern!(manager.read().unwrap().get_table("msg"), None);
+}
+
+#[test]
+fn test_create_drop_index() {
+    let config = gen_test_config("test_create_drop_index", 4);
+    let file_dir = config.get_str("table_file_dir");
+    let table_name = "test_create_drop_index_message".to_string();
+    let manager = TableManager::make_ref(&config);
+    manager.write().unwrap().add_table(gen_test_table(&table_name));
+    for id in 0..20 {
+        assert_pattern!(run_plan(&format!("insert {} values({}, {}.5, \"c{}\")",
+            table_name, id, id, id % 5), &manager), None);
+    }
+
+    let sql = format!("create index content_index on {}(content)", table_name);
+    assert_pattern!(run_plan(&sql, &manager), None);
+    let table = manager.read().unwrap().get_table(&table_name).unwrap();
+    assert_eq!(table.read().unwrap().index_list.len(), 1);
+    let content = vec![TupleValue::Char("c3".to_string())];
+    assert_eq!(manager.write().unwrap().file_manager.index_lookup(
+        &table_name, &"content_index".to_string(), &content).len(), 4);
+    let mut select = gen_plan_helper!(
+        &format!("select * from {} where content = \"c3\" and id > 10", table_name), &manager);
+    assert!(format!("{:?}", select).contains("content_index"));
+    select.open();
+    let mut count = 0;
+    while let Some(..) = select.get_next() {
+        count += 1;
+    }
+    select.close();
+    assert_eq!(count, 2);  // 13, 18
+
+    // duplicate keys found when building
+    let sql = format!("create unique index content_unique on {}(content)", table_name);
+    assert_eq!(run_plan(&sql, &manager), Some(ExecErrorType::UniqueKeyExist));
+    assert_eq!(table.read().unwrap().index_list.len(), 1);
+    assert_pattern!(manager.read().unwrap().file_manager.get_index(&table_name, &"content_unique".to_string()), None);
+
+    let sql = format!("create unique index score_unique on {}(score)", table_name);
+    assert_pattern!(run_plan(&sql, &manager), None);
+    let sql = format!("insert {} values(100, 3.5, \"c\")", table_name);
+    assert_eq!(run_plan(&sql, &manager), Some(ExecErrorType::UniqueKeyExist));
+    let sql = format!("insert {} values(3, 100.5, \"c\")", table_name);
+    assert_eq!(run_plan(&sql, &manager), Some(ExecErrorType::PrimaryKeyExist));
+    for id in 100..102 {
+        let sql = format!("insert {} values({}, null, \"c\")", table_name, id);
+        assert_pattern!(run_plan(&sql, &manager), None);  // null never conflicts
+    }
+    let sql = format!("update {} set score = 3.5 where id = 4", table_name);
+    assert_eq!(run_plan(&sql, &manager), Some(ExecErrorType::UniqueKeyExist));
+    // the primary index is checked first
+    let sql = format!("update {} set id = 3 where id = 4", table_name);
+    assert_eq!(run_plan(&sql, &manager), Some(ExecErrorType::PrimaryKeyExist));
+    let sql = format!("update {} set id = 3, score = 3.5 where id = 4", table_name);
+    assert_eq!(run_plan(&sql, &manager), Some(ExecErrorType::PrimaryKeyExist));
+    let score = vec![TupleValue::Float(4.5)];
+    assert_eq!(manager.write().unwrap().file_manager.index_lookup(
+        &table_name, &"score_unique".to_string(), &score).len(), 1);
+    let sql = format!("update {} set score = 200.5 where id = 4", table_name);
+    assert_pattern!(run_plan(&sql, &manager), None);
+    assert_eq!(manager.write().unwrap().file_manager.index_lookup(
+        &table_name, &"score_unique".to_string(), &score).len(), 0);
+
+    let sql = format!("drop index content_index on {}", table_name);
+    assert_pattern!(run_plan(&sql, &manager), None);
+    assert_eq!(table.read().unwrap().index_list.len(), 1);
+    assert!(!file_exist(&path_join(&file_dir,
+        &format!("{}.content_index.index", table_name))));
+
+    // indexes are opened from the meta data
+    manager.write().unwrap().save_to_file();
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_eq!(manager.write().unwrap().file_manager.index_lookup(
+        &table_name, &"score_unique".to_string(), &vec![TupleValue::Float(7.5)]).len(), 1);
+    assert_pattern!(manager.read().unwrap().file_manager.get_index(&table_name, &"content_index".to_string()), None);
+}
+
+#[test]
+fn test_alter_table() {
+    let config = gen_test_config("test_alter_table", 4);
+    let file_dir = config.get_str("table_file_dir");
+    let manager = TableManager::make_ref(&config);
+    manager.write().unwrap().add_table(gen_test_table(&"msg".to_string()));
+    for id in 0..300 {
+        let score = if id % 3 == 0 { "null".to_string() } else { format!("{}.5", id) };
+        assert_pattern!(run_plan(&format!("insert msg values({}, {}, \"c{}\")", id, score, id % 5), &manager), None);
+    }
+    assert_pattern!(run_plan("create index content_index on msg(content)", &manager), None);
+    manager.write().unwrap().flush();
+
+    assert_pattern!(run_plan("alter table msg add column price int not null default 7", &manager), None);
+    assert_pattern!(run_plan("alter table msg add column note char(4)", &manager), None);
+    manager.write().unwrap().flush();
+    assert_eq!(count_rows("select * from msg where price = 7 and note is null", &manager), 300);
+    assert_eq!(count_rows("select * from msg where score is null", &manager), 100);
+    assert_eq!(count_rows("select * from msg where content = \"c3\"", &manager), 60);
+    assert_pattern!(run_plan("insert msg values(300, 1.5, \"c\", 8, \"n\")", &manager), None);
+    assert_eq!(run_plan("insert msg values(1, 1.5, \"c\", 8, \"n\")", &manager),
+        Some(ExecErrorType::PrimaryKeyExist));
+    // the old files are removed once the catalog is saved
+    assert!(!file_exist(&path_join(&file_dir, &"msg.table".to_string())));
+    assert!(!file_exist(&path_join(&file_dir, &"msg.content_index.index".to_string())));
+
+    assert_pattern!(run_plan("alter table msg drop column content", &manager), None);
+    let table = manager.read().unwrap().get_table("msg").unwrap();
+    assert_eq!(table.read().unwrap().attr_list.len(), 4);
+    assert_eq!(table.read().unwrap().index_list.len(), 0);
+    assert_eq!(count_rows("select * from msg where score > 100", &manager), 134);
+
+    assert_pattern!(run_plan("alter table msg rename column score to grade", &manager), None);
+    assert_pattern!(run_plan("alter table msg rename to message", &manager), None);
+    assert_pattern!(manager.read().unwrap().get_table("msg"), None);
+    assert_eq!(count_rows("select * from message where grade > 100", &manager), 134);
+    manager.write().unwrap().flush();
+
+    // the rows are kept after restarting
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_pattern!(manager.read().unwrap().get_table("msg"), None);
+    assert_eq!(count_rows("select * from message where grade > 100", &manager), 134);
+    assert_eq!(count_rows("select * from message where price = 8", &manager), 1);
+    assert_eq!(count_rows("select * from message", &manager), 301);
+}
+
+#[test]
+fn test_composite_primary_key() {
+    let config = gen_test_config("test_composite_primary_key", 4);
+    let manager = TableManager::make_ref(&config);
+    assert_pattern!(run_plan("create table enroll(student char(8) not null, course int not null, \
+        grade float, primary key (student, course))", &manager), None);
+    for &(student, course) in [("s1", 1), ("s1", 2), ("s2", 1)].iter() {
+        let sql = format!("insert enroll values(\"{}\", {}, 1.5)", student, course);
+        assert_pattern!(run_plan(&sql, &manager), None);
+    }
+    assert_eq!(run_plan("insert enroll values(\"s1\", 1, 2.5)", &manager), Some(ExecErrorType::PrimaryKeyExist));
+
+    // the primary key index is used only when all of its attributes are given
+    let select = gen_plan_helper!("select * from enroll where course = 2 and student = \"s1\"", &manager);
+    assert!(format!("{:?}", select).contains("IndexScan"));
+    let select = gen_plan_helper!("select * from enroll where student = \"s1\"", &manager);
+    assert!(!format!("{:?}", select).contains("IndexScan"));
+    assert_eq!(count_rows("select * from enroll where student = \"s1\" and course = 2", &manager), 1);
+    assert_eq!(count_rows("select * from enroll where student = \"s1\"", &manager), 2);
+
+    // the primary attributes can be updated as long as the key stays unique
+    assert_eq!(run_plan("update enroll set student = \"s1\" where student = \"s2\"", &manager),
+        Some(ExecErrorType::PrimaryKeyExist));
+    assert_eq!(run_plan("update enroll set course = 2 where student = \"s1\" and course = 1", &manager),
+        Some(ExecErrorType::PrimaryKeyExist));
+    assert_eq!(count_rows("select * from enroll where student = \"s1\" and course = 1", &manager), 1);
+    assert_pattern!(run_plan("update enroll set course = 3 where student = \"s2\"", &manager), None);
+    assert_pattern!(run_plan("update enroll set student = \"s1\" where student = \"s2\"", &manager), None);
+    assert_eq!(count_rows("select * from enroll where student = \"s1\"", &manager), 3);
+    assert_eq!(count_rows("select * from enroll where student = \"s1\" and course = 3", &manager), 1);
+    assert_pattern!(run_plan("insert enroll values(\"s2\", 1, 2.5)", &manager), None);
+    manager.write().unwrap().save_to_file();
+
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_eq!(run_plan("insert enroll values(\"s1\", 3, 2.5)", &manager), Some(ExecErrorType::PrimaryKeyExist));
+    assert_pattern!(run_plan("insert enroll values(\"s3\", 3, 2.5)", &manager), None);
+    assert_eq!(count_rows("select * from enroll where course = 3", &manager), 2);
+}
@@ -1,77 +1,223 @@
-use std::ptr::write;
-use std::ptr::read;
-use libc::malloc;
-use ::parser::condition::{ArithExpr, ConditionExpr};
-use ::store::table::AttrType;
-use ::exec::evaluate::{
-    PtrMap,
-    eval_arith,
-    eval_cond,
-};
-use ::utils::pointer::{pointer_offset, write_string};
-
-
-#[test]
-fn test_eval_arith() {
-    {
-        let arith = gen_parse_result!(ArithExpr::parse, "1 + 2 * 3 - (-6)");
-        assert_eq!(eval_arith(&arith, &PtrMap::new()), 13.0);
-    }
-    {
-        let int_p = unsafe{ malloc(8) };
-        let float_p = pointer_offset(int_p, 4);
-        unsafe{
-            write::<i32>(int_p as *mut i32, 233);
-            write::<f32>(float_p as *mut f32, 666.666);
-        }
-        let mut ptr_map = PtrMap::new();
-        ptr_map.insert(("student".to_string(), "score".to_string()), (int_p, AttrType::Int));
-        ptr_map.insert(("teacher".to_string(), "score".to_string()), (float_p, AttrType::Float));
-        let arith = gen_parse_result!(ArithExpr::parse, "100 + teacher.score + student.score)");
-        assert_eq!(eval_arith(&arith, &ptr_map), 999.666);
-    }
-}
-
-#[test]
-fn test_eval_cond() {
-    {
-        let cond = gen_parse_result!(ConditionExpr::parse, "not 2 > 1");
-        assert_eq!(eval_cond(&cond, &PtrMap::new()), false);
-    }
-    {
-        let cond = gen_parse_result!(ConditionExpr::parse, "2 > 1 and 1 == 2 or 3 > 1 and 2 >= 2");
-        assert_eq!(eval_cond(&cond, &PtrMap::new()), true);
-    }
-    {
-        let cond = gen_parse_result!(ConditionExpr::parse, r#" "bb" != "bb" "#);
-        assert_eq!(eval_cond(&cond, &PtrMap::new()), false);
-    }
-    {
-        let int_p = unsafe{ malloc(8) };
-        let float_p = pointer_offset(int_p, 4);
-        unsafe{
-            write::<i32>(int_p as *mut i32, 233);
-            write::<f32>(float_p as *mut f32, 666.666);
-        }
-        let mut ptr_map = PtrMap::new();
-        ptr_map.insert(("student".to_string(), "score".to_string()), (int_p, AttrType::Int));
-        ptr_map.insert(("teacher".to_string(), "score".to_string()), (float_p, AttrType::Float));
-        let cond = gen_parse_result!(ConditionExpr::parse,
-            "student.score = 233 and 666.666 = teacher.score and teacher.score > student.score");
-        assert_eq!(eval_cond(&cond, &ptr_map), true);
-    }
-    {
-        let s = unsafe{ malloc(8) };
-        let f = pointer_offset(s, 4);
-        unsafe{
-            write_string(s, &"aa".to_string(), 4);
-            write::<f32>(f as *mut f32, 666.666);
-        }
-        let mut ptr_map = PtrMap::new();
-        ptr_map.insert(("student".to_string(), "name".to_string()), (s, AttrType::Char{len:4}));
-        ptr_map.insert(("teacher".to_string(), "score".to_string()), (f, AttrType::Float));
-        let cond = gen_parse_result!(ConditionExpr::parse,
-            "student.name = \"aa\" and \"aa\" = student.name and 666.666 = teacher.score");
-        assert_eq!(eval_cond(&cond, &ptr_map), true);
-    }
-}
+use std::ptr::{write, null_mut};
+use std::ptr::read;
+use libc::malloc;
+use ::parser::condition::{ArithExpr, ConditionExpr};
+use ::store::table::AttrType;
+use ::exec::error::ExecErrorType;
+use ::exec::evaluate::{
+    PtrMap,
+    Num,
+    eval_arith,
+    eval_cond,
+    Truth,
+};
+use ::utils::pointer::{pointer_offset, write_string};
+
+
+#[test]
+fn test_eval_arith() {
+    {
+        let arith = gen_parse_result!(ArithExpr::parse, "1 + 2 * 3 - (-6)");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Int(13)));
+    }
+    {
+        let int_p = unsafe{ malloc(8) };
+        let float_p = pointer_offset(int_p, 4);
+        unsafe{
+            write::<i32>(int_p as *mut i32, 233);
+            write::<f32>(float_p as *mut f32, 666.666);
+        }
+        let mut ptr_map = PtrMap::new();
+        ptr_map.insert(("student".to_string(), "score".to_string()), (int_p, AttrType::Int));
+        ptr_map.insert(("teacher".to_string(), "score".to_string()), (float_p, AttrType::Float));
+        let arith = gen_parse_result!(ArithExpr::parse, "100 + teacher.score + student.score)");
+        assert_eq!(eval_arith(&arith, &ptr_map).unwrap(), Some(Num::Float(999.666)));
+    }
+}
+
+#[test]
+fn test_eval_exact_arith() {
+    {
+        let arith = gen_parse_result!(ArithExpr::parse, "3000000000 * 2 + 1");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Int(6000000001)));
+    }
+    {
+        let cond = gen_parse_result!(ConditionExpr::parse, "0.1 + 0.2 = 0.3 and 1.5 * 2 = 3 and 7 % 4 = 3");
+        assert_eq!(eval_cond(&cond, &PtrMap::new()).unwrap(), Truth::True);
+    }
+    {
+        let arith = gen_parse_result!(ArithExpr::parse, "1 / 0");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), None);
+        let arith = gen_parse_result!(ArithExpr::parse, "1 / 4");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Decimal(250000, 6)));
+        // the quotient is rounded half away from zero
+        let arith = gen_parse_result!(ArithExpr::parse, "2 / 3.0");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Decimal(666667, 6)));
+        let arith = gen_parse_result!(ArithExpr::parse, "1 / 3.0000000");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Decimal(3333333, 7)));
+        let cond = gen_parse_result!(ConditionExpr::parse, "1 / 3 * 3 = 0.999999");
+        assert_eq!(eval_cond(&cond, &PtrMap::new()).unwrap(), Truth::True);
+    }
+    {
+        // the scale of the products is capped instead of overflowing
+        let arith = gen_parse_result!(ArithExpr::parse, "0.0000000001 * 0.0000000003");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Decimal(0, 18)));
+        let arith = gen_parse_result!(ArithExpr::parse, "0.000000001 * 0.000000003 * 0.1 * 0.1");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Decimal(0, 18)));
+        let arith = gen_parse_result!(ArithExpr::parse, "0.000000002 * 0.000000003 * 100");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Decimal(600, 18)));
+        let arith = gen_parse_result!(ArithExpr::parse, "1.000000000 * 1.000000000 * 1.000000000 * 3");
+        assert_eq!(eval_arith(&arith, &PtrMap::new()).unwrap(), Some(Num::Decimal(3 * 10i128.pow(18), 18)));
+    }
+    {
+        let big_p = unsafe{ malloc(24) };
+        let double_p = pointer_offset(big_p, 8);
+        let decimal_p = pointer_offset(big_p, 16);
+        unsafe{
+            write::<i64>(big_p as *mut i64, 1 << 40);
+            write::<f64>(double_p as *mut f64, 0.1);
+            write::<i64>(decimal_p as *mut i64, 1235);
+        }
+        let mut ptr_map = PtrMap::new();
+        ptr_map.insert(("t".to_string(), "big".to_string()), (big_p, AttrType::BigInt));
+        ptr_map.insert(("t".to_string(), "d".to_string()), (double_p, AttrType::Double));
+        ptr_map.insert(("t".to_string(), "m".to_string()), (decimal_p, AttrType::Decimal{precision:10, scale:2}));
+        let arith = gen_parse_result!(ArithExpr::parse, "t.big + 1");
+        assert_eq!(eval_arith(&arith, &ptr_map).unwrap(), Some(Num::Int((1 << 40) + 1)));
+        let arith = gen_parse_result!(ArithExpr::parse, "t.m + 0.005");
+        assert_eq!(eval_arith(&arith, &ptr_map).unwrap(), Some(Num::Decimal(12355, 3)));
+        let cond = gen_parse_result!(ConditionExpr::parse,
+            "t.m = 12.35 and t.d = 0.1 and t.d + 0.2 > 0.3 and t.big > 1099511627775");
+        assert_eq!(eval_cond(&cond, &ptr_map).unwrap(), Truth::True);
+    }
+}
+
+#[test]
+fn test_eval_cond() {
+    {
+        let cond = gen_parse_result!(ConditionExpr::parse, "not 2 > 1");
+        assert_eq!(eval_cond(&cond, &PtrMap::new()).unwrap(), Truth::False);
+    }
+    {
+        let cond = gen_parse_result!(ConditionExpr::parse, "2 > 1 and 1 == 2 or 3 > 1 and 2 >= 2");
+        assert_eq!(eval_cond(&cond, &PtrMap::new()).unwrap(), Truth::True);
+    }
+    {
+        let cond = gen_parse_result!(ConditionExpr::parse, r#" "bb" != "bb" "#);
+        assert_eq!(eval_cond(&cond, &PtrMap::new()).unwrap(), Truth::False);
+    }
+    {
+        let int_p = unsafe{ malloc(8) };
+        let float_p = pointer_offset(int_p, 4);
+        unsafe{
+            write::<i32>(int_p as *mut i32, 233);
+            write::<f32>(float_p as *mut f32, 666.666);
+        }
+        let mut ptr_map = PtrMap::new();
+        ptr_map.insert(("student".to_string(), "score".to_string()), (int_p, AttrType::Int));
+        ptr_map.insert(("teacher".to_string(), "score".to_string()), (float_p, AttrType::Float));
+        let cond = gen_parse_result!(ConditionExpr::parse,
+            "student.score = 233 and 666.666 = teacher.score and teacher.score > student.score");
+        assert_eq!(eval_cond(&cond, &ptr_map).unwrap(), Truth::True);
+    }
+    {
+        let s = unsafe{ malloc(8) };
+        let f = pointer_offset(s, 4);
+        unsafe{
+            write_string(s, &"aa".to_string(), 4);
+            write::<f32>(f as *mut f32, 666.666);
+        }
+        let mut ptr_map = PtrMap::new();
+        ptr_map.insert(("student".to_string(), "name".to_string()), (s, AttrType::Char{len:4}));
+        ptr_map.insert(("teacher".to_string(), "score".to_string()), (f, AttrType::Float));
+        let cond = gen_parse_result!(ConditionExpr::parse,
+            "student.name = \"aa\" and \"aa\" = student.name and 666.666 = teacher.score");
+        assert_eq!(eval_cond(&cond, &ptr_map).unwrap(), Truth::True);
+    }
+}
+
+#[test]
+fn test_eval_datetime() {
+    let p = unsafe{ malloc(16) };
+    let day_p = pointer_offset(p, 4);
+    let at_p = pointer_offset(p, 8);
+    unsafe{
+        write::<i32>(p as *mut i32, 1);
+        write::<i32>(day_p as *mut i32, 20455);  // 2026-01-02
+        write::<i64>(at_p as *mut i64, 20455 * 86400000000 + 3600000000);
+    }
+    let mut ptr_map = PtrMap::new();
+    ptr_map.insert(("event".to_string(), "done".to_string()), (p, AttrType::Boolean));
+    ptr_map.insert(("event".to_string(), "day".to_string()), (day_p, AttrType::Date));
+    ptr_map.insert(("event".to_string(), "at".to_string()), (at_p, AttrType::Timestamp));
+    ptr_map.insert(("event".to_string(), "end".to_string()), (null_mut(), AttrType::Timestamp));
+    let cases = vec![
+        ("event.done = true and false < event.done", Truth::True),
+        ("event.day = date '2026-01-02' and event.day < date '2026-01-03'", Truth::True),
+        ("event.day >= date '2026-01-03'", Truth::False),
+        ("event.at = timestamp '2026-01-02 01:00:00'", Truth::True),
+        ("timestamp '2026-01-02 00:59:59.999999' < event.at", Truth::True),
+        ("event.end > event.at", Truth::Unknown),
+        ("event.at != event.at", Truth::False),
+    ];
+    for (input, expected) in cases {
+        let cond = gen_parse_result!(ConditionExpr::parse, input);
+        assert_eq!(eval_cond(&cond, &ptr_map).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_eval_type_mismatch() {
+    // rejected by sem_check, but should fail the statement rather than panic
+    let p = unsafe{ malloc(8) };
+    unsafe{ write::<i32>(p as *mut i32, 20455) };
+    let name_p = pointer_offset(p, 4);
+    unsafe{ write_string(name_p, &"ab".to_string(), 4) };
+    let mut ptr_map = PtrMap::new();
+    ptr_map.insert(("event".to_string(), "day".to_string()), (p, AttrType::Date));
+    ptr_map.insert(("event".to_string(), "name".to_string()), (name_p, AttrType::Char{len:4}));
+    for input in vec!["event.day = true", "event.name = date '2026-01-02'", "event.name + 1 = 2"] {
+        let cond = gen_parse_result!(ConditionExpr::parse, input);
+        let err = extract!(eval_cond(&cond, &ptr_map), Err(err), err);
+        assert_eq!(err.error_type, ExecErrorType::TypeMismatch);
+    }
+}
+
+#[test]
+fn test_truth_table() {
+    assert_eq!(Truth::True.and(Truth::Unknown), Truth::Unknown);
+    assert_eq!(Truth::False.and(Truth::Unknown), Truth::False);
+    assert_eq!(Truth::True.or(Truth::Unknown), Truth::True);
+    assert_eq!(Truth::False.or(Truth::Unknown), Truth::Unknown);
+    assert_eq!(Truth::Unknown.not(), Truth::Unknown);
+    assert!(!Truth::Unknown.is_true());
+}
+
+#[test]
+fn test_eval_null() {
+    let p = unsafe{ malloc(4) };
+    unsafe{ write::<i32>(p as *mut i32, 233) };
+    let mut ptr_map = PtrMap::new();
+    ptr_map.insert(("student".to_string(), "id".to_string()), (p, AttrType::Int));
+    ptr_map.insert(("student".to_string(), "score".to_string()), (null_mut(), AttrType::Float));
+    ptr_map.insert(("student".to_string(), "name".to_string()), (null_mut(), AttrType::Char{len:4}));
+    {
+        let arith = gen_parse_result!(ArithExpr::parse, "student.id + student.score");
+        assert_eq!(eval_arith(&arith, &ptr_map).unwrap(), None);
+    }
+    let cases = vec![
+        ("student.score is null", Truth::True),
+        ("student.score is not null", Truth::False),
+        ("student.id is null", Truth::False),
+        ("student.score > 1", Truth::Unknown),
+        ("not student.score = 1", Truth::Unknown),
+        ("student.name = \"aa\"", Truth::Unknown),
+        ("student.score > 1 and student.id = 1", Truth::False),
+        ("student.score > 1 or student.id = 233", Truth::True),
+        ("student.score > 1 or student.id = 1", Truth::Unknown),
+    ];
+    for (input, expected) in cases {
+        let cond = gen_parse_result!(ConditionExpr::parse, input);
+        assert_eq!(eval_cond(&cond, &ptr_map).unwrap(), expected);
+    }
+}
@@ -0,0 +1,184 @@
+use std::sync::{Arc, RwLock};
+use ::utils::pointer::{read_varchar, read_bigint, read_double};
+use ::store::format::DEFAULT_PAGE_SIZE;
+use ::store::table::TableManager;
+use ::store::tuple::{TupleValue, gen_tuple_value};
+use ::store::overflow::{read_large_value, overflow_page_capacity};
+use ::store::fsck::check_database;
+use ::test::fixture::{gen_test_config, run_sql, count_rows};
+
+
+#[test]
+fn test_varchar() {
+    let config = gen_test_config("test_varchar", 4);
+    let table_name = "test_varchar_message".to_string();
+    let row_num = 200;
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        run_sql(&format!("create table {}(id int not null primary, content varchar(500))", table_name), &manager);
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        // the slot directory is sized for the shortest tuples
+        assert!(file.read().unwrap().get_page_slot_sum() > DEFAULT_PAGE_SIZE / 500);
+        for id in 0..row_num {
+            run_sql(&format!("insert {} values({}, \"c{}\")", table_name, id, id % 3), &manager);
+        }
+        assert_eq!(file.read().unwrap().page_sum, 1);
+        run_sql(&format!("create index content_index on {}(content)", table_name), &manager);
+        // growing tuples are moved to other pages
+        let long_content = "x".repeat(400);
+        run_sql(&format!("update {} set content = \"{}\" where id % 3 = 0", table_name, long_content), &manager);
+        assert!(file.read().unwrap().page_sum > 1);
+        assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), row_num);
+        let expected = (0..row_num).filter(|id| id % 3 == 0).count();
+        assert_eq!(count_rows(&format!("select * from {} where content = \"{}\"", table_name, long_content),
+            &manager), expected);
+        // shrinking tuples stay in place
+        run_sql(&format!("update {} set content = \"s\" where id < 30", table_name), &manager);
+        assert_eq!(count_rows(&format!("select * from {} where content = \"s\"", table_name), &manager), 30);
+        run_sql(&format!("delete from {} where id % 3 = 1", table_name), &manager);
+        manager.write().unwrap().save_to_file();
+    }
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    let expected = (0..row_num).filter(|id| id % 3 != 1).count();
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), expected);
+    let mut query = gen_plan_helper!(&format!("select * from {} where id = 150", table_name), &manager);
+    query.open();
+    let t = extract!(query.get_next(), Some(tuple_data), tuple_data);
+    assert_eq!(unsafe{ read_varchar(t[1]) }, "x".repeat(400));
+    assert_pattern!(query.get_next(), None);
+}
+
+#[test]
+fn test_overflow() {
+    let config = gen_test_config("test_overflow", 4);
+    let meta_dir = config.get_str("table_meta_dir");
+    let file_dir = config.get_str("table_file_dir");
+    let table_name = "test_overflow_message".to_string();
+    let row_num = 20;
+    let long_len = 9000;
+    let chain_len = (long_len + overflow_page_capacity(DEFAULT_PAGE_SIZE) - 1)
+        / overflow_page_capacity(DEFAULT_PAGE_SIZE);
+    let read_value = |manager : &Arc<RwLock<TableManager>>, id : usize, attr : usize| {
+        let mut query = gen_plan_helper!(&format!("select * from {} where id = {}", table_name, id), manager);
+        query.open();
+        let t = extract!(query.get_next(), Some(tuple_data), tuple_data);
+        let value = unsafe{ read_large_value(t[attr]) };
+        let value = manager.write().unwrap().file_manager.load_overflow_value(&table_name, value).unwrap();
+        query.close();
+        extract!(value, TupleValue::Bytes(bytes), String::from_utf8(bytes).unwrap())
+    };
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        run_sql(&format!("create table {}(id int not null primary, body text, data blob)", table_name), &manager);
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        for id in 0..row_num {
+            // the long values are saved in overflow pages
+            let body = if id % 2 == 0 { "y".repeat(long_len + id) } else { format!("b{}", id) };
+            run_sql(&format!("insert {} values({}, \"{}\", null)", table_name, id, body), &manager);
+        }
+        assert_eq!(file.read().unwrap().page_sum, 1 + chain_len * row_num / 2);
+        assert_eq!(read_value(&manager, 4, 1), "y".repeat(long_len + 4));
+        assert_eq!(read_value(&manager, 3, 1), "b3");
+        // the overflow pages of the replaced and deleted values become free
+        run_sql(&format!("update {} set body = \"s\" where id = 2", table_name), &manager);
+        assert_eq!(read_value(&manager, 2, 1), "s");
+        run_sql(&format!("delete from {} where id % 2 = 0", table_name), &manager);
+        run_sql("vacuum", &manager);
+        assert_eq!(file.read().unwrap().page_sum, 1);
+        run_sql(&format!("update {} set data = \"{}\" where id = 1", table_name, "z".repeat(long_len)), &manager);
+        assert_eq!(file.read().unwrap().page_sum, 1 + chain_len);
+        manager.write().unwrap().save_to_file();
+    }
+    let report = check_database(&meta_dir, &file_dir);
+    assert!(report.is_ok(), "{}", report.desc());
+    assert!(report.desc().contains(&format!("  {} overflow pages\n", chain_len)));
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), row_num / 2);
+    assert_eq!(read_value(&manager, 1, 2), "z".repeat(long_len));
+    assert_eq!(read_value(&manager, 1, 1), "b1");
+    assert_eq!(count_rows(&format!("select * from {} where data is null", table_name), &manager), row_num / 2 - 1);
+}
+
+#[test]
+fn test_numeric_types() {
+    let config = gen_test_config("test_numeric_types", 4);
+    let table_name = "test_numeric_account".to_string();
+    let read_row = |manager : &Arc<RwLock<TableManager>>, id : i64| {
+        let mut query = gen_plan_helper!(&format!("select * from {} where id = {}", table_name, id), manager);
+        query.open();
+        let t = extract!(query.get_next(), Some(tuple_data), tuple_data);
+        let row = unsafe{ (read_bigint(t[0]), read_double(t[1]), read_bigint(t[2])) };
+        assert_pattern!(query.get_next(), None);
+        query.close();
+        row
+    };
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        run_sql(&format!("create table {}(id bigint not null primary, rate double, balance decimal(10, 2))",
+            table_name), &manager);
+        run_sql(&format!("insert {} values(3000000000, 0.1, 12.345)", table_name), &manager);
+        run_sql(&format!("insert {} values(5000000000, 123456789012.5, 0.005)", table_name), &manager);
+        run_sql(&format!("insert {} values(1, 2, 3)", table_name), &manager);
+        // decimals are rounded to their scale
+        assert_eq!(read_row(&manager, 3000000000), (3000000000, 0.1, 1235));
+        assert_eq!(read_row(&manager, 5000000000), (5000000000, 123456789012.5, 1));
+        assert_eq!(read_row(&manager, 1), (1, 2.0, 300));
+        run_sql(&format!("create index {}_balance on {}(balance)", table_name, table_name), &manager);
+        run_sql(&format!("update {} set balance = 99999999.99 where id = 1", table_name), &manager);
+        manager.write().unwrap().save_to_file();
+    }
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    let count = |cond : &str| count_rows(&format!("select * from {} where {}", table_name, cond), &manager);
+    assert_eq!(count("balance = 12.35"), 1);
+    assert_eq!(count("balance = 99999999.99"), 1);
+    assert_eq!(count("balance + 0.01 = 100000000"), 1);
+    assert_eq!(count("id > 2147483647"), 2);
+    assert_eq!(count("id * 2 = 6000000000"), 1);
+    assert_eq!(count("rate + 0.2 < 1"), 1);
+    assert_eq!(count("rate > 123456789012.25"), 1);
+}
+
+#[test]
+fn test_datetime_types() {
+    let config = gen_test_config("test_datetime_types", 4);
+    let table_name = "test_datetime_event".to_string();
+    let read_row = |manager : &Arc<RwLock<TableManager>>, id : i32| {
+        let mut query = gen_plan_helper!(&format!("select * from {} where id = {}", table_name, id), manager);
+        query.open();
+        let t = extract!(query.get_next(), Some(tuple_data), tuple_data);
+        let attr_desc = manager.read().unwrap().get_table(&table_name).unwrap().read().unwrap()
+            .gen_tuple_desc().attr_desc;
+        let row = format!("{:?}", gen_tuple_value(&attr_desc, t));
+        query.close();
+        row
+    };
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        run_sql(&format!("create table {}(id int not null primary, done boolean, day date, at time, \
+            created timestamp not null)", table_name), &manager);
+        run_sql(&format!("create index {}_created on {}(created)", table_name, table_name), &manager);
+        run_sql(&format!("insert {} values(1, true, date '1969-12-31', time '08:30:00', \
+            timestamp '2026-01-02 03:04:05.5')", table_name), &manager);
+        run_sql(&format!("insert {} values(2, false, date '2024-02-29', null, timestamp '1970-01-01')",
+            table_name), &manager);
+        run_sql(&format!("insert {} values(3, null, null, time '23:59:59.999999', \
+            timestamp '2026-01-02 03:04:05.5')", table_name), &manager);
+        assert_eq!(read_row(&manager, 1), format!("[Int(1), Boolean(true), Date(-1), Time({}), Timestamp({})]",
+            (8 * 60 + 30) * 60 * 1000000i64, 20455 * 86400000000i64 + (3 * 3600 + 4 * 60 + 5) * 1000000 + 500000));
+        assert_eq!(read_row(&manager, 2), "[Int(2), Boolean(false), Date(19782), Null, Timestamp(0)]");
+        run_sql(&format!("update {} set done = true, day = date '2026-01-02' where id = 3", table_name), &manager);
+        run_sql(&format!("alter table {} add column due date default date '2026-12-31'", table_name), &manager);
+        manager.write().unwrap().save_to_file();
+    }
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    let count = |cond : &str| count_rows(&format!("select * from {} where {}", table_name, cond), &manager);
+    assert_eq!(count("done = true"), 2);
+    assert_eq!(count("done != true"), 1);
+    assert_eq!(count("day < date '2000-01-01'"), 1);
+    assert_eq!(count("day = date '2026-01-02'"), 1);
+    assert_eq!(count("at > time '12:00:00'"), 1);
+    assert_eq!(count("at is null"), 1);
+    assert_eq!(count("created = timestamp '2026-01-02 03:04:05.5'"), 2);
+    assert_eq!(count("created < timestamp '2026-01-02 03:04:05.499999'"), 1);
+    assert_eq!(count("due = date '2026-12-31' and due > day"), 3);
+}
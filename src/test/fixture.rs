@@ -0,0 +1,47 @@
+use std::fs::remove_dir_all;
+use ::utils::config::Config;
+use ::store::table::TableManagerRef;
+use ::exec::error::ExecErrorType;
+
+
+// every test gets its own directories so that tests can run in parallel,
+// the files left by the last run of the test are removed
+pub fn clean_test_dirs(test_name : &str) -> (String, String) {
+    let meta_dir = format!("test_file/table_meta/{}/", test_name);
+    let file_dir = format!("test_file/table_file/{}/", test_name);
+    let _ = remove_dir_all(&meta_dir);
+    let _ = remove_dir_all(&file_dir);
+    (meta_dir, file_dir)
+}
+
+pub fn gen_test_config(test_name : &str, page_num : usize) -> Config {
+    let (meta_dir, file_dir) = clean_test_dirs(test_name);
+    Config::new(&format!(r#"
+        max_memory_pool_page_num = {}
+        table_meta_dir = "{}"
+        table_file_dir = "{}""#, page_num, meta_dir, file_dir))
+}
+
+pub fn run_plan(sql : &str, manager : &TableManagerRef) -> Option<ExecErrorType> {
+    let mut plan = gen_plan_helper!(sql, manager);
+    plan.open();
+    while let Some(..) = plan.get_next() {}
+    plan.close();
+    plan.get_error().map(|e| e.error_type)
+}
+
+pub fn run_sql(sql : &str, manager : &TableManagerRef) {
+    assert_pattern!(run_plan(sql, manager), None);
+}
+
+pub fn count_rows(sql : &str, manager : &TableManagerRef) -> usize {
+    let mut plan = gen_plan_helper!(sql, manager);
+    plan.open();
+    let mut count = 0;
+    while let Some(..) = plan.get_next() {
+        count += 1;
+    }
+    plan.close();
+    assert_pattern!(plan.get_error(), None);
+    count
+}
@@ -1,8 +1,10 @@
-#[macro_use]
-#[allow(dead_code)]  // lint bug
-#[allow(unused_imports)]
-mod utils;
-mod parser;
-mod store;
-mod exec;
-mod server;
+#[macro_use]
+#[allow(dead_code)]  // lint bug
+#[allow(unused_imports)]
+mod utils;
+#[allow(dead_code)]
+mod fixture;
+mod parser;
+mod store;
+mod exec;
+mod server;
@@ -1,26 +1,26 @@
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_common;
-
-#[allow(unused_imports)]  // lint bug
-mod test_lexer;
-
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_condition;
-
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_attribute;
-
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_statement;
-
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_sem_check;
-
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_unimpl;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_common;
+
+#[allow(unused_imports)]  // lint bug
+mod test_lexer;
+
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_condition;
+
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_attribute;
+
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_statement;
+
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_sem_check;
+
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_unimpl;
@@ -1,6 +1,6 @@
-#[allow(dead_code)]
-#[allow(unused_imports)]
-mod test_handler;
-#[allow(dead_code)]
-#[allow(unused_imports)]
-mod test_buf;
+#[allow(dead_code)]
+#[allow(unused_imports)]
+mod test_handler;
+#[allow(dead_code)]
+#[allow(unused_imports)]
+mod test_buf;
@@ -1,45 +1,142 @@
-use ::server::handler::{sql_handler, ResultHandler};
-use ::store::tuple::TupleData;
-use ::store::table::{TableManager, AttrType};
-use ::utils::config::Config;
-
-
-#[derive(Debug)]
-struct MockHandler {
-    pub helper_data : String,
-}
-
-impl MockHandler {
-    pub fn new() -> MockHandler {
-        // Box::new(MockHandler{ helper_data : String::new() })
-        MockHandler{ helper_data : String::new() }
-    }
-}
-
-impl ResultHandler for MockHandler {
-    fn handle_error(&mut self, err_msg : String) {
-        self.helper_data = err_msg
-    }
-    fn handle_tuple_data(&mut self, tuple_data : Option<TupleData>) {
-        match tuple_data {
-            Some(..) => self.helper_data.push('1'),
-            None => self.helper_data.push('0'),
-        }
-    }
-    fn set_tuple_info(&mut self, _attr_desc : Vec<AttrType>, _attr_index : Vec<usize>) {}
-    fn handle_non_query_finished(&mut self) {}
-}
-
-
-#[test]
-fn test_handler() {
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 2
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let manager = TableManager::make_ref(&config);
-    let mut handler = MockHandler::new();
-    let sql = "create table msg(id int not null primary)".to_string();
-    sql_handler(&sql, &mut handler, &manager);
-    assert_eq!(handler.helper_data, "");
-}
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::sync::{Arc, RwLock};
+use std::thread::spawn;
+use ::server::handler::{sql_handler, ResultHandler, LargeValueReader, process_table_command};
+use ::store::tuple::TupleData;
+use ::store::table::{TableManager, AttrType};
+use ::store::format::DEFAULT_PAGE_SIZE;
+use ::utils::config::Config;
+use ::test::fixture::gen_test_config;
+
+
+#[derive(Debug)]
+struct MockHandler {
+    pub helper_data : String,
+}
+
+impl MockHandler {
+    pub fn new() -> MockHandler {
+        // Box::new(MockHandler{ helper_data : String::new() })
+        MockHandler{ helper_data : String::new() }
+    }
+}
+
+impl ResultHandler for MockHandler {
+    fn handle_error(&mut self, err_msg : String) {
+        self.helper_data = err_msg
+    }
+    fn handle_tuple_data(&mut self, tuple_data : Option<TupleData>) {
+        match tuple_data {
+            Some(..) => self.helper_data.push('1'),
+            None => self.helper_data.push('0'),
+        }
+    }
+    fn set_tuple_info(&mut self, _attr_desc : Vec<AttrType>, _attr_index : Vec<usize>,
+        _reader : LargeValueReader) {}
+    fn handle_non_query_finished(&mut self) {}
+}
+
+
+#[test]
+fn test_handler() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 2
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let manager = TableManager::make_ref(&config);
+    let mut handler = MockHandler::new();
+    let sql = "create table msg(id int not null primary)".to_string();
+    sql_handler(&sql, &mut handler, &manager);
+    assert_eq!(handler.helper_data, "");
+}
+
+#[test]
+fn test_table_command() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 2
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let manager = TableManager::make_ref(&config);
+    let status = extract!(process_table_command(&"show buffer status".to_string(), &manager), Ok(s), s);
+    assert!(status.starts_with("buffer: capacity 2"));
+    assert_pattern!(process_table_command(&"show buffer".to_string(), &manager), Err(..));
+}
+
+#[test]
+fn test_concurrent_statements() {
+    let config = gen_test_config("test_concurrent_statements", 8);
+    let manager = TableManager::make_ref(&config);
+    let mut handler = MockHandler::new();
+    for sql in ["create table written(id int not null primary)",
+            "create table scanned(id int not null primary)"].iter() {
+        sql_handler(&sql.to_string(), &mut handler, &manager);
+    }
+    for id in 0..20 {
+        sql_handler(&format!("insert scanned values({})", id), &mut handler, &manager);
+    }
+    assert_eq!(handler.helper_data, "");
+    let workers : Vec<_> = (0..6).map(|i| {
+        let manager = manager.clone();
+        spawn(move || {
+            let mut handler = MockHandler::new();
+            for j in 0..10 {
+                if i % 2 == 0 {
+                    let sql = format!("insert written values({})", i * 100 + j);
+                    sql_handler(&sql, &mut handler, &manager);
+                    assert_eq!(handler.helper_data, "");
+                } else {
+                    handler.helper_data.clear();
+                    sql_handler(&"select * from scanned".to_string(), &mut handler, &manager);
+                    assert_eq!(handler.helper_data.len(), 21);
+                }
+            }
+        })
+    }).collect();
+    for worker in workers {
+        assert!(worker.join().is_ok());
+    }
+    sql_handler(&"select * from written".to_string(), &mut handler, &manager);
+    assert_eq!(handler.helper_data, format!("{}0", "1".repeat(30)));
+    let m = manager.read().unwrap();
+    assert_eq!(m.file_manager.get_unpinned_num(), 8);
+    assert_eq!(m.lock_manager.get_reader_num(&"scanned".to_string()), 0);
+    assert!(!m.lock_manager.is_writing());
+}
+
+#[test]
+fn test_corrupted_page() {
+    let config = gen_test_config("test_corrupted_page", 4);
+    let file_dir = config.get_str("table_file_dir");
+    {
+        let manager = TableManager::make_ref(&config);
+        let mut handler = MockHandler::new();
+        sql_handler(&"create table corrupted(id int not null primary, content char(1000))".to_string(),
+            &mut handler, &manager);
+        for id in 0..6 {
+            // 4 tuples in a page
+            sql_handler(&format!("insert corrupted values({}, \"a\")", id), &mut handler, &manager);
+        }
+        assert_eq!(handler.helper_data, "");
+    }
+    // flip a bit in the second page
+    let path = format!("{}corrupted.table", file_dir);
+    let mut data = Vec::new();
+    check_ok!(check_ok!(OpenOptions::new().read(true).open(&path)).read_to_end(&mut data));
+    data[2 * DEFAULT_PAGE_SIZE + 100] ^= 1;
+    check_ok!(check_ok!(OpenOptions::new().write(true).open(&path)).write_all(&data));
+
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    let mut handler = MockHandler::new();
+    sql_handler(&"select * from corrupted where id = 1".to_string(), &mut handler, &manager);
+    assert_eq!(handler.helper_data, "10");
+    let error = "PageCorrupted: page 1 of table corrupted is corrupted: checksum mismatch";
+    let mut handler = MockHandler::new();
+    sql_handler(&"select * from corrupted".to_string(), &mut handler, &manager);
+    assert_eq!(handler.helper_data, error);
+    // the free slots are in the corrupted page
+    let mut handler = MockHandler::new();
+    sql_handler(&"insert corrupted values(6, \"a\")".to_string(), &mut handler, &manager);
+    assert_eq!(handler.helper_data, error);
+    assert_eq!(manager.read().unwrap().file_manager.get_unpinned_num(), 4);
+}
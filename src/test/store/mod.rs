@@ -1,12 +1,30 @@
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_lru;
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-pub mod test_buffer;
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_table;
-#[allow(dead_code)]
-#[allow(unused_imports)]  // lint bug
-mod test_file;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_lru;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_policy;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+pub mod test_buffer;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_table;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_file;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_wal;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_index;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_lock;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_format;
+#[allow(dead_code)]
+#[allow(unused_imports)]  // lint bug
+mod test_fsck;
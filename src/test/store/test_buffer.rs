@@ -1,48 +1,97 @@
-use std::boxed::Box;
-use std::option::Option::{Some, None};
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::ptr::{write, read, null_mut};
-use ::store::buffer::{Page, PagePool, DataPtr};
-
-
-#[test]
-fn test_page_pool() {
-    let mut pool = PagePool::new(1);
-    let (mut fd, mut page_index) = (11, 12);
-    assert_pattern!(pool.get_page(fd, page_index), None);
-    let ptr;
-    pool.put_page(fd, page_index, null_mut());
-    {
-        let page1 = pool.get_page(fd, page_index).unwrap();
-        let mut p1 = page1.borrow_mut();
-        assert_eq!(p1.fd, fd);
-        assert_eq!(p1.page_index, page_index);
-        assert!(!p1.data.is_null());
-        ptr = p1.data;
-        unsafe{write::<i32>(p1.data as *mut i32, 666);}
-        p1.data = null_mut();
-    }
-    pool.remove_tail();
-    pool.put_page(21, 22, ptr);
-    fd = 21;
-    page_index = 22;
-    {
-        let page2 = pool.get_page(fd, page_index).unwrap();
-        let p2 = page2.borrow();
-        assert_eq!(p2.fd, fd);
-        assert_eq!(p2.page_index, page_index);
-        assert!(!p2.data.is_null());
-        assert_eq!(p2.data, ptr);
-        let n = unsafe{read::<i32>(p2.data as *const i32)};
-        assert_eq!(n, 666);
-    }
-}
-
-#[test]
-#[should_panic]
-fn test_crash() {
-    let mut pool = PagePool::new(1);
-    pool.put_page(11, 12, null_mut());
-    pool.put_page(11, 13, null_mut());
-}
+use std::boxed::Box;
+use std::option::Option::{Some, None};
+use std::sync::{Arc, RwLock};
+use std::ptr::{write, read, null_mut};
+use ::store::buffer::{Page, PagePool, DataPtr};
+use ::store::format::DEFAULT_PAGE_SIZE;
+
+
+#[test]
+fn test_page_pool() {
+    let mut pool = PagePool::new(1);
+    let (mut fd, mut page_index) = (11, 12);
+    assert_pattern!(pool.get_page(fd, page_index), None);
+    let ptr;
+    pool.put_page(fd, page_index, null_mut());
+    {
+        let page1 = pool.get_page(fd, page_index).unwrap();
+        let mut p1 = page1.write().unwrap();
+        assert_eq!(p1.fd, fd);
+        assert_eq!(p1.page_index, page_index);
+        assert!(!p1.data.is_null());
+        ptr = p1.data;
+        unsafe{write::<i32>(p1.data as *mut i32, 666);}
+        p1.data = null_mut();
+    }
+    pool.remove_victim();
+    pool.put_page(21, 22, ptr);
+    fd = 21;
+    page_index = 22;
+    {
+        let page2 = pool.get_page(fd, page_index).unwrap();
+        let p2 = page2.read().unwrap();
+        assert_eq!(p2.fd, fd);
+        assert_eq!(p2.page_index, page_index);
+        assert!(!p2.data.is_null());
+        assert_eq!(p2.data, ptr);
+        let n = unsafe{read::<i32>(p2.data as *const i32)};
+        assert_eq!(n, 666);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_crash() {
+    let mut pool = PagePool::new(1);
+    pool.put_page(11, 12, null_mut());
+    pool.put_page(11, 13, null_mut());
+}
+
+#[test]
+fn test_page_pool_policy() {
+    for policy in ["lru", "clock", "lru-k", "2q"].iter() {
+        let mut pool = PagePool::with_policy(policy, 2, DEFAULT_PAGE_SIZE);
+        assert_eq!(pool.get_capacity(), 2);
+        pool.put_page(11, 0, null_mut());
+        pool.put_page(11, 1, null_mut());
+        pool.pin_page(11, 0).unwrap();
+        let page = pool.prepare_page().unwrap().unwrap();
+        assert_eq!(page.read().unwrap().page_index, 1);
+        pool.remove_victim();
+        pool.put_page(12, 1, null_mut());
+        assert_pattern!(pool.get_page(11, 1), None);
+        assert_pattern!(pool.get_page(12, 1), Some(..));
+        pool.unpin_page(11, 0);
+        assert_pattern!(pool.remove_page(11, 0), Some(..));
+        assert_pattern!(pool.prepare_page(), Ok(None));
+    }
+}
+
+#[test]
+fn test_pin_count() {
+    let pool = PagePool::new(2);
+    pool.put_page(11, 0, null_mut());
+    pool.put_page(11, 1, null_mut());
+    pool.pin_page(11, 0).unwrap();
+    pool.pin_page(11, 0).unwrap();
+    assert_eq!(pool.get_unpinned_num(), 1);
+    pool.unpin_page(11, 0);
+    assert!(pool.get_page(11, 0).unwrap().read().unwrap().is_pinned());
+    assert_eq!(pool.prepare_page().unwrap().unwrap().read().unwrap().page_index, 1);
+    pool.unpin_page(11, 0);
+    assert_eq!(pool.get_page(11, 0).unwrap().read().unwrap().get_pin_count(), 0);
+    assert_eq!(pool.get_unpinned_num(), 2);
+}
+
+#[test]
+fn test_pin_last_page() {
+    // the last unpinned page is kept for the index pages
+    let pool = PagePool::new(2);
+    pool.put_page(11, 0, null_mut());
+    pool.put_page(11, 1, null_mut());
+    pool.pin_page(11, 0).unwrap();
+    assert_pattern!(pool.pin_page(11, 1), Err(..));
+    assert!(!pool.get_page(11, 1).unwrap().read().unwrap().is_pinned());
+    assert_eq!(pool.get_unpinned_num(), 1);
+    assert_eq!(pool.prepare_page().unwrap().unwrap().read().unwrap().page_index, 1);
+}
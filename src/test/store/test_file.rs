@@ -1,320 +1,746 @@
-use std::ptr::{read, write};
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::sync::{Arc, RwLock};
-use libc::malloc;
-use ::utils::pointer::{read_string, write_string, pointer_offset};
-use ::utils::config::Config;
-use ::store::file::{TableFile, FilePage, BitMap, PageHeader, TableFileManager};
-use ::store::buffer::{DataPtr, Page};
-use ::store::table::{Table, Attr, AttrType, TableManager};
-use ::parser::common::{ValueExpr, ValueType};
-use ::store::tuple::TupleValue;
-use ::test::exec::test_query;
-use ::test::exec::test_query::gen_test_manager;
-
-
-#[test]
-fn test_page_header() {
-    {
-        let data;
-        unsafe{
-            data = malloc(8);
-            write::<u32>(data as *mut u32, 233);
-            write::<u32>((data as *mut u32).offset(1), 666);
-        }
-        let mut header = PageHeader{
-            slot_sum : 233,
-            first_free_slot : 0,
-            data : data,
-        };
-        header.init_from_page_data();
-        assert_eq!(header.slot_sum, 233);
-        assert_eq!(header.first_free_slot, 666);
-    }
-    {
-        let data;
-        unsafe{
-            data = malloc(8);
-            write::<u32>(data as *mut u32, 111);
-            write::<u32>((data as *mut u32).offset(1), 222);
-        }
-        let mut header = PageHeader{
-            slot_sum : 233,
-            first_free_slot : 666,
-            data : data,
-        };
-        header.save_to_page_data();
-        assert_eq!(unsafe{read::<u32>(data as *const u32)}, 233);
-        assert_eq!(unsafe{read::<u32>((data as *const u32).offset(1))}, 666);
-    }
-}
-
-#[test]
-fn test_bitmap()
-{
-    let data = unsafe{ malloc(3) };
-    let mut bitmap = BitMap{
-        data : data,
-        slot_sum : 24,
-    };
-    bitmap.clean();
-    for i in 0..24 {
-        assert!(!bitmap.is_inuse(i));
-    }
-    bitmap.set_inuse(8, true);
-    assert!(!bitmap.is_inuse(7));
-    assert!(bitmap.is_inuse(8));
-    assert!(!bitmap.is_inuse(9));
-    assert_eq!(bitmap.next_tuple_index(0), 8);
-    bitmap.set_inuse(8 + 7, true);
-    assert!(!bitmap.is_inuse(8 + 6));
-    assert!(bitmap.is_inuse(8 + 7));
-    assert!(!bitmap.is_inuse(8 + 8));
-    assert_eq!(bitmap.next_tuple_index(0), 8);
-    assert_eq!(bitmap.next_tuple_index(8), 8);
-    assert_eq!(bitmap.next_tuple_index(8 + 1), 8 + 7);
-    bitmap.set_inuse(8 + 3, true);
-    assert!(!bitmap.is_inuse(8 + 2));
-    assert!(bitmap.is_inuse(8 + 3));
-    assert!(!bitmap.is_inuse(8 + 4));
-    unsafe{
-        assert_eq!(read::<u8>(data as *const u8), 0);
-        assert_eq!(read::<u8>((data as *const u8).offset(1)), 137);
-        assert_eq!(read::<u8>((data as *const u8).offset(2)), 0);
-    }
-    assert_eq!(bitmap.get_first_free_slot(), 0);
-    bitmap.set_inuse(0, true);
-    assert_eq!(bitmap.get_first_free_slot(), 1);
-    for i in 1..8 {
-        bitmap.set_inuse(i, true);
-    }
-    assert_eq!(bitmap.next_tuple_index(8), 8);
-    assert_eq!(bitmap.get_first_free_slot(), 8 + 1);
-    bitmap.set_inuse(3, false);
-    assert_eq!(bitmap.get_first_free_slot(), 3);
-    assert_eq!(bitmap.next_tuple_index(50), bitmap.slot_sum);
-}
-
-fn gen_test_table() -> Table {
-    Table{
-        name : "test_file_message".to_string(),
-        attr_list : vec![
-            Attr{
-                name : "id".to_string(),
-                attr_type : AttrType::Int,
-                primary : true,
-                nullable : false,
-            },
-            Attr{
-                name : "content".to_string(),
-                attr_type : AttrType::Char{ len : 6 },
-                primary : false,
-                nullable : false,
-            },
-            Attr{
-                name : "score".to_string(),
-                attr_type : AttrType::Float,
-                primary : false,
-                nullable : true,
-            },
-        ],
-    }
-}
-
-#[test]
-fn test_file_page_insert() {
-    let table = gen_test_table();
-    let tuple_desc = table.gen_tuple_desc();
-    assert_eq!(tuple_desc.tuple_len, 16);
-    let mut mem_page = Page::new(1, 2);
-    mem_page.alloc();
-    let page = Rc::new(RefCell::new(mem_page));
-    let mut file_page = FilePage::new(page, tuple_desc.tuple_len);
-    file_page.init_empty_page();
-    let mut value_list = vec![
-        ValueExpr{ value : "233".to_string(), value_type : ValueType::Integer },
-        ValueExpr{ value : "abcdef".to_string(), value_type : ValueType::String },
-        ValueExpr{ value : "666.666".to_string(), value_type : ValueType::Float },
-    ];
-    assert_eq!(file_page.header.first_free_slot, 0);
-    assert_eq!(file_page.is_inuse(0), false);
-    file_page.insert(&value_list, &tuple_desc);
-    assert_eq!(file_page.header.first_free_slot, 1);
-    assert_eq!(file_page.is_inuse(0), true);
-    value_list[0].value = "777".to_string();
-    value_list[1].value = "dyb".to_string();
-    value_list[2].value = "12345.777".to_string();
-    file_page.insert(&value_list, &tuple_desc);
-    assert_eq!(file_page.header.first_free_slot, 2);
-    assert_eq!(file_page.is_inuse(1), true);
-
-    file_page.save_to_page();
-    let mut p = file_page.mem_page.borrow().data;
-    assert_eq!(unsafe{ read(p as *const u32) }, 253);  // slot_sum
-    p = pointer_offset(p, 4);
-    assert_eq!(unsafe{ read(p as *const u32) }, 2);  // first_free_slot
-    p = pointer_offset(p, 4);
-    assert_eq!(unsafe{ read(p as *const u8) }, 3);  // bitmap
-    assert_eq!(unsafe{ read(pointer_offset(p, 4) as *const u8) }, 0);  // bitmap
-    p = pointer_offset(p, (253 + 7) / 8);
-    // first tuple
-    assert_eq!(unsafe{ read(p as *const u32) }, 233);  // tuple data: id
-    p = pointer_offset(p, 4);
-    assert_eq!(unsafe{ read_string(p, 6) }, "abcdef");  // tuple data: content
-    p = pointer_offset(p, 8);
-    assert_eq!(unsafe{ read(p as *const f32) }, 666.666);  // tuple data: score
-    p = pointer_offset(p, 4);
-    // second tuple
-    assert_eq!(unsafe{ read(p as *const u32) }, 777);  // tuple data: id
-    p = pointer_offset(p, 4);
-    assert_eq!(unsafe{ read_string(p, 6) }, "dyb");  // tuple data: content
-    p = pointer_offset(p, 8);
-    assert_eq!(unsafe{ read(p as *const f32) }, 12345.777);  // tuple data: score
-}
-
-#[test]
-fn test_file_insert() {
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 2
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let mut manager = TableFileManager::new(&config);
-    let table = Rc::new(RefCell::new(gen_test_table()));
-    let table_name = "test_file_message".to_string();
-    manager.create_file(table_name.clone(), table);
-    let mut value_list = vec![
-        ValueExpr{ value : "233".to_string(), value_type : ValueType::Integer },
-        ValueExpr{ value : "abcdef".to_string(), value_type : ValueType::String },
-        ValueExpr{ value : "666.666".to_string(), value_type : ValueType::Float },
-    ];
-    manager.insert(&table_name, &value_list);
-    assert_pattern!(manager.get_tuple_value(&table_name, 0, 0), TupleValue::Int(233));
-    assert_pattern!(manager.get_tuple_value(&table_name, 0, 2), TupleValue::Float(666.666));
-    assert_eq!(extract!(
-        manager.get_tuple_value(&table_name, 0, 1), TupleValue::Char(s), s), "abcdef");
-
-    value_list[0].value = "777".to_string();
-    value_list[1].value = "dyb".to_string();
-    value_list[2].value = "12345.777".to_string();
-    manager.insert(&table_name, &value_list);
-    assert_pattern!(manager.get_tuple_value(&table_name, 1, 0), TupleValue::Int(777));
-    assert_pattern!(manager.get_tuple_value(&table_name, 1, 2), TupleValue::Float(12345.777));
-    assert_eq!(extract!(
-        manager.get_tuple_value(&table_name, 1, 1), TupleValue::Char(s), s), "dyb");
-}
-
-#[test]
-fn test_get_tuple_data() {
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 2
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let mut manager = TableFileManager::new(&config);
-    let table = Rc::new(RefCell::new(gen_test_table()));
-    let table_name = "test_file_message".to_string();
-    manager.create_file(table_name.clone(), table);
-    let value_list = vec![
-        ValueExpr{ value : "233".to_string(), value_type : ValueType::Integer },
-        ValueExpr{ value : "abcdef".to_string(), value_type : ValueType::String },
-        ValueExpr{ value : "666.666".to_string(), value_type : ValueType::Float },
-    ];
-    manager.insert(&table_name, &value_list);
-    let tuple_data = manager.get_tuple_data(&table_name, 0).unwrap();
-    let p1 = tuple_data[0];
-    let p2 = tuple_data[1];
-    let p3 = tuple_data[2];
-    assert_eq!(unsafe{ read::<i32>(p1 as *const i32) }, 233);
-    assert_eq!(unsafe{ read_string(p2, 6) }, "abcdef");
-    assert_eq!(unsafe{ read::<f32>(p3 as *const f32) }, 666.666);
-}
-
-#[test]
-fn test_file_persistence() {
-    {
-        let config = Config::new(&r#"
-            max_memory_pool_page_num = 2
-            table_meta_dir = "test_file/table_meta/test_file_persistence/"
-            table_file_dir = "test_file/table_file""#.to_string());
-        let table_name = "test_file_persistence_message".to_string();
-        {
-            let manager = Rc::new(RefCell::new(TableManager::new(&config)));
-            manager.borrow_mut().add_table(test_query::gen_test_table(&table_name));
-            test_query::insert_data(&table_name, &manager);
-            manager.borrow_mut().save_to_file();
-        }
-        let manager = Rc::new(RefCell::new(TableManager::from_json_file(&config)));
-        let file = manager.borrow_mut().file_manager.get_file(&table_name);
-        assert_eq!(file.borrow().page_sum, 2);
-        assert_eq!(file.borrow().first_free_page, 0);
-        let mut query = gen_plan_helper!(
-            "select * from test_file_persistence_message", &manager);
-        query.open();
-        let t1 = extract!(query.get_next(), Some(tuple_data), tuple_data);
-        let t2 = extract!(query.get_next(), Some(tuple_data), tuple_data);
-        let t3 = extract!(query.get_next(), Some(tuple_data), tuple_data);
-        assert_pattern!(query.get_next(), None);
-        assert_eq!(unsafe{ read::<i32>(t1[0] as *const i32) }, 233);
-        assert_eq!(unsafe{ read::<i32>(t2[0] as *const i32) }, 777);
-        assert_eq!(unsafe{ read::<i32>(t3[0] as *const i32) }, 1);
-    }
-    {
-        // test page switch
-        let config = Config::new(&r#"
-            max_memory_pool_page_num = 2
-            table_meta_dir = "test_file/table_meta/test_persistence_with_page_switch/"
-            table_file_dir = "test_file/table_file/test_persistence_with_page_switch/""#.to_string());
-        let table_name = "test_file_persistence_message".to_string();
-        let add_table_name = "test_file_message".to_string();
-        {
-            let manager = Rc::new(RefCell::new(TableManager::new(&config)));
-            manager.borrow_mut().add_table(test_query::gen_test_table(&table_name));
-            test_query::insert_data(&table_name, &manager);
-
-            assert_eq!(manager.borrow().file_manager.page_pool.get_capacity(), 2);
-            manager.borrow_mut().add_table(gen_test_table());
-
-            let mut insert = gen_plan_helper!(
-                "insert test_file_message values(766, \"test\", 2.3333)",
-                &manager);
-            insert.open();
-            assert_pattern!(insert.get_next(), None);
-            assert_pattern!(insert.get_error(), None);
-
-            let mut scan = gen_plan_helper!(
-                "select * from test_file_message", &manager);
-            scan.open();
-            assert_pattern!(scan.get_next(), Some(..));
-            assert_pattern!(insert.get_next(), None);
-
-            manager.borrow_mut().save_to_file();
-        }
-        let manager = Rc::new(RefCell::new(TableManager::from_json_file(&config)));
-        {
-            let file = manager.borrow_mut().file_manager.get_file(&table_name);
-            assert_eq!(file.borrow().page_sum, 2);
-            assert_eq!(file.borrow().first_free_page, 0);
-            let mut query = gen_plan_helper!(
-                "select * from test_file_persistence_message", &manager);
-            query.open();
-            let t1 = extract!(query.get_next(), Some(tuple_data), tuple_data);
-            let t2 = extract!(query.get_next(), Some(tuple_data), tuple_data);
-            let t3 = extract!(query.get_next(), Some(tuple_data), tuple_data);
-            assert_pattern!(query.get_next(), None);
-            assert_eq!(unsafe{ read::<i32>(t1[0] as *const i32) }, 233);
-            assert_eq!(unsafe{ read::<i32>(t2[0] as *const i32) }, 777);
-            assert_eq!(unsafe{ read::<i32>(t3[0] as *const i32) }, 1);
-        }
-        {
-            let file = manager.borrow_mut().file_manager.get_file(&add_table_name);
-            assert_eq!(file.borrow().page_sum, 1);
-            assert_eq!(file.borrow().first_free_page, 0);
-            let mut query = gen_plan_helper!(
-                "select * from test_file_message", &manager);
-            query.open();
-            let t1 = extract!(query.get_next(), Some(tuple_data), tuple_data);
-            assert_pattern!(query.get_next(), None);
-            assert_eq!(unsafe{ read::<i32>(t1[0] as *const i32) }, 766);
-        }
-    }
-}
+use std::ptr::{read, write};
+use std::slice::{from_raw_parts, from_raw_parts_mut};
+use std::sync::{Arc, RwLock};
+use std::fs::{OpenOptions, remove_file};
+use std::io::{Read, Write, Seek, SeekFrom};
+use libc::malloc;
+use ::utils::pointer::{read_string, write_string, pointer_offset};
+use ::utils::config::Config;
+use ::utils::file::path_join;
+use ::store::format::{DEFAULT_PAGE_SIZE, TABLE_MAGIC, TABLE_VERSION, page_checksum, get_page_checksum};
+use ::store::file::{TableFile, FilePage, BitMap, PageHeader, TableFileManager};
+use ::store::buffer::{DataPtr, Page};
+use ::store::table::{Table, Attr, AttrType, Index, TableManager};
+use ::parser::common::{ValueExpr, ValueType};
+use ::store::tuple::TupleValue;
+use ::store::durability::Durability;
+use ::store::overflow::overflow_page_capacity;
+use ::test::exec::test_query;
+use ::test::exec::test_query::gen_test_manager;
+use ::test::fixture::{clean_test_dirs, gen_test_config, run_sql, count_rows};
+
+
+#[test]
+fn test_page_header() {
+    {
+        let data;
+        unsafe{
+            data = malloc(8);
+            from_raw_parts_mut(data as *mut u8, 4).copy_from_slice(&[233, 0, 154, 2]);
+        }
+        let mut header = PageHeader{
+            slot_sum : 233,
+            first_free_slot : 0,
+            data : data,
+        };
+        header.init_from_page_data();
+        assert_eq!(header.slot_sum, 233);
+        assert_eq!(header.first_free_slot, 666);
+    }
+    {
+        let data;
+        unsafe{
+            data = malloc(8);
+            write::<u32>(data as *mut u32, 111);
+            write::<u32>((data as *mut u32).offset(1), 222);
+        }
+        let mut header = PageHeader{
+            slot_sum : 233,
+            first_free_slot : 666,
+            data : data,
+        };
+        header.save_to_page_data();
+        // little endian u16, the checksum is untouched
+        assert_eq!(unsafe{from_raw_parts(data as *const u8, 8)}, &[233, 0, 154, 2, 222, 0, 0, 0]);
+    }
+}
+
+#[test]
+fn test_bitmap()
+{
+    let data = unsafe{ malloc(3) };
+    let mut bitmap = BitMap{
+        data : data,
+        slot_sum : 24,
+    };
+    bitmap.clean();
+    for i in 0..24 {
+        assert!(!bitmap.is_inuse(i));
+    }
+    bitmap.set_inuse(8, true);
+    assert!(!bitmap.is_inuse(7));
+    assert!(bitmap.is_inuse(8));
+    assert!(!bitmap.is_inuse(9));
+    assert_eq!(bitmap.next_tuple_index(0), 8);
+    bitmap.set_inuse(8 + 7, true);
+    assert!(!bitmap.is_inuse(8 + 6));
+    assert!(bitmap.is_inuse(8 + 7));
+    assert!(!bitmap.is_inuse(8 + 8));
+    assert_eq!(bitmap.next_tuple_index(0), 8);
+    assert_eq!(bitmap.next_tuple_index(8), 8);
+    assert_eq!(bitmap.next_tuple_index(8 + 1), 8 + 7);
+    bitmap.set_inuse(8 + 3, true);
+    assert!(!bitmap.is_inuse(8 + 2));
+    assert!(bitmap.is_inuse(8 + 3));
+    assert!(!bitmap.is_inuse(8 + 4));
+    unsafe{
+        assert_eq!(read::<u8>(data as *const u8), 0);
+        assert_eq!(read::<u8>((data as *const u8).offset(1)), 137);
+        assert_eq!(read::<u8>((data as *const u8).offset(2)), 0);
+    }
+    assert_eq!(bitmap.get_first_free_slot(), 0);
+    bitmap.set_inuse(0, true);
+    assert_eq!(bitmap.get_first_free_slot(), 1);
+    for i in 1..8 {
+        bitmap.set_inuse(i, true);
+    }
+    assert_eq!(bitmap.next_tuple_index(8), 8);
+    assert_eq!(bitmap.get_first_free_slot(), 8 + 1);
+    bitmap.set_inuse(3, false);
+    assert_eq!(bitmap.get_first_free_slot(), 3);
+    assert_eq!(bitmap.next_tuple_index(50), bitmap.slot_sum);
+}
+
+fn gen_test_table() -> Table {
+    Table{
+        name : "test_file_message".to_string(),
+        file : "test_file_message".to_string(),
+        attr_list : vec![
+            Attr{
+                name : "id".to_string(),
+                attr_type : AttrType::Int,
+                primary : true,
+                nullable : false,
+            },
+            Attr{
+                name : "content".to_string(),
+                attr_type : AttrType::Char{ len : 6 },
+                primary : false,
+                nullable : false,
+            },
+            Attr{
+                name : "score".to_string(),
+                attr_type : AttrType::Float,
+                primary : false,
+                nullable : true,
+            },
+        ],
+        index_list : vec![],
+    }
+}
+
+#[test]
+fn test_file_page_insert() {
+    let table = gen_test_table();
+    let tuple_desc = table.gen_tuple_desc();
+    assert_eq!(tuple_desc.null_bitmap_len, 4);
+    assert_eq!(tuple_desc.tuple_len, 20);
+    let mut mem_page = Page::new(1, 2);
+    mem_page.alloc(DEFAULT_PAGE_SIZE);
+    let page = Arc::new(RwLock::new(mem_page));
+    let mut file_page = FilePage::new(page, &tuple_desc, DEFAULT_PAGE_SIZE);
+    file_page.init_empty_page();
+    let mut value_list = vec![
+        ValueExpr{ value : "233".to_string(), value_type : ValueType::Integer },
+        ValueExpr{ value : "abcdef".to_string(), value_type : ValueType::String },
+        ValueExpr{ value : "666.666".to_string(), value_type : ValueType::Float },
+    ];
+    assert_eq!(file_page.header.first_free_slot, 0);
+    assert_eq!(file_page.is_inuse(0), false);
+    file_page.insert(&value_list, &tuple_desc);
+    assert_eq!(file_page.header.first_free_slot, 1);
+    assert_eq!(file_page.is_inuse(0), true);
+    value_list[0].value = "777".to_string();
+    value_list[1].value = "dyb".to_string();
+    value_list[2].value = "12345.777".to_string();
+    file_page.insert(&value_list, &tuple_desc);
+    assert_eq!(file_page.header.first_free_slot, 2);
+    assert_eq!(file_page.is_inuse(1), true);
+
+    file_page.save_to_page();
+    let mut p = file_page.mem_page.read().unwrap().data;
+    assert_eq!(unsafe{ read(p as *const u16) }, 203);  // slot_sum
+    p = pointer_offset(p, 2);
+    assert_eq!(unsafe{ read(p as *const u16) }, 2);  // first_free_slot
+    p = pointer_offset(p, 6);  // checksum
+    assert_eq!(unsafe{ read(p as *const u8) }, 3);  // bitmap
+    assert_eq!(unsafe{ read(pointer_offset(p, 4) as *const u8) }, 0);  // bitmap
+    p = pointer_offset(p, (203 + 7) / 8);
+    // first tuple
+    assert_eq!(unsafe{ read(p as *const u32) }, 0);  // null bitmap
+    p = pointer_offset(p, 4);
+    assert_eq!(unsafe{ read(p as *const u32) }, 233);  // tuple data: id
+    p = pointer_offset(p, 4);
+    assert_eq!(unsafe{ read_string(p, 6) }, "abcdef");  // tuple data: content
+    p = pointer_offset(p, 8);
+    assert_eq!(unsafe{ read(p as *const f32) }, 666.666);  // tuple data: score
+    p = pointer_offset(p, 4);
+    // second tuple
+    assert_eq!(unsafe{ read(p as *const u32) }, 0);  // null bitmap
+    p = pointer_offset(p, 4);
+    assert_eq!(unsafe{ read(p as *const u32) }, 777);  // tuple data: id
+    p = pointer_offset(p, 4);
+    assert_eq!(unsafe{ read_string(p, 6) }, "dyb");  // tuple data: content
+    p = pointer_offset(p, 8);
+    assert_eq!(unsafe{ read(p as *const f32) }, 12345.777);  // tuple data: score
+}
+
+#[test]
+fn test_file_page_insert_null() {
+    let table = gen_test_table();
+    let tuple_desc = table.gen_tuple_desc();
+    let mut mem_page = Page::new(1, 2);
+    mem_page.alloc(DEFAULT_PAGE_SIZE);
+    let page = Arc::new(RwLock::new(mem_page));
+    let mut file_page = FilePage::new(page, &tuple_desc, DEFAULT_PAGE_SIZE);
+    file_page.init_empty_page();
+    let value_list = vec![
+        ValueExpr{ value : "777".to_string(), value_type : ValueType::Integer },
+        ValueExpr{ value : "dyb".to_string(), value_type : ValueType::String },
+        ValueExpr{ value : "null".to_string(), value_type : ValueType::Null },
+    ];
+    file_page.insert(&value_list, &tuple_desc);
+    assert_eq!(file_page.is_inuse(0), true);
+    assert_pattern!(file_page.get_tuple_value(0, 2, &tuple_desc), TupleValue::Null);
+    let tuple_data = file_page.get_tuple_data(0, &tuple_desc).unwrap();
+    assert!(!tuple_data[1].is_null());
+    assert!(tuple_data[2].is_null());
+
+    file_page.save_to_page();
+    let mut p = file_page.mem_page.read().unwrap().data;
+    p = pointer_offset(p, 8 + (203 + 7) / 8);
+    assert_eq!(unsafe{ read(p as *const u32) }, 4);  // null bitmap
+    p = pointer_offset(p, 16);
+    assert_eq!(unsafe{ read(p as *const u32) }, 0);  // tuple data: null score
+}
+
+#[test]
+fn test_file_insert() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 2
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let mut manager = TableFileManager::new(&config).unwrap();
+    let table = Arc::new(RwLock::new(gen_test_table()));
+    let table_name = "test_file_message".to_string();
+    manager.create_file(table_name.clone(), table);
+    let mut value_list = vec![
+        ValueExpr{ value : "233".to_string(), value_type : ValueType::Integer },
+        ValueExpr{ value : "abcdef".to_string(), value_type : ValueType::String },
+        ValueExpr{ value : "666.666".to_string(), value_type : ValueType::Float },
+    ];
+    manager.insert(&table_name, &value_list).unwrap();
+    assert_pattern!(manager.get_tuple_value(&table_name, 0, 0), TupleValue::Int(233));
+    assert_pattern!(manager.get_tuple_value(&table_name, 0, 2), TupleValue::Float(666.666));
+    assert_eq!(extract!(
+        manager.get_tuple_value(&table_name, 0, 1), TupleValue::Char(s), s), "abcdef");
+
+    value_list[0].value = "777".to_string();
+    value_list[1].value = "dyb".to_string();
+    value_list[2].value = "12345.777".to_string();
+    manager.insert(&table_name, &value_list).unwrap();
+    assert_pattern!(manager.get_tuple_value(&table_name, 1, 0), TupleValue::Int(777));
+    assert_pattern!(manager.get_tuple_value(&table_name, 1, 2), TupleValue::Float(12345.777));
+    assert_eq!(extract!(
+        manager.get_tuple_value(&table_name, 1, 1), TupleValue::Char(s), s), "dyb");
+}
+
+#[test]
+fn test_dirty_page() {
+    let config = gen_test_config("test_dirty_page", 3);
+    let file_dir = config.get_str("table_file_dir");
+    let mut manager = TableFileManager::new(&config).unwrap();
+    let table_name = "test_dirty_page_message".to_string();
+    let other_table_name = "test_dirty_page_other".to_string();
+    for name in vec![&table_name, &other_table_name] {
+        let table = Arc::new(RwLock::new(test_query::gen_test_table(name)));
+        manager.create_file(name.clone(), table);
+    }
+    let value_list = vec![
+        ValueExpr{ value : "233".to_string(), value_type : ValueType::Integer },
+        ValueExpr{ value : "666.666".to_string(), value_type : ValueType::Float },
+        ValueExpr{ value : "abcdef".to_string(), value_type : ValueType::String },
+    ];
+    manager.insert(&table_name, &value_list).unwrap();
+    let file = manager.get_file(&table_name);
+    let is_dirty = |file : &Arc<RwLock<TableFile>>|
+        file.read().unwrap().loaded_pages.get(&0).unwrap().mem_page.read().unwrap().dirty;
+    assert!(is_dirty(&file));
+    manager.save_all();
+    assert!(!is_dirty(&file));
+    manager.get_tuple_value(&table_name, 0, 0);
+    assert!(!is_dirty(&file));
+
+    // the clean page is swapped out without being written back
+    let page_size = DEFAULT_PAGE_SIZE;
+    let last_byte = page_size * 2 - 1;
+    file.write().unwrap().write_page_range(0, page_size - 1, &[233]);
+    manager.insert(&other_table_name, &value_list).unwrap();
+    assert!(file.read().unwrap().loaded_pages.get(&0).is_none());
+    let mut f = OpenOptions::new().read(true).open(
+        path_join(&file_dir, &format!("{}.table", table_name))).unwrap();
+    is_match!(f.seek(SeekFrom::Start(last_byte as u64)), Ok(..));
+    let mut buf = [0];
+    is_match!(f.read_exact(&mut buf), Ok(..));
+    assert_eq!(buf[0], 233);
+}
+
+#[test]
+fn test_get_tuple_data() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 2
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let mut manager = TableFileManager::new(&config).unwrap();
+    let table = Arc::new(RwLock::new(gen_test_table()));
+    let table_name = "test_file_message".to_string();
+    manager.create_file(table_name.clone(), table);
+    let value_list = vec![
+        ValueExpr{ value : "233".to_string(), value_type : ValueType::Integer },
+        ValueExpr{ value : "abcdef".to_string(), value_type : ValueType::String },
+        ValueExpr{ value : "666.666".to_string(), value_type : ValueType::Float },
+    ];
+    manager.insert(&table_name, &value_list).unwrap();
+    let tuple_data = manager.get_tuple_data(&table_name, 0).unwrap();
+    let p1 = tuple_data[0];
+    let p2 = tuple_data[1];
+    let p3 = tuple_data[2];
+    assert_eq!(unsafe{ read::<i32>(p1 as *const i32) }, 233);
+    assert_eq!(unsafe{ read_string(p2, 6) }, "abcdef");
+    assert_eq!(unsafe{ read::<f32>(p3 as *const f32) }, 666.666);
+}
+
+#[test]
+fn test_file_persistence() {
+    {
+        let config = Config::new(&r#"
+            max_memory_pool_page_num = 2
+            table_meta_dir = "test_file/table_meta/test_file_persistence/"
+            table_file_dir = "test_file/table_file""#.to_string());
+        let table_name = "test_file_persistence_message".to_string();
+        {
+            let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+            manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+            test_query::insert_data(&table_name, &manager);
+            manager.write().unwrap().save_to_file();
+        }
+        let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        assert_eq!(file.read().unwrap().page_sum, 2);
+        assert_eq!(file.read().unwrap().first_free_page, 0);
+        let mut query = gen_plan_helper!(
+            "select * from test_file_persistence_message", &manager);
+        query.open();
+        let t1 = extract!(query.get_next(), Some(tuple_data), tuple_data);
+        let t2 = extract!(query.get_next(), Some(tuple_data), tuple_data);
+        let t3 = extract!(query.get_next(), Some(tuple_data), tuple_data);
+        assert_pattern!(query.get_next(), None);
+        assert_eq!(unsafe{ read::<i32>(t1[0] as *const i32) }, 233);
+        assert_eq!(unsafe{ read::<i32>(t2[0] as *const i32) }, 777);
+        assert_eq!(unsafe{ read::<i32>(t3[0] as *const i32) }, 1);
+    }
+    {
+        // test page switch
+        let config = Config::new(&r#"
+            max_memory_pool_page_num = 2
+            table_meta_dir = "test_file/table_meta/test_persistence_with_page_switch/"
+            table_file_dir = "test_file/table_file/test_persistence_with_page_switch/""#.to_string());
+        let table_name = "test_file_persistence_message".to_string();
+        let add_table_name = "test_file_message".to_string();
+        {
+            let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+            manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+            test_query::insert_data(&table_name, &manager);
+
+            assert_eq!(manager.read().unwrap().file_manager.page_pool.get_capacity(), 2);
+            manager.write().unwrap().add_table(gen_test_table());
+
+            let mut insert = gen_plan_helper!(
+                "insert test_file_message values(766, \"test\", 2.3333)",
+                &manager);
+            insert.open();
+            assert_pattern!(insert.get_next(), None);
+            assert_pattern!(insert.get_error(), None);
+
+            let mut scan = gen_plan_helper!(
+                "select * from test_file_message", &manager);
+            scan.open();
+            assert_pattern!(scan.get_next(), Some(..));
+            assert_pattern!(insert.get_next(), None);
+
+            manager.write().unwrap().save_to_file();
+        }
+        let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+        {
+            let file = manager.write().unwrap().file_manager.get_file(&table_name);
+            assert_eq!(file.read().unwrap().page_sum, 2);
+            assert_eq!(file.read().unwrap().first_free_page, 0);
+            let mut query = gen_plan_helper!(
+                "select * from test_file_persistence_message", &manager);
+            query.open();
+            let t1 = extract!(query.get_next(), Some(tuple_data), tuple_data);
+            let t2 = extract!(query.get_next(), Some(tuple_data), tuple_data);
+            let t3 = extract!(query.get_next(), Some(tuple_data), tuple_data);
+            assert_pattern!(query.get_next(), None);
+            assert_eq!(unsafe{ read::<i32>(t1[0] as *const i32) }, 233);
+            assert_eq!(unsafe{ read::<i32>(t2[0] as *const i32) }, 777);
+            assert_eq!(unsafe{ read::<i32>(t3[0] as *const i32) }, 1);
+        }
+        {
+            let file = manager.write().unwrap().file_manager.get_file(&add_table_name);
+            assert_eq!(file.read().unwrap().page_sum, 1);
+            assert_eq!(file.read().unwrap().first_free_page, 0);
+            let mut query = gen_plan_helper!(
+                "select * from test_file_message", &manager);
+            query.open();
+            let t1 = extract!(query.get_next(), Some(tuple_data), tuple_data);
+            assert_pattern!(query.get_next(), None);
+            assert_eq!(unsafe{ read::<i32>(t1[0] as *const i32) }, 766);
+        }
+    }
+}
+
+#[test]
+fn test_free_space_reuse() {
+    let config = gen_test_config("test_free_space_reuse", 4);
+    let file_dir = config.get_str("table_file_dir");
+    let table_name = "test_free_space_reuse_message".to_string();
+    let slot_sum;
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        slot_sum = file.read().unwrap().get_page_slot_sum();
+        for id in 0..3 * slot_sum {
+            run_sql(&format!("insert {} values({}, 1.0, \"a\")", table_name, id), &manager);
+        }
+        assert_eq!(file.read().unwrap().page_sum, 3);
+        run_sql(&format!("delete from {} where id < {}", table_name, slot_sum), &manager);
+        for id in 0..slot_sum {
+            run_sql(&format!("insert {} values({}, 1.0, \"a\")", table_name, id), &manager);
+        }
+        assert_eq!(file.read().unwrap().page_sum, 3);
+        run_sql(&format!("delete from {} where id >= {} and id < {}",
+            table_name, slot_sum, slot_sum + 10), &manager);
+        manager.write().unwrap().save_to_file();
+    }
+    {
+        // free-space map is saved
+        let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        assert!(!file.read().unwrap().fsm.may_have_free_slot(0));
+        assert!(file.read().unwrap().fsm.may_have_free_slot(1));
+        for id in 0..10 {
+            run_sql(&format!("insert {} values({}, 1.0, \"a\")", table_name, slot_sum + id), &manager);
+        }
+        assert_eq!(file.read().unwrap().page_sum, 3);
+        run_sql(&format!("delete from {} where id < 10", table_name), &manager);
+        manager.write().unwrap().save_to_file();
+    }
+    // free-space map lost
+    is_match!(remove_file(path_join(&file_dir, &format!("{}.fsm", table_name))), Ok(..));
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    for id in 0..10 {
+        run_sql(&format!("insert {} values({}, 1.0, \"a\")", table_name, id), &manager);
+    }
+    let file = manager.write().unwrap().file_manager.get_file(&table_name);
+    assert_eq!(file.read().unwrap().page_sum, 3);
+    assert!(!file.read().unwrap().fsm.may_have_free_slot(0));
+}
+
+#[test]
+fn test_vacuum() {
+    let config = gen_test_config("test_vacuum", 4);
+    let file_dir = config.get_str("table_file_dir");
+    let table_name = "test_vacuum_message".to_string();
+    let slot_sum;
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        let mut table = test_query::gen_test_table(&table_name);
+        table.index_list.push(Index{
+            name : "content_index".to_string(),
+            attr : "content".to_string(),
+            unique : false,
+        });
+        manager.write().unwrap().add_table(table);
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        slot_sum = file.read().unwrap().get_page_slot_sum();
+        for id in 0..4 * slot_sum {
+            run_sql(&format!("insert {} values({}, 1.0, \"c{}\")", table_name, id, id % 3), &manager);
+        }
+        run_sql(&format!("delete from {} where id % 2 = 1", table_name), &manager);
+        manager.write().unwrap().save_to_file();
+        assert_eq!(file.read().unwrap().page_sum, 4);
+
+        run_sql("vacuum", &manager);
+        assert_eq!(file.read().unwrap().page_sum, 2);
+        assert!(!file.read().unwrap().fsm.may_have_free_slot(2));
+        manager.write().unwrap().save_to_file();
+        assert_eq!(check_ok!(file.read().unwrap().file.metadata()).len(), (3 * DEFAULT_PAGE_SIZE) as u64);
+        assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), 2 * slot_sum);
+    }
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    let file = manager.write().unwrap().file_manager.get_file(&table_name);
+    assert_eq!(file.read().unwrap().page_sum, 2);
+    let table_path = path_join(&file_dir, &format!("{}.table", table_name));
+    assert_eq!(check_ok!(OpenOptions::new().read(true).open(&table_path)).metadata().unwrap().len(),
+        (3 * DEFAULT_PAGE_SIZE) as u64);
+    // tuples moved from the last pages can be found by the indexes
+    let last_id = 4 * slot_sum - 2;
+    let mut query = gen_plan_helper!(
+        &format!("select * from {} where id = {}", table_name, last_id), &manager);
+    query.open();
+    let t = extract!(query.get_next(), Some(tuple_data), tuple_data);
+    assert_pattern!(query.get_next(), None);
+    assert_eq!(unsafe{ read::<i32>(t[0] as *const i32) }, last_id as i32);
+    let expected = (0..4 * slot_sum).filter(|id| id % 2 == 0 && id % 3 == 1).count();
+    assert_eq!(count_rows(&format!("select * from {} where content = \"c1\"", table_name), &manager),
+        expected);
+    run_sql(&format!("delete from {} where id < {}", table_name, 2 * slot_sum), &manager);
+    run_sql(&format!("vacuum {}", table_name), &manager);
+    assert_eq!(file.read().unwrap().page_sum, 1);
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), slot_sum);
+    run_sql(&format!("delete from {}", table_name), &manager);
+    run_sql(&format!("vacuum {}", table_name), &manager);
+    assert_eq!(file.read().unwrap().page_sum, 0);
+    run_sql(&format!("insert {} values(1, 1.0, \"c1\")", table_name), &manager);
+    assert_eq!(file.read().unwrap().page_sum, 1);
+    assert_eq!(count_rows(&format!("select * from {} where content = \"c1\"", table_name), &manager), 1);
+}
+
+#[test]
+fn test_buffer_stats() {
+    let config = gen_test_config("test_buffer_stats", 2);
+    let table_name = "test_buffer_stats_message".to_string();
+    let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+    manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+    let fd = manager.read().unwrap().file_manager.get_file_fd(&table_name);
+    let stats = manager.read().unwrap().file_manager.page_pool.get_stats(fd);
+    assert_eq!((stats.hits, stats.misses, stats.evictions, stats.write_backs), (0, 0, 0, 0));
+    let slot_sum = manager.write().unwrap().file_manager.get_file(&table_name).read().unwrap().get_page_slot_sum();
+    for id in 0..3 * slot_sum {
+        run_sql(&format!("insert {} values({}, 1.0, \"a\")", table_name, id), &manager);
+    }
+    manager.write().unwrap().save_to_file();
+    let stats = manager.read().unwrap().file_manager.page_pool.get_stats(fd);
+    assert!(stats.misses >= 3);
+    assert!(stats.evictions > 0);
+    assert!(stats.write_backs >= 3);
+
+    // only the pages of the table are used
+    manager.write().unwrap().file_manager.get_tuple_data(&table_name, slot_sum);
+    manager.write().unwrap().file_manager.get_tuple_data(&table_name, 2 * slot_sum);
+    let before = manager.read().unwrap().file_manager.page_pool.get_stats(fd);
+    manager.write().unwrap().file_manager.get_tuple_data(&table_name, slot_sum);
+    let after = manager.read().unwrap().file_manager.page_pool.get_stats(fd);
+    assert_eq!((after.hits, after.misses), (before.hits + 1, before.misses));
+    manager.write().unwrap().file_manager.get_tuple_data(&table_name, 0);
+    let after = manager.read().unwrap().file_manager.page_pool.get_stats(fd);
+    assert_eq!(after.misses, before.misses + 1);
+    assert_eq!(after.evictions, before.evictions + 1);
+    assert_eq!(after.write_backs, before.write_backs);  // clean page is only dropped
+
+    run_sql(&format!("update {} set score = 2.0 where id = 0", table_name), &manager);
+    manager.write().unwrap().save_to_file();
+    let stats = manager.read().unwrap().file_manager.page_pool.get_stats(fd);
+    assert!(stats.write_backs > after.write_backs);
+    let status = manager.read().unwrap().show_buffer_status();
+    assert!(status.starts_with("buffer: capacity 2, loaded 2, pinned 0"));
+    assert!(status.contains(&format!("{}: {}", table_name, stats.desc())));
+    assert!(status.contains(&format!("{}.primary: hits", table_name)));
+}
+
+#[test]
+fn test_page_size() {
+    let (meta_dir, file_dir) = clean_test_dirs("test_page_size");
+    let gen_config = |page_size : usize| Config::new(&format!(r#"
+        max_memory_pool_page_num = 4
+        page_size = {}
+        table_meta_dir = "{}"
+        table_file_dir = "{}""#, page_size, meta_dir, file_dir));
+    let table_name = "test_page_size_message".to_string();
+    let slot_sum;
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&gen_config(16384))));
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        slot_sum = file.read().unwrap().get_page_slot_sum();
+        assert!(slot_sum > 500);  // about 140 in a 4K page
+        for id in 0..slot_sum + 1 {
+            run_sql(&format!("insert {} values({}, 1.0, \"a\")", table_name, id), &manager);
+        }
+        manager.write().unwrap().save_to_file();
+        assert_eq!(check_ok!(file.read().unwrap().file.metadata()).len(), 3 * 16384);
+    }
+    // the page size in the config only works for new database
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&gen_config(4096)).unwrap()));
+    assert_eq!(manager.read().unwrap().file_manager.page_pool.get_page_size(), 16384);
+    let file = manager.write().unwrap().file_manager.get_file(&table_name);
+    assert_eq!(file.read().unwrap().get_page_slot_sum(), slot_sum);
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), slot_sum + 1);
+    assert_eq!(count_rows(&format!("select * from {} where id = {}", table_name, slot_sum), &manager), 1);
+}
+
+#[test]
+fn test_upgrade_legacy_file() {
+    let config = gen_test_config("test_upgrade_legacy_file", 4);
+    let file_dir = config.get_str("table_file_dir");
+    let table_name = "test_upgrade_legacy_file_message".to_string();
+    let slot_sum;
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        let file = manager.write().unwrap().file_manager.get_file(&table_name);
+        slot_sum = file.read().unwrap().get_page_slot_sum();
+        for id in 0..slot_sum + 1 {
+            run_sql(&format!("insert {} values({}, 1.5, \"a\")", table_name, id), &manager);
+        }
+        manager.write().unwrap().save_to_file();
+    }
+    // rewrite the file in version 1, whose headers are native u32 and tuples are native endian,
+    // the tuples are the same on a little endian host
+    let path = path_join(&file_dir, &format!("{}.table", table_name));
+    let mut data = Vec::new();
+    check_ok!(check_ok!(OpenOptions::new().read(true).open(&path)).read_to_end(&mut data));
+    assert_eq!(&data[..8], TABLE_MAGIC);
+    let mut legacy = Vec::new();
+    legacy.extend_from_slice(&2u32.to_ne_bytes());  // page_sum
+    legacy.extend_from_slice(&1u32.to_ne_bytes());  // first_free_page
+    legacy.resize(DEFAULT_PAGE_SIZE, 0);
+    for page in data[DEFAULT_PAGE_SIZE..].chunks(DEFAULT_PAGE_SIZE) {
+        let slot_sum = page[0] as u32 | (page[1] as u32) << 8;
+        let first_free_slot = page[2] as u32 | (page[3] as u32) << 8;
+        legacy.extend_from_slice(&slot_sum.to_ne_bytes());
+        legacy.extend_from_slice(&first_free_slot.to_ne_bytes());
+        legacy.extend_from_slice(&page[8..]);
+    }
+    check_ok!(check_ok!(OpenOptions::new().write(true).truncate(true).open(&path)).write_all(&legacy));
+
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    let file = manager.write().unwrap().file_manager.get_file(&table_name);
+    assert_eq!(file.read().unwrap().version, TABLE_VERSION);
+    let mut data = Vec::new();
+    check_ok!(check_ok!(OpenOptions::new().read(true).open(&path)).read_to_end(&mut data));
+    assert_eq!(&data[..8], TABLE_MAGIC);
+    assert_eq!(data.len(), 3 * DEFAULT_PAGE_SIZE);
+    let page = &data[DEFAULT_PAGE_SIZE..2 * DEFAULT_PAGE_SIZE];
+    assert_eq!(get_page_checksum(page), page_checksum(page));
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), slot_sum + 1);
+    assert_eq!(count_rows(&format!("select * from {} where id = {} and score = 1.5",
+        table_name, slot_sum), &manager), 1);
+    run_sql(&format!("insert {} values({}, 1.5, \"a\")", table_name, slot_sum + 1), &manager);
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), slot_sum + 2);
+}
+
+#[test]
+fn test_upgrade_corrupted_file() {
+    let config = gen_test_config("test_upgrade_corrupted_file", 4);
+    let file_dir = config.get_str("table_file_dir");
+    let table_name = "test_upgrade_corrupted_file_message".to_string();
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        run_sql(&format!("insert {} values(1, 1.5, \"a\")", table_name), &manager);
+        manager.write().unwrap().save_to_file();
+    }
+    let path = path_join(&file_dir, &format!("{}.table", table_name));
+    let write_file = |data : &[u8]| check_ok!(check_ok!(OpenOptions::new().write(true).truncate(true)
+        .open(&path)).write_all(data));
+    // a version 1 file whose page header does not match the table
+    let mut legacy = Vec::new();
+    legacy.extend_from_slice(&1u32.to_ne_bytes());  // page_sum
+    legacy.extend_from_slice(&1u32.to_ne_bytes());  // first_free_page
+    legacy.resize(DEFAULT_PAGE_SIZE, 0);
+    legacy.extend_from_slice(&7u32.to_ne_bytes());  // slot_sum
+    legacy.resize(2 * DEFAULT_PAGE_SIZE, 0);
+    write_file(&legacy);
+    let err = extract!(TableManager::from_json_file(&config), Err(err), err);
+    assert!(err.contains(&format!("page 0 of table {} is corrupted", table_name)), "{}", err);
+    // a truncated header
+    write_file(&[TABLE_MAGIC, &[2, 0, 0, 0]].concat());
+    let err = extract!(TableManager::from_json_file(&config), Err(err), err);
+    assert!(err.contains(&format!("table {} is corrupted", table_name)), "{}", err);
+}
+
+#[test]
+fn test_durability() {
+    let gen_config = |durability : &str| Config::new(&format!(r#"
+        max_memory_pool_page_num = 5
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file"
+        {}"#, durability));
+    assert_eq!(Durability::from_config(&gen_config("")), Durability::PerStatement);
+    assert_eq!(Durability::from_config(&gen_config("durability = \"none\"")), Durability::None);
+    assert_eq!(Durability::from_config(&gen_config("durability = \"periodic\"")),
+        Durability::Periodic{ interval : 1000 });
+    assert_eq!(Durability::from_config(&gen_config("durability = \"periodic\"\ndurability_interval = 50")),
+        Durability::Periodic{ interval : 50 });
+
+    let (meta_dir, file_dir) = clean_test_dirs("test_durability");
+    let config = Config::new(&format!(r#"
+        max_memory_pool_page_num = 5
+        table_meta_dir = "{}"
+        table_file_dir = "{}"
+        durability = "periodic"
+        durability_interval = 3600000"#, meta_dir, file_dir));
+    let wal_path = format!("{}blastoise.wal", file_dir);
+    let table_name = "test_durability_message".to_string();
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        // the change of the catalog is saved at once
+        manager.write().unwrap().save_to_file();
+        assert_eq!(manager.read().unwrap().catalog_version, 1);
+        run_sql(&format!("insert {} values(1, 1.0, \"a\")", table_name), &manager);
+        manager.write().unwrap().save_to_file();
+        let fd = manager.read().unwrap().file_manager.get_file_fd(&table_name);
+        assert_eq!(manager.read().unwrap().file_manager.page_pool.get_stats(fd).write_backs, 0);
+        assert!(check_ok!(OpenOptions::new().read(true).open(&wal_path)).metadata().unwrap().len() > 0);
+    }
+    {
+        // the changes left in the log are recovered
+        let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+        assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), 1);
+        run_sql(&format!("insert {} values(2, 1.0, \"a\")", table_name), &manager);
+        manager.write().unwrap().flush();
+        assert_eq!(check_ok!(OpenOptions::new().read(true).open(&wal_path)).metadata().unwrap().len(), 0);
+    }
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), 2);
+}
+
+#[test]
+fn test_vacuum_overflow_tail() {
+    let config = gen_test_config("test_vacuum_overflow_tail", 4);
+    let table_name = "test_vacuum_overflow_tail_message".to_string();
+    let long_len = 9000;
+    let chain_len = (long_len + overflow_page_capacity(DEFAULT_PAGE_SIZE) - 1)
+        / overflow_page_capacity(DEFAULT_PAGE_SIZE);
+    let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+    run_sql(&format!("create table {}(id int not null primary, body text)", table_name), &manager);
+    let file = manager.write().unwrap().file_manager.get_file(&table_name);
+    let mut row_num = 0;
+    while file.read().unwrap().page_sum < 2 {
+        run_sql(&format!("insert {} values({}, \"b{:04}\")", table_name, row_num, row_num), &manager);
+        row_num += 1;
+    }
+    // the last page has 3 tuples
+    for _ in 0..2 {
+        run_sql(&format!("insert {} values({}, \"b{:04}\")", table_name, row_num, row_num), &manager);
+        row_num += 1;
+    }
+    // the overflow chain is at the end of the file
+    run_sql(&format!("insert {} values({}, \"{}\")", table_name, row_num, "y".repeat(long_len)), &manager);
+    assert_eq!(file.read().unwrap().page_sum, 2 + chain_len);
+    run_sql(&format!("delete from {} where id < 8", table_name), &manager);
+    run_sql("vacuum", &manager);
+    // the tuples before the overflow pages are still moved
+    assert_eq!(file.read().unwrap().page_sum, 2 + chain_len);
+    manager.write().unwrap().file_manager.ensure_page_loaded(&file, 1).unwrap();
+    assert_pattern!(file.read().unwrap().next_tuple_index(1, 0), None);
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), row_num - 7);
+    run_sql(&format!("delete from {} where id = {}", table_name, row_num), &manager);
+    run_sql("vacuum", &manager);
+    assert_eq!(file.read().unwrap().page_sum, 1);
+    assert_eq!(count_rows(&format!("select * from {}", table_name), &manager), row_num - 8);
+}
@@ -0,0 +1,91 @@
+use std::fs::{remove_dir_all, OpenOptions};
+use std::io::Write;
+use ::utils::file::ensure_dir_exist;
+use ::utils::checksum::crc32;
+use ::store::format::{DbHeader, DB_VERSION, check_page_size, TableHeader, TABLE_MAGIC, TABLE_VERSION,
+    LEGACY_TABLE_VERSION, TABLE_HEADER_SIZE, page_checksum, set_page_checksum, get_page_checksum};
+
+
+#[test]
+fn test_db_header() {
+    let header = DbHeader{ version : DB_VERSION, page_size : 16384 };
+    let mut buf = header.encode();
+    assert_eq!(buf.len(), 16);
+    assert_eq!(DbHeader::decode(&buf), Ok(header.clone()));
+    buf[0] = b'X';
+    assert_pattern!(DbHeader::decode(&buf), Err(..));
+    assert_pattern!(DbHeader::decode(&header.encode()[..12]), Err(..));
+    let newer = DbHeader{ version : DB_VERSION + 1, page_size : 4096 };
+    assert_pattern!(DbHeader::decode(&newer.encode()), Err(..));
+    let invalid = DbHeader{ version : DB_VERSION, page_size : 5000 };
+    assert_pattern!(DbHeader::decode(&invalid.encode()), Err(..));
+}
+
+#[test]
+fn test_check_page_size() {
+    assert_pattern!(check_page_size(4096), Ok(()));
+    assert_pattern!(check_page_size(65536), Ok(()));
+    assert_pattern!(check_page_size(2048), Err(..));
+    assert_pattern!(check_page_size(131072), Err(..));
+    assert_pattern!(check_page_size(12288), Err(..));
+}
+
+#[test]
+fn test_open_db_header() {
+    let dir = "test_file/table_file/test_open_db_header/".to_string();
+    let _ = remove_dir_all(&dir);
+    ensure_dir_exist(&dir);
+    assert_eq!(DbHeader::open(&dir, 8192).unwrap().page_size, 8192);
+    // the page size is decided when the database is created
+    assert_eq!(DbHeader::open(&dir, 4096).unwrap().page_size, 8192);
+    // a corrupted header is an error rather than a panic
+    check_ok!(OpenOptions::new().write(true).truncate(true).open(format!("{}blastoise.db", dir)))
+        .write_all(b"BLSTOISE").unwrap();
+    let err = extract!(DbHeader::open(&dir, 8192), Err(err), err);
+    assert!(err.contains("invalid database header"), "{}", err);
+}
+
+#[test]
+fn test_invalid_page_size_config() {
+    let dir = "test_file/table_file/test_invalid_page_size_config/".to_string();
+    let _ = remove_dir_all(&dir);
+    ensure_dir_exist(&dir);
+    let err = extract!(DbHeader::open(&dir, 1000), Err(err), err);
+    assert!(err.contains("invalid page_size in config"), "{}", err);
+    // the directory can not be read
+    let _ = remove_dir_all(&dir);
+    assert_pattern!(DbHeader::open(&dir, 4096), Err(..));
+}
+
+#[test]
+fn test_table_header() {
+    let header = TableHeader{ version : TABLE_VERSION, page_sum : 3, first_free_page : 1 };
+    let mut buf = header.encode();
+    assert_eq!(buf.len(), TABLE_HEADER_SIZE);
+    assert_eq!(&buf[..8], TABLE_MAGIC);
+    assert_eq!(TableHeader::decode(&buf), Ok(header.clone()));
+    assert_eq!(TableHeader::decode(&[]).unwrap().version, TABLE_VERSION);
+    assert_pattern!(TableHeader::decode(&buf[..20]), Err(..));
+    buf[12] = 4;
+    assert_pattern!(TableHeader::decode(&buf), Err(..));  // checksum mismatch
+    let newer = TableHeader{ version : TABLE_VERSION + 1, page_sum : 3, first_free_page : 1 };
+    assert_pattern!(TableHeader::decode(&newer.encode()), Err(..));
+    // without magic
+    let legacy = TableHeader{ version : LEGACY_TABLE_VERSION, page_sum : 3, first_free_page : 1 };
+    let buf = legacy.encode();
+    assert_eq!(buf.len(), 8);
+    assert_eq!(TableHeader::decode(&buf), Ok(legacy));
+}
+
+#[test]
+fn test_page_checksum() {
+    let mut page = vec![0; 4096];
+    page[0] = 203;
+    page[100] = 7;
+    set_page_checksum(&mut page);
+    let sum = get_page_checksum(&page);
+    assert_eq!(sum, page_checksum(&page));
+    page[100] = 8;
+    assert!(page_checksum(&page) != sum);
+    assert_eq!(crc32(b"123456789"), 0xcbf43926);
+}
@@ -0,0 +1,90 @@
+use std::fs::{OpenOptions, remove_dir_all};
+use std::io::{Read, Write};
+use ::utils::file::path_join;
+use ::store::table::TableManager;
+use ::store::fsck::check_database;
+use ::store::format::{DEFAULT_PAGE_SIZE, PAGE_HEADER_SIZE, set_page_checksum};
+use ::parser::common::{ValueExpr, ValueType};
+use ::test::exec::test_query;
+use ::test::fixture::{clean_test_dirs, gen_test_config};
+
+
+#[test]
+fn test_fsck() {
+    let config = gen_test_config("test_fsck", 4);
+    let meta_dir = config.get_str("table_meta_dir");
+    let file_dir = config.get_str("table_file_dir");
+    let table_name = "test_fsck_message".to_string();
+    let (slot_sum, tuple_desc) = {
+        let mut manager = TableManager::new(&config);
+        let table = test_query::gen_test_table(&table_name);
+        let tuple_desc = table.gen_tuple_desc();
+        manager.add_table(table);
+        let slot_sum = manager.file_manager.get_file(&table_name).read().unwrap().get_page_slot_sum();
+        for id in 0..slot_sum + 2 {
+            let value_list = vec![
+                ValueExpr{ value : id.to_string(), value_type : ValueType::Integer },
+                ValueExpr{ value : "null".to_string(), value_type : ValueType::Null },
+                ValueExpr{ value : "a".to_string(), value_type : ValueType::String },
+            ];
+            manager.insert(&table_name, &value_list).unwrap();
+        }
+        manager.save_to_file();
+        (slot_sum, tuple_desc)
+    };
+    let report = check_database(&meta_dir, &file_dir);
+    assert!(report.is_ok(), "{}", report.desc());
+    assert!(report.desc().contains(&format!("  2 pages, {} tuples\n", slot_sum + 2)));
+
+    let path = path_join(&file_dir, &format!("{}.table", table_name));
+    let mut data = Vec::new();
+    check_ok!(check_ok!(OpenOptions::new().read(true).open(&path)).read_to_end(&mut data));
+    {
+        let page = &mut data[DEFAULT_PAGE_SIZE..2 * DEFAULT_PAGE_SIZE];
+        let tuple = |slot : usize| PAGE_HEADER_SIZE + (slot_sum + 7) / 8 + slot * tuple_desc.tuple_len;
+        let id_offset = tuple(1) + tuple_desc.null_bitmap_len;
+        for b in page[id_offset..id_offset + 4].iter_mut() {
+            *b = 0;  // the same id as slot 0
+        }
+        page[tuple(2)] |= 1 << 2;  // content is null
+        set_page_checksum(page);
+    }
+    {
+        let page = &mut data[2 * DEFAULT_PAGE_SIZE..3 * DEFAULT_PAGE_SIZE];
+        page[2] = 0;  // first_free_slot
+        set_page_checksum(page);
+    }
+    data.push(0);
+    check_ok!(check_ok!(OpenOptions::new().write(true).open(&path)).write_all(&data));
+    check_ok!(OpenOptions::new().write(true).create(true).open(path_join(&file_dir, &"orphan.table".to_string())));
+
+    let report = check_database(&meta_dir, &file_dir);
+    let desc = report.desc();
+    assert_eq!(report.problem_num, 5, "{}", desc);
+    assert!(desc.contains("file length"));
+    assert!(desc.contains("page 0 slot 1: duplicate primary key Int(0)"));
+    assert!(desc.contains("page 0 slot 2: null in not null attribute content"));
+    assert!(desc.contains("page 1: first_free_slot is 0, but the first free slot in the bitmap is 2"));
+    assert!(desc.contains("orphan.table has no table"));
+}
+
+#[test]
+fn test_fsck_missing_dir() {
+    let (meta_dir, file_dir) = clean_test_dirs("test_fsck_missing_dir");
+    let report = check_database(&meta_dir, &file_dir);
+    assert_eq!(report.problem_num, 1, "{}", report.desc());
+    assert!(report.desc().contains("table_meta.json not found"));
+    // the catalog refers to a table whose directory is missing
+    {
+        let config = gen_test_config("test_fsck_missing_dir", 4);
+        let mut manager = TableManager::new(&config);
+        manager.add_table(test_query::gen_test_table(&"message".to_string()));
+        manager.save_to_file();
+    }
+    check_ok!(remove_dir_all(&file_dir));
+    let report = check_database(&meta_dir, &file_dir);
+    let desc = report.desc();
+    assert_eq!(report.problem_num, 2, "{}", desc);
+    assert!(desc.contains("message.table not found"));
+    assert!(desc.contains(&format!("can not read {}", file_dir)));
+}
@@ -0,0 +1,168 @@
+use std::sync::{Arc, RwLock};
+use std::fs::remove_file;
+use ::utils::file::path_join;
+use ::store::index::{encode_key, btree_insert, btree_delete, btree_lookup, PRIMARY_INDEX};
+use ::store::file::TableFileManager;
+use ::store::table::{TableManager, TableManagerRef, Table, Attr, AttrType};
+use ::store::tuple::TupleValue;
+use ::parser::common::{ValueExpr, ValueType};
+use ::test::exec::test_query;
+use ::test::fixture::gen_test_config;
+
+
+fn gen_long_key_table(table_name : &String) -> Table {
+    // long key makes nodes split frequently
+    Table{
+        name : table_name.clone(),
+        file : table_name.clone(),
+        attr_list : vec![
+            Attr{
+                name : "name".to_string(),
+                attr_type : AttrType::Char{ len : 200 },
+                primary : true,
+                nullable : false,
+            },
+        ],
+        index_list : vec![],
+    }
+}
+
+fn gen_name(n : usize) -> Vec<TupleValue> {
+    vec![TupleValue::Char(format!("name{:05}", n))]
+}
+
+fn lookup_id(id : i32, table_name : &String, manager : &TableManagerRef) -> Vec<usize> {
+    manager.write().unwrap().file_manager.index_lookup(
+        table_name, &PRIMARY_INDEX.to_string(), &vec![TupleValue::Int(id)])
+}
+
+fn insert_id(id : i32, table_name : &String, manager : &TableManagerRef) {
+    let value_list = vec![
+        ValueExpr{ value : id.to_string(), value_type : ValueType::Integer },
+        ValueExpr{ value : "1.0".to_string(), value_type : ValueType::Float },
+        ValueExpr{ value : "index".to_string(), value_type : ValueType::String },
+    ];
+    manager.write().unwrap().insert(table_name, &value_list).unwrap();
+}
+
+#[test]
+fn test_encode_key() {
+    let int_desc = vec![AttrType::Int];
+    let int_list = vec![i32::min_value(), -5, -1, 0, 3, 256, i32::max_value()];
+    for w in int_list.windows(2) {
+        assert!(encode_key(&vec![TupleValue::Int(w[0])], &int_desc)
+            < encode_key(&vec![TupleValue::Int(w[1])], &int_desc));
+    }
+    let float_desc = vec![AttrType::Float];
+    let float_list = vec![-1000.5, -2.5, -0.5, 0.0, 0.25, 1.5, 1000.0];
+    for w in float_list.windows(2) {
+        assert!(encode_key(&vec![TupleValue::Float(w[0])], &float_desc)
+            < encode_key(&vec![TupleValue::Float(w[1])], &float_desc));
+    }
+    assert_eq!(encode_key(&vec![TupleValue::Float(-0.0)], &float_desc),
+        encode_key(&vec![TupleValue::Float(0.0)], &float_desc));
+    let char_desc = vec![AttrType::Char{ len : 4 }];
+    let char_list = vec!["", "ab", "abc", "abcd", "b"];
+    for w in char_list.windows(2) {
+        assert!(encode_key(&vec![TupleValue::Char(w[0].to_string())], &char_desc)
+            < encode_key(&vec![TupleValue::Char(w[1].to_string())], &char_desc));
+    }
+    let null_key = encode_key(&vec![TupleValue::Null], &int_desc);
+    assert_eq!(null_key.len(), 5);
+    assert!(null_key < encode_key(&vec![TupleValue::Int(i32::min_value())], &int_desc));
+}
+
+#[test]
+fn test_btree() {
+    let config = gen_test_config("test_btree", 2);
+    let table_name = "test_btree_message".to_string();
+    let mut manager = TableFileManager::new(&config).unwrap();
+    let table = Arc::new(RwLock::new(gen_long_key_table(&table_name)));
+    manager.create_file(table_name.clone(), table);
+    let index = manager.get_index(&table_name, &PRIMARY_INDEX.to_string()).unwrap();
+    assert!(index.read().unwrap().max_key_num() < 100);
+
+    // insert in an order mixing small and large keys to split both leaf and internal nodes
+    let n = 3000;
+    let order : Vec<usize> = (0..n).map(|i| i * 1237 % n).collect();
+    for i in order.iter() {
+        let key = index.read().unwrap().gen_entry_key(&gen_name(*i), *i);
+        assert!(btree_insert(&mut manager, &index, &key, *i));
+    }
+    let key = index.read().unwrap().gen_entry_key(&gen_name(42), n);
+    assert!(!btree_insert(&mut manager, &index, &key, n));
+    for i in 0..n {
+        let key = index.read().unwrap().gen_key(&gen_name(i));
+        assert_eq!(btree_lookup(&mut manager, &index, &key), vec![i]);
+    }
+    assert_eq!(btree_lookup(&mut manager, &index, &[1]).len(), n);  // prefix of all keys
+
+    for i in (0..n).filter(|i| i % 3 == 0) {
+        let key = index.read().unwrap().gen_entry_key(&gen_name(i), i);
+        assert!(btree_delete(&mut manager, &index, &key));
+        assert!(!btree_delete(&mut manager, &index, &key));
+    }
+    for i in 0..n {
+        let key = index.read().unwrap().gen_key(&gen_name(i));
+        let expected = if i % 3 == 0 { vec![] } else { vec![i] };
+        assert_eq!(btree_lookup(&mut manager, &index, &key), expected);
+    }
+    let key = index.read().unwrap().gen_key(&gen_name(n));
+    assert_eq!(btree_lookup(&mut manager, &index, &key), vec![]);
+}
+
+#[test]
+fn test_index_maintenance() {
+    let config = gen_test_config("test_index_maintenance", 2);
+    let table_name = "test_index_maintenance_message".to_string();
+    let manager = TableManager::make_ref(&config);
+    manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+    for id in 0..1000 {
+        insert_id(id, &table_name, &manager);
+    }
+    let slot_sum = manager.write().unwrap().file_manager.get_file(&table_name).read().unwrap().get_page_slot_sum();
+    assert_eq!(lookup_id(0, &table_name, &manager), vec![0]);
+    assert_eq!(lookup_id(999, &table_name, &manager), vec![999]);
+    assert_eq!(lookup_id(slot_sum as i32, &table_name, &manager), vec![slot_sum]);
+    assert_eq!(lookup_id(1000, &table_name, &manager), vec![]);
+
+    let mut delete = gen_plan_helper!(
+        &format!("delete from {} where id >= 500", table_name), &manager);
+    delete.open();
+    while let Some(..) = delete.get_next() {}
+    delete.close();
+    assert_eq!(lookup_id(499, &table_name, &manager), vec![499]);
+    assert_eq!(lookup_id(500, &table_name, &manager), vec![]);
+    assert_eq!(lookup_id(999, &table_name, &manager), vec![]);
+
+    // deleted key can be inserted again
+    insert_id(999, &table_name, &manager);
+    assert_eq!(lookup_id(999, &table_name, &manager).len(), 1);
+}
+
+#[test]
+fn test_index_rebuild() {
+    let config = gen_test_config("test_index_rebuild", 2);
+    let table_name = "test_index_rebuild_message".to_string();
+    {
+        let manager = TableManager::make_ref(&config);
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        for id in 0..100 {
+            insert_id(id, &table_name, &manager);
+        }
+        manager.write().unwrap().save_to_file();
+    }
+    {
+        let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+        assert_eq!(lookup_id(42, &table_name, &manager), vec![42]);
+    }
+    // index file lost, built from the table file
+    let file_dir = config.get_str("table_file_dir");
+    is_match!(remove_file(path_join(&file_dir,
+        &format!("{}.{}.index", table_name, PRIMARY_INDEX))), Ok(..));
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    for id in 0..100 {
+        assert_eq!(lookup_id(id, &table_name, &manager), vec![id as usize]);
+    }
+    assert_eq!(lookup_id(100, &table_name, &manager), vec![]);
+}
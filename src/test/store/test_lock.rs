@@ -0,0 +1,71 @@
+use std::thread::{spawn, yield_now};
+use std::sync::Arc;
+use std::sync::mpsc::channel;
+use ::store::lock::{TableLockManager, LockTarget};
+
+
+fn tables(names : &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_shared_read() {
+    let manager = TableLockManager::new();
+    let t1 = manager.lock_read(tables(&["t1"]));
+    let t2 = manager.lock_read(tables(&["t1", "t2"]));
+    assert_eq!(manager.get_reader_num(&"t1".to_string()), 2);
+    drop(t1);
+    assert_eq!(manager.get_reader_num(&"t1".to_string()), 1);
+    {
+        // other tables can be written
+        let _w = manager.lock_write(LockTarget::Tables(tables(&["t3"])));
+        assert!(manager.is_writing());
+    }
+    drop(t2);
+    assert_eq!(manager.get_reader_num(&"t2".to_string()), 0);
+    assert!(!manager.is_writing());
+}
+
+fn wait_for_writer(manager : &TableLockManager) {
+    // the writer is blocked once it is in the waiting list
+    while manager.get_waiting_writer_num() == 0 {
+        yield_now();
+    }
+}
+
+#[test]
+fn test_writer_wait() {
+    let manager = Arc::new(TableLockManager::new());
+    let reader = manager.lock_read(tables(&["t1"]));
+    let (m, (sender, receiver)) = (manager.clone(), channel());
+    let writer = spawn(move || {
+        let _w = m.lock_write(LockTarget::All);
+        assert_eq!(m.get_reader_num(&"t1".to_string()), 0);
+        sender.send(()).unwrap();
+    });
+    wait_for_writer(&manager);
+    assert!(!manager.is_writing());
+    assert_pattern!(receiver.try_recv(), Err(..));
+    drop(reader);
+    assert_pattern!(receiver.recv(), Ok(()));
+    assert!(writer.join().is_ok());
+    assert!(!manager.is_writing());
+}
+
+#[test]
+fn test_writers_serialized() {
+    // the wal only has one statement in progress, so writers of different tables also wait
+    let manager = Arc::new(TableLockManager::new());
+    let writer = manager.lock_write(LockTarget::Tables(tables(&["t1"])));
+    let (m, (sender, receiver)) = (manager.clone(), channel());
+    let other = spawn(move || {
+        let _w = m.lock_write(LockTarget::Tables(tables(&["t2"])));
+        sender.send(()).unwrap();
+    });
+    wait_for_writer(&manager);
+    assert_pattern!(receiver.try_recv(), Err(..));
+    drop(writer);
+    assert_pattern!(receiver.recv(), Ok(()));
+    assert!(other.join().is_ok());
+    assert_eq!(manager.get_waiting_writer_num(), 0);
+}
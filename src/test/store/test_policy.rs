@@ -0,0 +1,220 @@
+use std::hash::{Hash, Hasher};
+use ::store::lru::{CacheValue, LruCache};
+use ::store::policy::{CachePolicy, CachePolicyRef, gen_cache_policy, check_policy, POLICY_LIST};
+use ::store::clock::ClockCache;
+use ::store::lru_k::LruKCache;
+use ::store::two_queue::TwoQueueCache;
+
+
+#[derive(Debug, Clone)]
+struct MockValue {
+    pub key : u64,
+    pub pinned : bool,
+}
+
+impl CacheValue for MockValue {
+    type KeyType = u64;
+    fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+}
+
+impl MockValue {
+    fn new(k : u64) -> Self {
+        MockValue{
+            key : k,
+            pinned : false,
+        }
+    }
+    fn new_pinned(k : u64) -> Self {
+        MockValue{
+            key : k,
+            pinned : true,
+        }
+    }
+}
+
+macro_rules! assert_victim {
+    ($cache:expr, $key:expr) => ({
+        let victim = $cache.prepare_page();
+        assert_eq!(extract!(victim, Ok(Some(ref v)), v.key), $key);
+    })
+}
+
+fn replace(cache : &mut CachePolicy<MockValue>, value : MockValue) -> u64 {
+    // return the key of the replaced value
+    let victim = cache.prepare_page().unwrap().unwrap().key;
+    cache.remove_victim();
+    let key = value.key;
+    cache.put(&key, value);
+    victim
+}
+
+#[test]
+fn test_all_policies() {
+    for policy in ["lru", "clock", "lru-k", "2q"].iter() {
+        let mut c : CachePolicyRef<MockValue> = gen_cache_policy(policy, 3);
+        assert_eq!(c.capacity(), 3);
+        assert_pattern!(c.get(&1), None);
+        c.put(&1, MockValue::new_pinned(1));
+        c.put(&2, MockValue::new(2));
+        assert_pattern!(c.prepare_page(), Ok(None));
+        c.put(&3, MockValue::new_pinned(3));
+        assert_eq!(c.get_load(), 3);
+        assert_eq!(extract!(c.get(&2), Some(v), v.key), 2);
+        // pinned values are never replaced
+        assert_eq!(replace(&mut *c, MockValue::new(4)), 2);
+        assert_eq!(replace(&mut *c, MockValue::new(5)), 4);
+        assert_pattern!(c.get(&4), None);
+        assert_eq!(extract!(c.remove(&5), Some(v), v.key), 5);
+        assert_pattern!(c.remove(&5), None);
+        assert_eq!(c.get_load(), 2);
+        assert_pattern!(c.prepare_page(), Ok(None));
+        c.put(&6, MockValue::new(6));
+        assert_eq!(extract!(c.get(&1), Some(v), v.key), 1);
+        assert_eq!(extract!(c.get(&6), Some(v), v.key), 6);
+    }
+}
+
+#[test]
+fn test_all_pinned() {
+    for policy in ["lru", "clock", "lru-k", "2q"].iter() {
+        let mut c : CachePolicyRef<MockValue> = gen_cache_policy(policy, 2);
+        c.put(&1, MockValue::new_pinned(1));
+        c.put(&2, MockValue::new_pinned(2));
+        assert_pattern!(c.prepare_page(), Err(..));
+        assert_eq!(c.get_load(), 2);
+    }
+}
+
+#[test]
+fn test_check_policy() {
+    for policy in POLICY_LIST.iter() {
+        assert_pattern!(check_policy(policy), Ok(()));
+    }
+    let err = extract!(check_policy("fifo"), Err(err), err);
+    assert!(err.contains("lru, clock, lru-k, 2q"), "{}", err);
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_policy() {
+    let _ : CachePolicyRef<MockValue> = gen_cache_policy("fifo", 3);
+}
+
+#[test]
+fn test_clock() {
+    let mut c = ClockCache::new(3);
+    for k in 1..4 {
+        c.put(&k, MockValue::new(k));
+    }
+    assert_victim!(c, 1);  // every value has been referenced
+    c.remove_victim();
+    c.put(&4, MockValue::new(4));
+    c.get(&2);
+    assert_victim!(c, 3);  // 4 and 2 get the second chance
+    c.remove_victim();
+    c.put(&5, MockValue::new(5));
+    assert_victim!(c, 4);
+}
+
+#[test]
+fn test_lru_k() {
+    let mut c = LruKCache::new(3, 2);
+    for k in 1..4 {
+        c.put(&k, MockValue::new(k));
+    }
+    c.get(&1);
+    c.get(&2);
+    assert_victim!(c, 3);  // accessed only once
+    c.remove_victim();
+    c.put(&4, MockValue::new(4));
+    c.get(&4);
+    c.get(&1);
+    assert_victim!(c, 2);  // the second last access of 2 is the oldest
+}
+
+#[test]
+fn test_two_queue() {
+    let mut c = TwoQueueCache::new(4);
+    for k in 1..5 {
+        c.put(&k, MockValue::new(k));
+    }
+    c.get(&1);  // not promoted while in the fifo
+    assert_victim!(c, 1);
+    c.remove_victim();
+    c.put(&1, MockValue::new(1));  // found in the ghost queue
+    assert_victim!(c, 2);
+    c.remove_victim();
+    c.put(&5, MockValue::new(5));
+    assert_victim!(c, 3);
+}
+
+#[test]
+fn test_scan_resistance() {
+    {
+        let mut c = LruKCache::new(3, 2);
+        c.put(&1, MockValue::new(1));
+        c.get(&1);
+        c.put(&2, MockValue::new(2));
+        c.put(&3, MockValue::new(3));
+        for k in 10..30 {
+            assert!(replace(&mut c, MockValue::new(k)) != 1);
+        }
+        assert_pattern!(c.get(&1), Some(..));
+    }
+    {
+        let mut c = TwoQueueCache::new(4);
+        for k in 1..5 {
+            c.put(&k, MockValue::new(k));
+        }
+        assert_eq!(replace(&mut c, MockValue::new(5)), 1);
+        assert_eq!(replace(&mut c, MockValue::new(1)), 2);  // 1 enters the main queue
+        for k in 10..30 {
+            assert!(replace(&mut c, MockValue::new(k)) != 1);
+        }
+        assert_pattern!(c.get(&1), Some(..));
+    }
+    {
+        // lru is flushed by the scan
+        let mut c = LruCache::new(3);
+        c.put(&1, MockValue::new(1));
+        c.get(&1);
+        c.put(&2, MockValue::new(2));
+        c.put(&3, MockValue::new(3));
+        for k in 10..13 {
+            replace(&mut c, MockValue::new(k));
+        }
+        assert_pattern!(c.get(&1), None);
+    }
+}
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct CollidedKey(u64);
+
+impl Hash for CollidedKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        0.hash(state);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CollidedValue(u64);
+
+impl CacheValue for CollidedValue {
+    type KeyType = CollidedKey;
+    fn is_pinned(&self) -> bool { false }
+}
+
+#[test]
+fn test_hash_collision() {
+    let mut c = LruCache::new(2);
+    c.put(&CollidedKey(1), CollidedValue(1));
+    c.put(&CollidedKey(2), CollidedValue(2));
+    assert_eq!(extract!(c.get(&CollidedKey(1)), Some(v), v.0), 1);
+    assert_eq!(extract!(c.get(&CollidedKey(2)), Some(v), v.0), 2);
+    assert_pattern!(c.get(&CollidedKey(3)), None);
+    assert_eq!(extract!(c.remove(&CollidedKey(1)), Some(v), v.0), 1);
+    assert_eq!(extract!(c.get(&CollidedKey(2)), Some(v), v.0), 2);
+}
@@ -1,143 +1,301 @@
-use std::collections::HashMap;
-use rustc_serialize::json::{encode, decode};
-use ::store::table::{Table, Attr, AttrType, TableManager};
-use ::test::utils::remove_blanks;
-use ::utils::config::Config;
-
-
-#[test]
-fn test_attr_type() {
-    {
-        let json = extract!(encode(&AttrType::Int), Ok(s), s);
-        assert_eq!(json, "{\"type\":\"Int\"}");
-        let attr = extract!(decode::<AttrType>(&json), Ok(attr), attr);
-        assert_pattern!(attr, AttrType::Int);
-    }
-    {
-        let json = extract!(encode(&AttrType::Float), Ok(s), s);
-        assert_eq!(json, "{\"type\":\"Float\"}");
-        let attr = extract!(decode::<AttrType>(&json), Ok(attr), attr);
-        assert_pattern!(attr, AttrType::Float);
-    }
-    {
-        let json = extract!(encode(&AttrType::Char{len:233}), Ok(s), s);
-        assert_eq!(json, "{\"len\":\"233\",\"type\":\"Char\"}");
-        let attr = extract!(decode::<AttrType>(&json), Ok(attr), attr);
-        assert_pattern!(attr, AttrType::Char{len:233});
-    }
-}
-
-const JSON_DATA : &'static str = r#"
-    {
-        "author": {
-            "name": "author",
-            "attr_list": [
-                {
-                    "name": "id",
-                    "attr_type": { "type": "Int" },
-                    "primary": true,
-                    "nullable": false
-                },
-                {
-                    "name": "name",
-                    "attr_type": { "len": "10", "type": "Char" },
-                    "primary": false,
-                    "nullable": false
-                }
-            ]
-        },
-        "book": {
-            "name": "book",
-            "attr_list": [
-                {
-                    "name": "id",
-                    "attr_type": { "type": "Int" },
-                    "primary": true,
-                    "nullable": false
-                },
-                {
-                    "name": "author_id",
-                    "attr_type": { "type": "Int" },
-                    "primary": true,
-                    "nullable": true
-                }
-            ]
-        }
-    }
-    "#;
-
-#[test]
-fn test_json_translate() {
-    let t1 = Table{
-        name : "author".to_string(),
-        attr_list : vec![
-            Attr{
-                name : "id".to_string(),
-                attr_type : AttrType::Int,
-                primary : true,
-                nullable : false,
-            },
-            Attr{
-                name : "name".to_string(),
-                attr_type : AttrType::Char{ len : 10 },
-                primary : false,
-                nullable : false,
-            }
-        ],
-    };
-    let t2 = Table{
-        name : "book".to_string(),
-        attr_list : vec![
-            Attr{
-                name : "id".to_string(),
-                attr_type : AttrType::Int,
-                primary : true,
-                nullable : false,
-            },
-            Attr{
-                name : "author_id".to_string(),
-                attr_type : AttrType::Int,
-                primary : true,
-                nullable : true,
-            }
-        ]
-    };
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 5
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let mut manager = TableManager::new(&config);
-    manager.add_table(t1);
-    manager.add_table(t2);
-    assert_eq!(manager.to_json(), remove_blanks(JSON_DATA));
-
-    let gen_manager = TableManager::from_json(&config, &JSON_DATA.to_string(), false);
-    assert_eq!(gen_manager.to_json(), remove_blanks(JSON_DATA));
-}
-
-#[test]
-fn test_get_table() {
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 5
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let manager = TableManager::from_json(&config, &JSON_DATA.to_string(), false);
-    let table = extract!(manager.get_table("book"), Some(table), table);
-    let table = table.borrow();
-    assert_eq!(table.name, "book");
-    assert_eq!(table.attr_list.len(), 2);
-}
-
-#[test]
-fn test_gen_table_set() {
-    let config = Config::new(&r#"
-        max_memory_pool_page_num = 5
-        table_meta_dir = "test_file/table_meta/"
-        table_file_dir = "test_file/table_file""#.to_string());
-    let manager = TableManager::from_json(&config, &JSON_DATA.to_string(), false);
-    let mut used_table = Vec::new();
-    used_table.push("author".to_string());
-    used_table.push("book".to_string());
-    let set = manager.gen_table_set(&used_table);
-    assert_eq!(set.tables.len(), 2);
-}
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use rustc_serialize::json::{encode, decode};
+use ::store::table::{Table, Attr, AttrType, TableManager, Index};
+use ::test::utils::remove_blanks;
+use ::utils::config::Config;
+use ::test::fixture::{clean_test_dirs, gen_test_config};
+
+
+#[test]
+fn test_attr_type() {
+    {
+        let json = extract!(encode(&AttrType::Int), Ok(s), s);
+        assert_eq!(json, "{\"type\":\"Int\"}");
+        let attr = extract!(decode::<AttrType>(&json), Ok(attr), attr);
+        assert_pattern!(attr, AttrType::Int);
+    }
+    {
+        let json = extract!(encode(&AttrType::Float), Ok(s), s);
+        assert_eq!(json, "{\"type\":\"Float\"}");
+        let attr = extract!(decode::<AttrType>(&json), Ok(attr), attr);
+        assert_pattern!(attr, AttrType::Float);
+    }
+    {
+        let json = extract!(encode(&AttrType::Char{len:233}), Ok(s), s);
+        assert_eq!(json, "{\"len\":\"233\",\"type\":\"Char\"}");
+        let attr = extract!(decode::<AttrType>(&json), Ok(attr), attr);
+        assert_pattern!(attr, AttrType::Char{len:233});
+    }
+    assert!(decode::<AttrType>("{}").is_err());
+    assert!(decode::<AttrType>("{\"type\":\"Char\"}").is_err());
+}
+
+const JSON_DATA : &'static str = r#"
+    {
+        "author": {
+            "name": "author",
+            "attr_list": [
+                {
+                    "name": "id",
+                    "attr_type": { "type": "Int" },
+                    "primary": true,
+                    "nullable": false
+                },
+                {
+                    "name": "name",
+                    "attr_type": { "len": "10", "type": "Char" },
+                    "primary": false,
+                    "nullable": false
+                }
+            ],
+            "index_list": [],
+            "file": "author"
+        },
+        "book": {
+            "name": "book",
+            "attr_list": [
+                {
+                    "name": "id",
+                    "attr_type": { "type": "Int" },
+                    "primary": true,
+                    "nullable": false
+                },
+                {
+                    "name": "author_id",
+                    "attr_type": { "type": "Int" },
+                    "primary": true,
+                    "nullable": true
+                }
+            ],
+            "index_list": [
+                {
+                    "name": "author_index",
+                    "attr": "author_id",
+                    "unique": false
+                }
+            ],
+            "file": "book"
+        }
+    }
+    "#;
+
+const LEGACY_JSON_DATA : &'static str = r#"
+    {
+        "author": {
+            "name": "author",
+            "attr_list": [
+                {
+                    "name": "id",
+                    "attr_type": { "type": "Int" },
+                    "primary": true,
+                    "nullable": false
+                }
+            ]
+        }
+    }
+    "#;
+
+#[test]
+fn test_json_translate() {
+    let t1 = Table{
+        name : "author".to_string(),
+        file : "author".to_string(),
+        attr_list : vec![
+            Attr{
+                name : "id".to_string(),
+                attr_type : AttrType::Int,
+                primary : true,
+                nullable : false,
+            },
+            Attr{
+                name : "name".to_string(),
+                attr_type : AttrType::Char{ len : 10 },
+                primary : false,
+                nullable : false,
+            }
+        ],
+        index_list : vec![],
+    };
+    let t2 = Table{
+        name : "book".to_string(),
+        file : "book".to_string(),
+        attr_list : vec![
+            Attr{
+                name : "id".to_string(),
+                attr_type : AttrType::Int,
+                primary : true,
+                nullable : false,
+            },
+            Attr{
+                name : "author_id".to_string(),
+                attr_type : AttrType::Int,
+                primary : true,
+                nullable : true,
+            }
+        ],
+        index_list : vec![
+            Index{
+                name : "author_index".to_string(),
+                attr : "author_id".to_string(),
+                unique : false,
+            }
+        ],
+    };
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 5
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let mut manager = TableManager::new(&config);
+    manager.add_table(t1);
+    manager.add_table(t2);
+    assert_eq!(manager.to_json(), remove_blanks(JSON_DATA));
+
+    let gen_manager = TableManager::from_json(&config, &JSON_DATA.to_string(), false);
+    assert_eq!(gen_manager.to_json(), remove_blanks(JSON_DATA));
+}
+
+#[test]
+fn test_get_table() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 5
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let manager = TableManager::from_json(&config, &JSON_DATA.to_string(), false);
+    let table = extract!(manager.get_table("book"), Some(table), table);
+    let table = table.read().unwrap();
+    assert_eq!(table.name, "book");
+    assert_eq!(table.attr_list.len(), 2);
+    assert_eq!(table.index_list.len(), 1);
+    assert_eq!(table.gen_index_list().len(), 2);
+}
+
+#[test]
+fn test_legacy_json() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 5
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let manager = TableManager::from_json(&config, &LEGACY_JSON_DATA.to_string(), false);
+    let table = extract!(manager.get_table("author"), Some(table), table);
+    assert!(table.read().unwrap().index_list.is_empty());
+    assert_eq!(table.read().unwrap().file, "author");
+}
+
+#[test]
+fn test_gen_table_set() {
+    let config = Config::new(&r#"
+        max_memory_pool_page_num = 5
+        table_meta_dir = "test_file/table_meta/"
+        table_file_dir = "test_file/table_file""#.to_string());
+    let manager = TableManager::from_json(&config, &JSON_DATA.to_string(), false);
+    let mut used_table = Vec::new();
+    used_table.push("author".to_string());
+    used_table.push("book".to_string());
+    let set = manager.gen_table_set(&used_table);
+    assert_eq!(set.tables.len(), 2);
+}
+
+fn overwrite_file(path : &str, content : &str) {
+    let mut file = OpenOptions::new().write(true).truncate(true).open(path).unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+}
+
+fn read_file(path : &str) -> String {
+    let mut content = String::new();
+    OpenOptions::new().read(true).open(path).unwrap().read_to_string(&mut content).unwrap();
+    content
+}
+
+#[test]
+fn test_catalog_file() {
+    let config = gen_test_config("test_catalog_file", 5);
+    let meta_dir = config.get_str("table_meta_dir");
+    let file_dir = config.get_str("table_file_dir");
+    {
+        let mut manager = TableManager::from_json_file(&config).unwrap();
+        assert_eq!(manager.catalog_version, 0);
+        let tables = TableManager::from_json(&config, &JSON_DATA.to_string(), false);
+        manager.add_table(tables.get_table("author").unwrap().read().unwrap().clone());
+        manager.save_to_file();
+        assert_eq!(manager.catalog_version, 1);
+        manager.add_table(tables.get_table("book").unwrap().read().unwrap().clone());
+        manager.save_to_file();
+        assert_eq!(manager.catalog_version, 2);
+    }
+    {
+        let manager = TableManager::from_json_file(&config).unwrap();
+        assert_eq!(manager.catalog_version, 2);
+        assert!(manager.get_table("book").is_some());
+    }
+    // a broken catalog falls back to the backup, which should match the table files
+    let meta_path = format!("{}table_meta.json", meta_dir);
+    let backup_path = format!("{}table_meta.json.bak", meta_dir);
+    let broken = "{\"version\":2,\"tab";
+    let catalog = read_file(&meta_path);
+    overwrite_file(&meta_path, broken);
+    let err = extract!(TableManager::from_json_file(&config), Err(err), err);
+    assert!(err.contains(&format!("{}book.table is not in the backup", file_dir)), "{}", err);
+    overwrite_file(&meta_path, &catalog);
+    {
+        let mut manager = TableManager::from_json_file(&config).unwrap();
+        assert_pattern!(manager.catalog_warning, None);
+        manager.rename_table(&"book".to_string(), &"novel".to_string());  // the files keep their names
+        manager.save_to_file();
+        assert_eq!(manager.catalog_version, 3);
+    }
+    let catalog = read_file(&meta_path);
+    overwrite_file(&meta_path, broken);
+    {
+        let manager = TableManager::from_json_file(&config).unwrap();
+        assert_eq!(manager.catalog_version, 2);
+        assert!(manager.get_table("book").is_some());
+        assert!(manager.get_table("novel").is_none());
+        let warning = manager.catalog_warning.clone().unwrap();
+        assert!(warning.ends_with(&format!("using the backup {} of version 2", backup_path)), "{}", warning);
+    }
+    overwrite_file(&meta_path, &catalog);
+
+    // the files removed by drop table are still referred to by the backup
+    TableManager::from_json_file(&config).unwrap().remove_table(&"novel".to_string());
+    let catalog = read_file(&meta_path);
+    overwrite_file(&meta_path, broken);
+    let err = extract!(TableManager::from_json_file(&config), Err(err), err);
+    assert!(err.contains(&format!("{}book.table referred to by the backup is missing", file_dir)), "{}", err);
+    overwrite_file(&meta_path, &catalog);
+
+    // so are the files replaced by alter table
+    {
+        let mut manager = TableManager::from_json_file(&config).unwrap();
+        assert_eq!(manager.catalog_version, 4);
+        let mut table = manager.get_table("author").unwrap().read().unwrap().clone();
+        table.attr_list.push(Attr{
+            name : "age".to_string(),
+            attr_type : AttrType::Int,
+            primary : false,
+            nullable : true,
+        });
+        manager.alter_table(&"author".to_string(), table, |_| Vec::new()).unwrap();
+        manager.save_to_file();
+    }
+    overwrite_file(&meta_path, broken);
+    let err = extract!(TableManager::from_json_file(&config), Err(err), err);
+    assert!(err.contains(&format!("{}author.table referred to by the backup is missing", file_dir)), "{}", err);
+    overwrite_file(&backup_path, "{\"version\":1");
+    let err = extract!(TableManager::from_json_file(&config), Err(err), err);
+    assert!(err.starts_with(&format!("invalid {}", meta_path)));
+    assert!(err.contains(&format!("invalid backup {}", backup_path)));
+}
+
+#[test]
+fn test_open_invalid_database() {
+    let (meta_dir, file_dir) = clean_test_dirs("test_open_invalid_database");
+    let config = Config::new(&format!(r#"
+        max_memory_pool_page_num = 4
+        page_size = 1000
+        table_meta_dir = "{}"
+        table_file_dir = "{}""#, meta_dir, file_dir));
+    let err = extract!(TableManager::from_json_file(&config), Err(err), err);
+    assert!(err.contains("invalid page_size in config"), "{}", err);
+}
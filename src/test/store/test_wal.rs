@@ -0,0 +1,176 @@
+use std::sync::{Arc, RwLock};
+use std::ptr::read;
+use std::fs::OpenOptions;
+use std::io::Write;
+use ::utils::file::path_join;
+use ::utils::endian::read_u32_le;
+use ::utils::checksum::crc32;
+use ::store::wal::{LogRecord, WalManager, find_losers};
+use ::store::durability::Durability;
+use ::store::table::{TableManager, TableManagerRef};
+use ::parser::common::{ValueExpr, ValueType};
+use ::test::exec::test_query;
+use ::test::fixture::{clean_test_dirs, gen_test_config};
+
+
+#[test]
+fn test_log_record() {
+    let record = LogRecord::PageWrite{
+        txn : 233,
+        table : "test_wal_message".to_string(),
+        page_index : 6,
+        offset : 66,
+        before : vec![1, 2, 3],
+        after : vec![4, 5, 6],
+    };
+    let data = record.encode();
+    assert_eq!(read_u32_le(&data, 0) as usize, data.len() - 8);
+    assert_eq!(read_u32_le(&data, 4), crc32(&data[8..]));
+    let decoded = LogRecord::decode(&data[8..]).unwrap();
+    let (txn, table, page_index, offset, before, after) = extract!(decoded,
+        LogRecord::PageWrite{txn, table, page_index, offset, before, after},
+        (txn, table, page_index, offset, before, after));
+    assert_eq!(txn, 233);
+    assert_eq!(table, "test_wal_message");
+    assert_eq!(page_index, 6);
+    assert_eq!(offset, 66);
+    assert_eq!(before, vec![1, 2, 3]);
+    assert_eq!(after, vec![4, 5, 6]);
+
+    let data = LogRecord::Commit{ txn : 7 }.encode();
+    assert_pattern!(LogRecord::decode(&data[8..]), Some(LogRecord::Commit{ txn : 7 }));
+}
+
+#[test]
+fn test_read_records() {
+    let (_, dir) = clean_test_dirs("test_read_records");
+    ::utils::file::ensure_dir_exist(&dir);
+    {
+        let mut wal = WalManager::new(&dir, Durability::PerStatement);
+        wal.begin();
+        wal.commit();
+        wal.begin();
+    }
+    {
+        // torn record at the tail should be ignored
+        let full_path = path_join(&dir, &"blastoise.wal".to_string());
+        let mut file = OpenOptions::new().append(true).open(&full_path).unwrap();
+        let data = LogRecord::Commit{ txn : 2 }.encode();
+        is_match!(file.write_all(&data[..data.len() - 1]), Ok(..));
+    }
+    let mut wal = WalManager::new(&dir, Durability::PerStatement);
+    let records = wal.read_records();
+    assert_eq!(records.len(), 3);
+    let losers = find_losers(&records);
+    assert_eq!(losers.len(), 1);
+    assert!(losers.contains(&2));
+    wal.checkpoint();
+    assert_eq!(wal.read_records().len(), 0);
+}
+
+fn insert_id(id : i32, table_name : &String, manager : &TableManagerRef) {
+    let value_list = vec![
+        ValueExpr{ value : id.to_string(), value_type : ValueType::Integer },
+        ValueExpr{ value : "1.0".to_string(), value_type : ValueType::Float },
+        ValueExpr{ value : "wal".to_string(), value_type : ValueType::String },
+    ];
+    manager.write().unwrap().insert(table_name, &value_list).unwrap();
+}
+
+fn collect_id(table_name : &String, manager : &TableManagerRef) -> Vec<i32> {
+    let mut query = gen_plan_helper!(
+        &format!("select * from {}", table_name), manager);
+    query.open();
+    let mut id_list = Vec::new();
+    while let Some(tuple_data) = query.get_next() {
+        id_list.push(unsafe{ read::<i32>(tuple_data[0] as *const i32) });
+    }
+    query.close();
+    id_list
+}
+
+fn write_back_pages(table_name : &String, manager : &TableManagerRef) {
+    // pages written back by page switch, header is not saved
+    manager.write().unwrap().file_manager.wal.sync();
+    let file = manager.write().unwrap().file_manager.get_file(table_name);
+    let index_list : Vec<_> = file.read().unwrap().loaded_pages.keys().cloned().collect();
+    for i in index_list.iter() {
+        file.write().unwrap().save_page(*i);
+    }
+}
+
+#[test]
+fn test_recovery() {
+    let config = gen_test_config("test_recovery", 2);
+    let table_name = "test_recovery_message".to_string();
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        manager.write().unwrap().begin_statement();
+        insert_id(1, &table_name, &manager);
+        manager.write().unwrap().commit_statement();
+        manager.write().unwrap().save_to_file();
+
+        // committed but pages not saved
+        manager.write().unwrap().begin_statement();
+        insert_id(2, &table_name, &manager);
+        manager.write().unwrap().commit_statement();
+
+        // not committed but pages written back to the file
+        manager.write().unwrap().begin_statement();
+        insert_id(3, &table_name, &manager);
+        write_back_pages(&table_name, &manager);
+        // killed here
+    }
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_eq!(collect_id(&table_name, &manager), vec![1, 2]);
+    let file = manager.write().unwrap().file_manager.get_file(&table_name);
+    assert_eq!(file.read().unwrap().page_sum, 1);
+}
+
+#[test]
+fn test_recovery_uncommitted_new_page() {
+    let config = gen_test_config("test_recovery_uncommitted_new_page", 2);
+    let table_name = "test_recovery_new_page_message".to_string();
+    {
+        let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+        manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+        manager.write().unwrap().save_to_file();
+        manager.write().unwrap().begin_statement();
+        insert_id(1, &table_name, &manager);
+        write_back_pages(&table_name, &manager);
+    }
+    let manager = Arc::new(RwLock::new(TableManager::from_json_file(&config).unwrap()));
+    assert_eq!(collect_id(&table_name, &manager), vec![]);
+    let file = manager.write().unwrap().file_manager.get_file(&table_name);
+    assert_eq!(file.read().unwrap().page_sum, 0);
+}
+
+#[test]
+fn test_abort_statement() {
+    let config = gen_test_config("test_abort_statement", 2);
+    let table_name = "test_abort_message".to_string();
+    let manager = Arc::new(RwLock::new(TableManager::new(&config)));
+    manager.write().unwrap().add_table(test_query::gen_test_table(&table_name));
+    manager.write().unwrap().begin_statement();
+    insert_id(1, &table_name, &manager);
+    manager.write().unwrap().commit_statement();
+
+    manager.write().unwrap().begin_statement();
+    insert_id(2, &table_name, &manager);
+    let mut delete = gen_plan_helper!(
+        &format!("delete from {} where id = 1", table_name), &manager);
+    delete.open();
+    while let Some(..) = delete.get_next() {}
+    delete.close();
+    assert_eq!(collect_id(&table_name, &manager), vec![2]);
+    manager.write().unwrap().abort_statement();
+    assert_eq!(collect_id(&table_name, &manager), vec![1]);
+
+    // a new page created by the aborted statement can still be used
+    manager.write().unwrap().save_to_file();
+    manager.write().unwrap().begin_statement();
+    insert_id(3, &table_name, &manager);
+    manager.write().unwrap().commit_statement();
+    assert_eq!(collect_id(&table_name, &manager), vec![1, 3]);
+}
@@ -0,0 +1,28 @@
+// CRC-32 (IEEE), computed half a byte at a time to keep the table small
+const CRC_TABLE : [u32; 16] = [
+    0x00000000, 0x1db71064, 0x3b6e20c8, 0x26d930ac, 0x76dc4190, 0x6b6b51f4, 0x4db26158, 0x5005713c,
+    0xedb88320, 0xf00f9344, 0xd6d6a3e8, 0xcb61b38c, 0x9b64c2b0, 0x86d3d2d4, 0xa00ae278, 0xbdbdf21c,
+];
+
+pub fn crc32_update(crc : u32, data : &[u8]) -> u32 {
+    // start with crc32_init, and call crc32_finish after the last update
+    let mut crc = crc;
+    for b in data.iter() {
+        crc ^= *b as u32;
+        crc = (crc >> 4) ^ CRC_TABLE[(crc & 0xf) as usize];
+        crc = (crc >> 4) ^ CRC_TABLE[(crc & 0xf) as usize];
+    }
+    crc
+}
+
+pub fn crc32_init() -> u32 {
+    0xffffffff
+}
+
+pub fn crc32_finish(crc : u32) -> u32 {
+    !crc
+}
+
+pub fn crc32(data : &[u8]) -> u32 {
+    crc32_finish(crc32_update(crc32_init(), data))
+}
@@ -0,0 +1,103 @@
+// dates are saved as the days since 1970-01-01,
+// times and timestamps as the microseconds since the midnight and 1970-01-01 00:00:00
+
+pub const MICROS_PER_DAY : i64 = 24 * 60 * 60 * 1000000;
+
+fn parse_digits(s : &str, len : usize) -> Option<i64> {
+    if s.len() != len || !s.chars().all(|c| c.is_digit(10)) {
+        return None;
+    }
+    s.parse::<i64>().ok()
+}
+
+fn days_from_civil(y : i64, m : i64, d : i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = (if y >= 0 { y } else { y - 399 }) / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(z : i64) -> (i64, i64, i64) {
+    let z = z + 719468;
+    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + if m <= 2 { 1 } else { 0 }, m, d)
+}
+
+fn is_leap_year(y : i64) -> bool {
+    (y % 4 == 0 && y % 100 != 0) || y % 400 == 0
+}
+
+pub fn parse_date(s : &str) -> Option<i32> {
+    // YYYY-MM-DD
+    let parts : Vec<&str> = s.split('-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let (y, m, d) = match (parse_digits(parts[0], 4), parse_digits(parts[1], 2), parse_digits(parts[2], 2)) {
+        (Some(y), Some(m), Some(d)) => (y, m, d),
+        _ => return None,
+    };
+    let month_days = [31, if is_leap_year(y) { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    if y == 0 || m < 1 || m > 12 || d < 1 || d > month_days[(m - 1) as usize] {
+        return None;
+    }
+    Some(days_from_civil(y, m, d) as i32)
+}
+
+pub fn parse_time(s : &str) -> Option<i64> {
+    // HH:MM:SS with at most 6 fractional digits
+    let (hms, frac) = match s.find('.') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, "000000"),
+    };
+    if frac.is_empty() || frac.len() > 6 {
+        return None;
+    }
+    let parts : Vec<&str> = hms.split(':').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let micros = format!("{:0<6}", frac);
+    match (parse_digits(parts[0], 2), parse_digits(parts[1], 2), parse_digits(parts[2], 2),
+            parse_digits(&micros, 6)) {
+        (Some(h), Some(m), Some(s), Some(us)) if h < 24 && m < 60 && s < 60 =>
+            Some(((h * 60 + m) * 60 + s) * 1000000 + us),
+        _ => None,
+    }
+}
+
+pub fn parse_timestamp(s : &str) -> Option<i64> {
+    // YYYY-MM-DD HH:MM:SS[.ffffff], the time can be omitted
+    let (date, time) = match s.find(|c| c == ' ' || c == 'T') {
+        Some(i) => (&s[..i], parse_time(&s[i + 1..])),
+        None => (s, Some(0)),
+    };
+    match (parse_date(date), time) {
+        (Some(days), Some(micros)) => (days as i64).checked_mul(MICROS_PER_DAY).and_then(|n| n.checked_add(micros)),
+        _ => None,
+    }
+}
+
+pub fn format_date(days : i32) -> String {
+    let (y, m, d) = civil_from_days(days as i64);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+pub fn format_time(micros : i64) -> String {
+    let (secs, us) = (micros / 1000000, micros % 1000000);
+    let hms = format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+    if us == 0 { hms } else { format!("{}.{:06}", hms, us) }
+}
+
+pub fn format_timestamp(micros : i64) -> String {
+    let days = if micros >= 0 { micros / MICROS_PER_DAY } else { (micros + 1) / MICROS_PER_DAY - 1 };
+    format!("{} {}", format_date(days as i32), format_time(micros - days * MICROS_PER_DAY))
+}
@@ -0,0 +1,55 @@
+// fixed-point numbers are saved as the value multiplied by 10^scale in an i64
+
+pub const MAX_DECIMAL_PRECISION : usize = 18;
+
+pub fn parse_decimal(s : &str, scale : usize) -> Option<i64> {
+    // the extra fractional digits are rounded half away from zero,
+    // return None if the string is not a number or the result overflows
+    let (negative, s) = if s.starts_with('-') { (true, &s[1..]) } else { (false, s) };
+    let (int_part, frac_part) = match s.find('.') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    if !int_part.chars().chain(frac_part.chars()).all(|c| c.is_digit(10)) {
+        return None;
+    }
+    let mut n : i64 = 0;
+    let frac_digits = frac_part.chars().chain((0..).map(|_| '0')).take(scale);
+    for c in int_part.chars().chain(frac_digits) {
+        n = match n.checked_mul(10).and_then(|n| n.checked_add(c.to_digit(10).unwrap() as i64)) {
+            Some(n) => n,
+            None => return None,
+        };
+    }
+    if frac_part.chars().nth(scale).map_or(false, |c| c >= '5') {
+        n = match n.checked_add(1) {
+            Some(n) => n,
+            None => return None,
+        };
+    }
+    Some(if negative { -n } else { n })
+}
+
+pub fn format_decimal(n : i64, scale : usize) -> String {
+    let digits = (n as i128).abs().to_string();
+    let digits = if digits.len() <= scale {
+        format!("{}{}", "0".repeat(scale + 1 - digits.len()), digits)
+    } else {
+        digits
+    };
+    let sign = if n < 0 { "-" } else { "" };
+    if scale == 0 {
+        format!("{}{}", sign, digits)
+    } else {
+        let point = digits.len() - scale;
+        format!("{}{}.{}", sign, &digits[..point], &digits[point..])
+    }
+}
+
+pub fn decimal_fits(n : i64, precision : usize) -> bool {
+    // whether the number has at most `precision` digits
+    precision >= 19 || (n as i128).abs() < 10i128.pow(precision as u32)
+}
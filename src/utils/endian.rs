@@ -0,0 +1,62 @@
+use std::vec::Vec;
+
+
+pub fn push_u16_le(buf : &mut Vec<u8>, n : u16) {
+    buf.push(n as u8);
+    buf.push((n >> 8) as u8);
+}
+
+pub fn push_u32_le(buf : &mut Vec<u8>, n : u32) {
+    for i in 0..4 {
+        buf.push((n >> (8 * i)) as u8);
+    }
+}
+
+pub fn push_u64_le(buf : &mut Vec<u8>, n : u64) {
+    for i in 0..8 {
+        buf.push((n >> (8 * i)) as u8);
+    }
+}
+
+pub fn read_u16_le(buf : &[u8], pos : usize) -> u16 {
+    (buf[pos] as u16) | ((buf[pos + 1] as u16) << 8)
+}
+
+pub fn read_u32_le(buf : &[u8], pos : usize) -> u32 {
+    let mut n = 0;
+    for i in 0..4 {
+        n |= (buf[pos + i] as u32) << (8 * i);
+    }
+    n
+}
+
+pub fn read_u64_le(buf : &[u8], pos : usize) -> u64 {
+    let mut n = 0;
+    for i in 0..8 {
+        n |= (buf[pos + i] as u64) << (8 * i);
+    }
+    n
+}
+
+pub fn push_u32_be(buf : &mut Vec<u8>, n : u32) {
+    for i in (0..4).rev() {
+        buf.push((n >> (8 * i)) as u8);
+    }
+}
+
+pub fn push_u64_be(buf : &mut Vec<u8>, n : u64) {
+    for i in (0..8).rev() {
+        buf.push((n >> (8 * i)) as u8);
+    }
+}
+
+pub fn write_u16_le(buf : &mut [u8], pos : usize, n : u16) {
+    buf[pos] = n as u8;
+    buf[pos + 1] = (n >> 8) as u8;
+}
+
+pub fn write_u32_le(buf : &mut [u8], pos : usize, n : u32) {
+    for i in 0..4 {
+        buf[pos + i] = (n >> (8 * i)) as u8;
+    }
+}
@@ -1,12 +1,20 @@
-#[macro_use]
-pub mod macro_utils;
-#[allow(dead_code)]
-pub mod libwrapper;
-#[allow(dead_code)]
-pub mod pointer;
-#[allow(dead_code)]
-pub mod config;
-#[allow(dead_code)]
-pub mod file;
-#[allow(dead_code)]
-pub mod array;
+#[macro_use]
+pub mod macro_utils;
+#[allow(dead_code)]
+pub mod libwrapper;
+#[allow(dead_code)]
+pub mod pointer;
+#[allow(dead_code)]
+pub mod config;
+#[allow(dead_code)]
+pub mod file;
+#[allow(dead_code)]
+pub mod array;
+#[allow(dead_code)]
+pub mod endian;
+#[allow(dead_code)]
+pub mod checksum;
+#[allow(dead_code)]
+pub mod decimal;
+#[allow(dead_code)]
+pub mod datetime;
@@ -1,47 +1,94 @@
-use std::ptr::{write, read, write_bytes};
-use std::vec::Vec;
-use std::ffi::CString;
-use ::store::buffer::DataPtr;
-
-
-pub fn char_to_u8(c : char) -> u8 {
-    if (c as u32) > 127 {
-        return 42;  // invalid ascii to '*'
-    }
-    c as u8
-}
-
-pub unsafe fn write_string(ptr : DataPtr, input : &String, len : usize) {
-    assert!(input.len() <= len);
-    write_bytes(ptr, 0, len);
-    for (i, c) in input.chars().enumerate() {
-        let byte = char_to_u8(c);
-        write::<u8>((ptr as *mut u8).offset(i as isize), byte);
-    }
-}
-
-pub unsafe fn read_string(ptr : DataPtr, len : usize) -> String {
-    let mut s = String::new();
-    for i in 0..len {
-        let n = read::<u8>((ptr as *const u8).offset(i as isize));
-        if n == 0 {
-            return s;
-        }
-        s.push(n as char);
-    }
-    s
-}
-
-pub fn pointer_offset(ptr : DataPtr, byte_offset : usize) -> DataPtr {
-    unsafe{
-        (ptr as *mut u8).offset(byte_offset as isize) as DataPtr
-    }
-}
-
-pub fn to_cstring(s : String) -> CString {
-    let mut buf = Vec::new();
-    for c in s.chars() {
-        buf.push(char_to_u8(c));
-    }
-    CString::new(buf).unwrap()
-}
+use std::ptr::{write, read, write_bytes, read_unaligned, write_unaligned};
+use std::vec::Vec;
+use std::ffi::CString;
+use ::store::buffer::DataPtr;
+
+
+pub fn char_to_u8(c : char) -> u8 {
+    if (c as u32) > 127 {
+        return 42;  // invalid ascii to '*'
+    }
+    c as u8
+}
+
+pub unsafe fn write_string(ptr : DataPtr, input : &String, len : usize) {
+    assert!(input.len() <= len);
+    write_bytes(ptr, 0, len);
+    for (i, c) in input.chars().enumerate() {
+        let byte = char_to_u8(c);
+        write::<u8>((ptr as *mut u8).offset(i as isize), byte);
+    }
+}
+
+pub unsafe fn read_string(ptr : DataPtr, len : usize) -> String {
+    let mut s = String::new();
+    for i in 0..len {
+        let n = read::<u8>((ptr as *const u8).offset(i as isize));
+        if n == 0 {
+            return s;
+        }
+        s.push(n as char);
+    }
+    s
+}
+
+// a varchar starts with its length (2 bytes, little endian), the bytes follow
+pub unsafe fn read_varchar(ptr : DataPtr) -> String {
+    read_string(pointer_offset(ptr, 2), varchar_len(ptr))
+}
+
+pub unsafe fn write_varchar(ptr : DataPtr, input : &String) {
+    write_unaligned::<u16>(ptr as *mut u16, (input.len() as u16).to_le());
+    write_string(pointer_offset(ptr, 2), input, input.len());
+}
+
+pub unsafe fn varchar_len(ptr : DataPtr) -> usize {
+    u16::from_le(read_unaligned::<u16>(ptr as *const u16)) as usize
+}
+
+pub fn pointer_offset(ptr : DataPtr, byte_offset : usize) -> DataPtr {
+    unsafe{
+        (ptr as *mut u8).offset(byte_offset as isize) as DataPtr
+    }
+}
+
+pub fn to_cstring(s : String) -> CString {
+    let mut buf = Vec::new();
+    for c in s.chars() {
+        buf.push(char_to_u8(c));
+    }
+    CString::new(buf).unwrap()
+}
+
+// numbers in the tuples are saved in little endian whatever the host is
+pub unsafe fn read_int(ptr : DataPtr) -> i32 {
+    i32::from_le(read_unaligned::<i32>(ptr as *const i32))
+}
+
+pub unsafe fn write_int(ptr : DataPtr, n : i32) {
+    write_unaligned::<i32>(ptr as *mut i32, n.to_le())
+}
+
+pub unsafe fn read_float(ptr : DataPtr) -> f32 {
+    f32::from_bits(u32::from_le(read_unaligned::<u32>(ptr as *const u32)))
+}
+
+pub unsafe fn write_float(ptr : DataPtr, n : f32) {
+    write_unaligned::<u32>(ptr as *mut u32, n.to_bits().to_le())
+}
+
+pub unsafe fn read_bigint(ptr : DataPtr) -> i64 {
+    i64::from_le(read_unaligned::<i64>(ptr as *const i64))
+}
+
+pub unsafe fn write_bigint(ptr : DataPtr, n : i64) {
+    write_unaligned::<i64>(ptr as *mut i64, n.to_le())
+}
+
+pub unsafe fn read_double(ptr : DataPtr) -> f64 {
+    f64::from_bits(u64::from_le(read_unaligned::<u64>(ptr as *const u64)))
+}
+
+pub unsafe fn write_double(ptr : DataPtr, n : f64) {
+    write_unaligned::<u64>(ptr as *mut u64, n.to_bits().to_le())
+}